@@ -0,0 +1,105 @@
+//! Reading and writing trees in the supported file formats.
+
+use std::{
+    fs,
+    io::{Read, Write},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use fdt_edit::Fdt;
+
+use crate::{Result, json};
+
+/// `FDT_MAGIC` as it appears at the start of a blob.
+const MAGIC: [u8; 4] = fdt_raw::FDT_MAGIC.to_be_bytes();
+
+/// A device tree file format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Flattened device tree blob
+    Dtb,
+    /// Device tree source
+    Dts,
+    /// JSON, as written by `fdt convert`
+    Json,
+}
+
+impl Format {
+    /// Infers the format from a file extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "dtb" | "dtbo" => Some(Self::Dtb),
+            "dts" | "dtsi" => Some(Self::Dts),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Infers the format from file contents.
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(&MAGIC) {
+            Self::Dtb
+        } else if data.trim_ascii_start().starts_with(b"{") {
+            Self::Json
+        } else {
+            Self::Dts
+        }
+    }
+}
+
+/// Reads `path`, or standard input for `-`.
+pub fn read(path: &str) -> Result<Vec<u8>> {
+    if path == "-" {
+        let mut data = Vec::new();
+        std::io::stdin().read_to_end(&mut data)?;
+        return Ok(data);
+    }
+    fs::read(path).map_err(|err| format!("{path}: {err}").into())
+}
+
+/// Writes `data` to `path`, or standard output for `-`.
+pub fn write(path: &str, data: &[u8]) -> Result<()> {
+    if path == "-" {
+        std::io::stdout().write_all(data)?;
+        return Ok(());
+    }
+    fs::write(path, data).map_err(|err| format!("{path}: {err}").into())
+}
+
+/// Reads a tree in `format`, or in the format its contents look like.
+pub fn load(path: &str, format: Option<Format>) -> Result<Fdt> {
+    load_bytes(&read(path)?, format)
+}
+
+/// Parses `data` in `format`, or in the format it looks like.
+pub fn load_bytes(data: &[u8], format: Option<Format>) -> Result<Fdt> {
+    let fdt = match format.unwrap_or_else(|| Format::detect(data)) {
+        Format::Dtb => Fdt::from_bytes(data)?,
+        Format::Dts => Fdt::from_dts(std::str::from_utf8(data)?)?,
+        Format::Json => json::from_json(&serde_json::from_slice(data)?)?,
+    };
+    Ok(fdt)
+}
+
+/// Reads a blob, compiling source or JSON input to one first.
+pub fn load_blob(path: &str) -> Result<Vec<u8>> {
+    let data = read(path)?;
+    if Format::detect(&data) == Format::Dtb {
+        return Ok(data);
+    }
+    Ok(load_bytes(&data, None)?.encode().to_vec())
+}
+
+/// Serializes `fdt` in `format`.
+pub fn render(fdt: &Fdt, format: Format) -> Result<Vec<u8>> {
+    Ok(match format {
+        Format::Dtb => fdt.encode().to_vec(),
+        Format::Dts => fdt.to_dts().into_bytes(),
+        Format::Json => {
+            let mut text = serde_json::to_string_pretty(&json::to_json(fdt))?;
+            text.push('\n');
+            text.into_bytes()
+        }
+    })
+}
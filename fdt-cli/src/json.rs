@@ -0,0 +1,164 @@
+//! JSON representation of a tree.
+//!
+//! A node is an object with `properties` and `children` maps, both in
+//! blob order. Property values are chosen so that converting back gives
+//! the same bytes:
+//!
+//! - `true` for an empty property
+//! - a string, or an array of strings, for a printable string list
+//! - an array of numbers for 32-bit cells
+//! - `{"bytes": "<hex>"}` for anything else
+
+use fdt_edit::{Fdt, MemoryReservation, Node, NodeId, Property};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Result,
+    value::{self, ValueType},
+};
+
+/// Converts a tree to its JSON form.
+pub fn to_json(fdt: &Fdt) -> Value {
+    let memreserve: Vec<_> = fdt
+        .memory_reservations
+        .iter()
+        .map(|rsv| json!({ "address": rsv.address, "size": rsv.size }))
+        .collect();
+    json!({
+        "boot_cpuid_phys": fdt.boot_cpuid_phys,
+        "memreserve": memreserve,
+        "root": node_to_json(fdt, fdt.root_id()),
+    })
+}
+
+fn node_to_json(fdt: &Fdt, id: NodeId) -> Value {
+    let node = fdt.node(id).expect("node ids come from the tree");
+    let properties: Map<_, _> = node
+        .properties()
+        .iter()
+        .map(|prop| (prop.name.clone(), value_to_json(&prop.data)))
+        .collect();
+    let children: Map<_, _> = node
+        .children()
+        .iter()
+        .map(|&child| {
+            let name = fdt.node(child).map(|n| n.name.clone()).unwrap_or_default();
+            (name, node_to_json(fdt, child))
+        })
+        .collect();
+    json!({ "properties": properties, "children": children })
+}
+
+fn value_to_json(data: &[u8]) -> Value {
+    if data.is_empty() {
+        return Value::Bool(true);
+    }
+    match ValueType::guess(data) {
+        ValueType::Str => {
+            let list = value::strings(data).unwrap_or_default();
+            match list.as_slice() {
+                [single] => json!(single),
+                _ => json!(list),
+            }
+        }
+        ValueType::U32 => data
+            .chunks_exact(4)
+            .map(|cell| json!(u32::from_be_bytes(cell.try_into().unwrap())))
+            .collect(),
+        _ => {
+            let hex: String = data.iter().map(|b| format!("{b:02x}")).collect();
+            json!({ "bytes": hex })
+        }
+    }
+}
+
+/// Builds a tree from its JSON form.
+pub fn from_json(value: &Value) -> Result<Fdt> {
+    let mut fdt = Fdt::new();
+    if let Some(cpu) = value.get("boot_cpuid_phys") {
+        fdt.boot_cpuid_phys = cpu
+            .as_u64()
+            .and_then(|cpu| u32::try_from(cpu).ok())
+            .ok_or("`boot_cpuid_phys` must be a 32-bit number")?;
+    }
+    for rsv in value
+        .get("memreserve")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let field = |name: &str| {
+            rsv.get(name)
+                .and_then(Value::as_u64)
+                .ok_or_else(|| format!("memreserve entry has no numeric `{name}`"))
+        };
+        fdt.memory_reservations.push(MemoryReservation {
+            address: field("address")?,
+            size: field("size")?,
+        });
+    }
+
+    let root = value.get("root").ok_or("missing `root` node")?;
+    let root_id = fdt.root_id();
+    fill_node(&mut fdt, root_id, "/", root)?;
+    fdt.rebuild_phandle_cache();
+    Ok(fdt)
+}
+
+fn fill_node(fdt: &mut Fdt, id: NodeId, path: &str, value: &Value) -> Result<()> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| format!("{path}: node must be an object"))?;
+
+    let properties = object.get("properties").and_then(Value::as_object);
+    for (name, value) in properties.into_iter().flatten() {
+        let data = value_from_json(value).map_err(|err| format!("{path}: {name}: {err}"))?;
+        let node = fdt.node_mut(id).expect("node was just added");
+        node.add_property(Property::new(name, data));
+    }
+
+    let children = object.get("children").and_then(Value::as_object);
+    for (name, value) in children.into_iter().flatten() {
+        let child = fdt.add_node(id, Node::new(name));
+        let child_path = if path == "/" {
+            format!("/{name}")
+        } else {
+            format!("{path}/{name}")
+        };
+        fill_node(fdt, child, &child_path, value)?;
+    }
+    Ok(())
+}
+
+fn value_from_json(value: &Value) -> Result<Vec<u8>> {
+    match value {
+        Value::Bool(true) => Ok(Vec::new()),
+        Value::String(s) => value::parse(std::slice::from_ref(s), ValueType::Str),
+        Value::Array(items) if items.iter().all(Value::is_string) => {
+            let list: Vec<_> = items
+                .iter()
+                .filter_map(|s| s.as_str().map(String::from))
+                .collect();
+            value::parse(&list, ValueType::Str)
+        }
+        Value::Array(items) => {
+            let mut data = Vec::with_capacity(items.len() * 4);
+            for item in items {
+                let cell = item
+                    .as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .ok_or("cells must be 32-bit numbers")?;
+                data.extend_from_slice(&cell.to_be_bytes());
+            }
+            Ok(data)
+        }
+        Value::Object(object) => {
+            let hex = object
+                .get("bytes")
+                .and_then(Value::as_str)
+                .ok_or("object values must have a `bytes` hex string")?;
+            value::parse(&[hex.to_string()], ValueType::Bytes)
+        }
+        _ => Err("unsupported value".into()),
+    }
+}
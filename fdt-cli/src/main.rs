@@ -0,0 +1,274 @@
+//! `fdt`: inspect, edit and convert device tree blobs.
+//!
+//! The subcommands follow dtc's companion tools: `dump` prints a blob as
+//! source like `fdtdump`, `get` and `put` read and write single properties
+//! like `fdtget` and `fdtput`, and `convert` translates between blob,
+//! source and JSON like `dtc -I/-O`.
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use fdt_edit::{Fdt, Node, Property};
+
+mod io;
+mod json;
+mod value;
+
+use io::Format;
+use value::ValueType;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(
+    name = "fdt",
+    version,
+    about = "Inspect, edit and convert device tree blobs"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print a blob as device tree source.
+    Dump {
+        /// Input file, or `-` for standard input
+        file: String,
+    },
+    /// Print the value of a property.
+    Get {
+        /// Input file, or `-` for standard input
+        file: String,
+        /// Node path or alias
+        path: String,
+        /// Property name
+        prop: String,
+        /// How to decode the value (guessed if omitted)
+        #[arg(short, long, value_enum)]
+        r#type: Option<ValueType>,
+    },
+    /// Set a property, creating it if needed.
+    Put {
+        /// File to edit
+        file: String,
+        /// Node path or alias
+        path: String,
+        /// Property name
+        prop: String,
+        /// Values; strings form a string list, integers accept `0x` hex
+        values: Vec<String>,
+        /// How to encode the values
+        #[arg(short, long, value_enum, default_value = "str")]
+        r#type: ValueType,
+        /// Create missing nodes along the path
+        #[arg(short = 'p', long)]
+        create: bool,
+        /// Write the result here instead of editing in place
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Remove a property, or a whole node if no property is given.
+    Rm {
+        /// File to edit
+        file: String,
+        /// Node path or alias
+        path: String,
+        /// Property name
+        prop: Option<String>,
+        /// Write the result here instead of editing in place
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Convert between blob, source and JSON.
+    Convert {
+        /// Input file, or `-` for standard input
+        input: String,
+        /// Output file, or `-` for standard output
+        output: String,
+        /// Input format (detected from the contents if omitted)
+        #[arg(short = 'I', long, value_enum)]
+        from: Option<Format>,
+        /// Output format (taken from the output extension if omitted)
+        #[arg(short = 'O', long, value_enum)]
+        to: Option<Format>,
+    },
+    /// Print header fields, memory reservations and tree statistics.
+    Info {
+        /// Input file, or `-` for standard input
+        file: String,
+    },
+    /// Compare two trees, printing a unified diff of nodes and properties.
+    Diff {
+        /// First file
+        a: String,
+        /// Second file
+        b: String,
+    },
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("fdt: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: Command) -> Result<ExitCode> {
+    match command {
+        Command::Dump { file } => {
+            let data = io::load_blob(&file)?;
+            let text = fdt_raw::Fdt::from_bytes(&data)?.to_string();
+            io::write("-", text.as_bytes())?;
+        }
+        Command::Get {
+            file,
+            path,
+            prop,
+            r#type,
+        } => {
+            let fdt = io::load(&file, None)?;
+            let node = fdt
+                .get_by_path(&path)
+                .ok_or_else(|| format!("node `{path}` not found"))?;
+            let prop = node
+                .as_node()
+                .get_property(&prop)
+                .ok_or_else(|| format!("property `{prop}` not found in `{path}`"))?;
+            let ty = r#type.unwrap_or_else(|| ValueType::guess(&prop.data));
+            println!("{}", value::format(&prop.data, ty)?);
+        }
+        Command::Put {
+            file,
+            path,
+            prop,
+            values,
+            r#type,
+            create,
+            output,
+        } => {
+            let mut fdt = io::load(&file, None)?;
+            if create {
+                create_path(&mut fdt, &path);
+            }
+            let data = value::parse(&values, r#type)?;
+            let id = fdt
+                .get_by_path_mut(&path)
+                .ok_or_else(|| format!("node `{path}` not found"))?
+                .id();
+            let node = fdt.node_mut(id).ok_or("node vanished")?;
+            node.set_property(Property::new(&prop, data));
+            save(&fdt, &file, output.as_deref())?;
+        }
+        Command::Rm {
+            file,
+            path,
+            prop,
+            output,
+        } => {
+            let mut fdt = io::load(&file, None)?;
+            match prop {
+                Some(prop) => {
+                    let id = fdt
+                        .get_by_path_mut(&path)
+                        .ok_or_else(|| format!("node `{path}` not found"))?
+                        .id();
+                    let node = fdt.node_mut(id).ok_or("node vanished")?;
+                    node.remove_property(&prop)
+                        .ok_or_else(|| format!("property `{prop}` not found in `{path}`"))?;
+                }
+                None => {
+                    let id = fdt
+                        .get_by_path_id(&path)
+                        .ok_or_else(|| format!("node `{path}` not found"))?;
+                    fdt.remove_by_path(&fdt.path_of(id))
+                        .ok_or("cannot remove the root node")?;
+                }
+            }
+            save(&fdt, &file, output.as_deref())?;
+        }
+        Command::Convert {
+            input,
+            output,
+            from,
+            to,
+        } => {
+            let fdt = io::load(&input, from)?;
+            let to = to
+                .or_else(|| Format::from_path(&output))
+                .ok_or("cannot tell the output format; pass -O")?;
+            io::write(&output, &io::render(&fdt, to)?)?;
+        }
+        Command::Info { file } => {
+            let data = io::load_blob(&file)?;
+            info(&fdt_raw::Fdt::from_bytes(&data)?);
+        }
+        Command::Diff { a, b } => {
+            let diff = fdt_edit::diff(&io::load(&a, None)?, &io::load(&b, None)?);
+            if !diff.is_empty() {
+                let text = format!("--- {a}\n+++ {b}\n{diff}");
+                io::write("-", text.as_bytes())?;
+                return Ok(ExitCode::from(1));
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Writes `fdt` to `output`, or back to `file` in the format it was read in.
+fn save(fdt: &Fdt, file: &str, output: Option<&str>) -> Result<()> {
+    let target = output.unwrap_or(file);
+    let format = match Format::from_path(target) {
+        Some(format) => format,
+        None if output.is_none() => Format::detect(&io::read(file)?),
+        None => Format::Dtb,
+    };
+    io::write(target, &io::render(fdt, format)?)
+}
+
+/// Adds the nodes of `path` that do not exist yet.
+fn create_path(fdt: &mut Fdt, path: &str) {
+    let mut parent = fdt.root_id();
+    for name in path.split('/').filter(|name| !name.is_empty()) {
+        let existing = fdt.node(parent).and_then(|node| node.get_child(name));
+        parent = match existing {
+            Some(child) => child,
+            None => fdt.add_node(parent, Node::new(name)),
+        };
+    }
+}
+
+fn info(fdt: &fdt_raw::Fdt<'_>) {
+    let header = fdt.header();
+    println!("magic:             {:#x}", header.magic);
+    println!(
+        "totalsize:         {:#x} ({})",
+        header.totalsize, header.totalsize
+    );
+    println!("off_dt_struct:     {:#x}", header.off_dt_struct);
+    println!("off_dt_strings:    {:#x}", header.off_dt_strings);
+    println!("off_mem_rsvmap:    {:#x}", header.off_mem_rsvmap);
+    println!("version:           {}", header.version);
+    println!("last_comp_version: {}", header.last_comp_version);
+    println!("boot_cpuid_phys:   {:#x}", header.boot_cpuid_phys);
+    println!("size_dt_strings:   {:#x}", header.size_dt_strings);
+    println!("size_dt_struct:    {:#x}", header.size_dt_struct);
+
+    let reservations: Vec<_> = fdt.memory_reservations().collect();
+    println!("memory reservations: {}", reservations.len());
+    for rsv in &reservations {
+        println!("  {:#018x} {:#018x}", rsv.address, rsv.size);
+    }
+
+    let (mut nodes, mut props) = (0, 0);
+    for node in fdt.all_nodes() {
+        nodes += 1;
+        props += node.properties().count();
+    }
+    println!("nodes:             {nodes}");
+    println!("properties:        {props}");
+}
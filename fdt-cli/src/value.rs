@@ -0,0 +1,129 @@
+//! Property value formatting and parsing for `get` and `put`.
+
+use std::fmt::Write;
+
+use clap::ValueEnum;
+
+use crate::Result;
+
+/// How a property value is interpreted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ValueType {
+    /// Big-endian 32-bit cells
+    U32,
+    /// Big-endian 64-bit values
+    U64,
+    /// NUL-terminated strings
+    Str,
+    /// Raw bytes
+    Bytes,
+}
+
+impl ValueType {
+    /// Picks the type a value most likely has: a string list if every
+    /// entry is printable, cells if the length is a multiple of four, and
+    /// bytes otherwise.
+    pub fn guess(data: &[u8]) -> Self {
+        if strings(data).is_some() {
+            Self::Str
+        } else if data.len().is_multiple_of(4) {
+            Self::U32
+        } else {
+            Self::Bytes
+        }
+    }
+}
+
+/// Splits `data` into strings if it is a non-empty list of printable,
+/// NUL-terminated strings.
+pub fn strings(data: &[u8]) -> Option<Vec<&str>> {
+    let body = data.strip_suffix(&[0])?;
+    body.split(|&b| b == 0)
+        .map(|s| {
+            let printable = !s.is_empty() && s.iter().all(|&b| (0x20..0x7f).contains(&b));
+            printable.then(|| std::str::from_utf8(s).ok()).flatten()
+        })
+        .collect()
+}
+
+/// Formats `data` as `ty`, with values separated by spaces.
+pub fn format(data: &[u8], ty: ValueType) -> Result<String> {
+    let mut out = String::new();
+    match ty {
+        ValueType::Str => {
+            let list = strings(data).ok_or("value is not a string list")?;
+            out = list.join(" ");
+        }
+        ValueType::U32 => {
+            if !data.len().is_multiple_of(4) {
+                return Err(format!("value of {} bytes is not a list of u32", data.len()).into());
+            }
+            for (i, cell) in data.chunks_exact(4).enumerate() {
+                let value = u32::from_be_bytes(cell.try_into().unwrap());
+                let sep = if i == 0 { "" } else { " " };
+                write!(out, "{sep}{value:#x}")?;
+            }
+        }
+        ValueType::U64 => {
+            if !data.len().is_multiple_of(8) {
+                return Err(format!("value of {} bytes is not a list of u64", data.len()).into());
+            }
+            for (i, cell) in data.chunks_exact(8).enumerate() {
+                let value = u64::from_be_bytes(cell.try_into().unwrap());
+                let sep = if i == 0 { "" } else { " " };
+                write!(out, "{sep}{value:#x}")?;
+            }
+        }
+        ValueType::Bytes => {
+            for (i, byte) in data.iter().enumerate() {
+                let sep = if i == 0 { "" } else { " " };
+                write!(out, "{sep}{byte:02x}")?;
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Parses an integer in decimal or, with a `0x` prefix, hexadecimal.
+pub fn parse_int(s: &str) -> Result<u64> {
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("invalid integer `{s}`").into())
+}
+
+/// Encodes command-line arguments as a property value of type `ty`.
+///
+/// Strings become a NUL-terminated list, and each `bytes` argument is a
+/// run of hex digit pairs such as `0a0b0c`.
+pub fn parse(args: &[String], ty: ValueType) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    for arg in args {
+        match ty {
+            ValueType::Str => {
+                data.extend_from_slice(arg.as_bytes());
+                data.push(0);
+            }
+            ValueType::U32 => {
+                let value = u32::try_from(parse_int(arg)?)
+                    .map_err(|_| format!("`{arg}` does not fit in 32 bits"))?;
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+            ValueType::U64 => data.extend_from_slice(&parse_int(arg)?.to_be_bytes()),
+            ValueType::Bytes => {
+                let digits = arg.strip_prefix("0x").unwrap_or(arg);
+                if !digits.len().is_multiple_of(2) {
+                    return Err(format!("`{arg}` has an odd number of hex digits").into());
+                }
+                for pair in digits.as_bytes().chunks(2) {
+                    let pair = std::str::from_utf8(pair)?;
+                    let byte = u8::from_str_radix(pair, 16)
+                        .map_err(|_| format!("invalid hex byte `{pair}`"))?;
+                    data.push(byte);
+                }
+            }
+        }
+    }
+    Ok(data)
+}
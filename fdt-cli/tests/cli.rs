@@ -0,0 +1,171 @@
+//! End-to-end tests of the `fdt` binary.
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+use dtb_file::*;
+use fdt_raw::Fdt;
+
+/// Runs `fdt` with `args`.
+fn fdt(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fdt"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Runs `fdt` with `args`, feeding `input` to its standard input.
+fn fdt_with_stdin(args: &[&str], input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_fdt"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "fdt failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// Returns the path of a scratch file called `name`.
+fn tmp_path(name: &str) -> String {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("fdt-cli");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join(name).to_str().unwrap().to_string()
+}
+
+/// Writes `data` to a scratch file called `name` and returns its path.
+fn fixture(name: &str, data: &[u8]) -> String {
+    let path = tmp_path(name);
+    std::fs::write(&path, data).unwrap();
+    path
+}
+
+#[test]
+fn test_info() {
+    let raw = fdt_qemu();
+    let file = fixture("info.dtb", &raw);
+    let out = stdout(&fdt(&["info", &file]));
+
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let nodes = fdt.all_nodes().count();
+    assert!(out.contains("magic:             0xd00dfeed"));
+    assert!(out.contains(&format!("totalsize:         {:#x}", fdt.header().totalsize)));
+    assert!(out.contains(&format!("nodes:             {nodes}\n")));
+    assert!(out.contains("memory reservations: 0"));
+}
+
+#[test]
+fn test_dump() {
+    let raw = fdt_rpi_4b();
+    let file = fixture("dump.dtb", &raw);
+    let out = stdout(&fdt(&["dump", &file]));
+    assert_eq!(out, Fdt::from_bytes(&raw).unwrap().to_string());
+}
+
+#[test]
+fn test_source_from_stdin() {
+    let source = b"/dts-v1/;\n/ {\n    model = \"stdin\";\n    soc { };\n};\n";
+    let file = fixture("stdin.dts", source);
+
+    let piped = stdout(&fdt_with_stdin(&["dump", "-"], source));
+    assert_eq!(piped, stdout(&fdt(&["dump", &file])));
+    assert!(piped.contains("model = \"stdin\""));
+
+    let piped = stdout(&fdt_with_stdin(&["info", "-"], source));
+    assert_eq!(piped, stdout(&fdt(&["info", &file])));
+}
+
+#[test]
+fn test_get() {
+    let file = fixture("get.dtb", &fdt_rpi_4b());
+
+    let out = stdout(&fdt(&["get", &file, "/chosen", "bootargs"]));
+    assert!(out.starts_with("coherent_pool=1M"));
+    let out = stdout(&fdt(&["get", &file, "/", "#address-cells"]));
+    assert_eq!(out, "0x2\n");
+    let out = stdout(&fdt(&["get", &file, "/", "#address-cells", "-t", "bytes"]));
+    assert_eq!(out, "00 00 00 02\n");
+
+    let missing = fdt(&["get", &file, "/chosen", "missing"]);
+    assert!(!missing.status.success());
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("not found"));
+}
+
+#[test]
+fn test_put_and_rm() {
+    let file = fixture("edit.dtb", &fdt_qemu());
+
+    stdout(&fdt(&[
+        "put",
+        &file,
+        "/chosen",
+        "bootargs",
+        "console=ttyAMA0",
+    ]));
+    stdout(&fdt(&[
+        "put",
+        &file,
+        "-p",
+        "-t",
+        "u32",
+        "/new/node",
+        "cells",
+        "1",
+        "0x20",
+    ]));
+    stdout(&fdt(&["rm", &file, "/chosen", "kaslr-seed"]));
+    stdout(&fdt(&["rm", &file, "/psci"]));
+    assert!(!fdt(&["rm", &file, "/psci"]).status.success());
+
+    let data = std::fs::read(&file).unwrap();
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    let chosen = fdt.chosen().unwrap();
+    assert_eq!(chosen.bootargs(), Some("console=ttyAMA0"));
+    assert_eq!(chosen.kaslr_seed(), None);
+    assert!(fdt.find_by_path("/psci").is_none());
+    let node = fdt.find_by_path("/new/node").unwrap();
+    let cells: Vec<u32> = node.find_property("cells").unwrap().as_u32_iter().collect();
+    assert_eq!(cells, [1, 0x20]);
+}
+
+#[test]
+fn test_convert_round_trip() {
+    for (name, raw) in [("qemu", fdt_qemu()), ("reserve", fdt_reserve())] {
+        let dtb = fixture(&format!("{name}.dtb"), &raw);
+        for ext in ["json", "dts"] {
+            let text = tmp_path(&format!("{name}.{ext}"));
+            let back = tmp_path(&format!("{name}.{ext}.dtb"));
+            stdout(&fdt(&["convert", &dtb, &text]));
+            stdout(&fdt(&["convert", &text, &back]));
+            assert_eq!(stdout(&fdt(&["diff", &dtb, &back])), "");
+        }
+    }
+}
+
+#[test]
+fn test_diff() {
+    let a = fixture("diff-a.dtb", &fdt_qemu());
+    let b = fixture("diff-b.dtb", &fdt_qemu());
+    stdout(&fdt(&["put", &b, "-t", "u32", "/", "#size-cells", "1"]));
+    stdout(&fdt(&["rm", &b, "/psci"]));
+
+    let out = fdt(&["diff", &a, &b]);
+    assert_eq!(out.status.code(), Some(1));
+    let out = String::from_utf8(out.stdout).unwrap();
+    assert!(out.starts_with(&format!("--- {a}\n+++ {b}\n")));
+    assert!(out.contains("@@ / @@\n-\t#size-cells = <0x2>;\n+\t#size-cells = <0x1>;\n"));
+    assert!(out.contains("@@ /psci (removed) @@\n"));
+}
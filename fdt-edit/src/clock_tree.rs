@@ -0,0 +1,548 @@
+//! Clock tree graph and nominal rate computation.
+//!
+//! [`ClockTree`] links every `clocks` consumer to its provider and records
+//! what the tree states about rates: `fixed-clock` and `gated-fixed-clock`
+//! frequencies, `fixed-factor-clock` ratios and the `assigned-clock-rates`
+//! and `assigned-clock-parents` set up at boot. Rates of other provider
+//! outputs depend on the driver and are reported as unknown.
+//!
+//! ```
+//! use fdt_edit::Fdt;
+//!
+//! let fdt = Fdt::from_dts(
+//!     "/dts-v1/;
+//!     / {
+//!         osc: osc {
+//!             compatible = \"fixed-clock\";
+//!             #clock-cells = <0>;
+//!             clock-frequency = <24000000>;
+//!         };
+//!         half: half {
+//!             compatible = \"fixed-factor-clock\";
+//!             #clock-cells = <0>;
+//!             clocks = <&osc>;
+//!             clock-div = <2>;
+//!             clock-mult = <1>;
+//!         };
+//!         uart { clocks = <&half>; clock-names = \"baudclk\"; };
+//!     };",
+//! )
+//! .unwrap();
+//!
+//! let tree = fdt.clock_tree();
+//! let uart = fdt.get_by_path_id("/uart").unwrap();
+//! let baudclk = &tree.clocks_of(uart)[0];
+//! assert_eq!(tree.rate_of(baudclk), Ok(12_000_000));
+//! ```
+
+use core::fmt;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    ClockNodeView, ClockType, Fdt, Node, NodeId, NodeType, Phandle, ViewOp,
+    node::view::{PhandleArg, PhandleArgs},
+};
+
+/// One output of a clock provider.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClockOutput {
+    /// The provider node.
+    pub provider: NodeId,
+    /// Specifier cells selecting the output, as many as `#clock-cells`.
+    pub specifier: Vec<u32>,
+}
+
+/// A phandle reference from a clock property to a provider output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClockLink {
+    /// Name from `clock-names`, for `clocks` entries.
+    pub name: Option<String>,
+    /// Phandle written in the property.
+    pub phandle: Phandle,
+    /// The referenced output, or `None` if the phandle does not lead to a
+    /// clock provider.
+    pub output: Option<ClockOutput>,
+}
+
+/// A clock property entry whose phandle does not lead to a clock provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DanglingClock {
+    /// The node holding the property.
+    pub node: NodeId,
+    /// The property, such as `clocks` or `assigned-clocks`.
+    pub property: &'static str,
+    /// The phandle that could not be resolved.
+    pub phandle: Phandle,
+}
+
+/// Errors from [`ClockTree::rate`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ClockError {
+    /// The reference does not lead to a clock provider.
+    #[error("clock phandle {0} does not lead to a clock provider")]
+    Dangling(Phandle),
+    /// The rate depends on itself through the clock parents.
+    #[error("clock cycle through `{0}`")]
+    Cycle(String),
+    /// Nothing in the tree fixes the rate of this output.
+    #[error("rate of `{path}` output {specifier:?} is not known statically")]
+    Unknown {
+        /// Path of the provider
+        path: String,
+        /// Specifier of the output
+        specifier: Vec<u32>,
+    },
+    /// Scaling the parent rate by `clock-mult` overflows 64 bits.
+    #[error("rate of `{0}` overflows")]
+    Overflow(String),
+}
+
+/// Clock providers and consumers of a device tree.
+///
+/// Built with [`Fdt::clock_tree`]. The graph is a snapshot: it does not
+/// follow later edits of the tree.
+#[derive(Clone, Debug)]
+pub struct ClockTree {
+    paths: BTreeMap<NodeId, String>,
+    providers: BTreeMap<NodeId, ClockType>,
+    clocks: BTreeMap<NodeId, Vec<ClockLink>>,
+    assigned_rates: BTreeMap<ClockOutput, u64>,
+    assigned_parents: BTreeMap<ClockOutput, ClockLink>,
+    dangling: Vec<DanglingClock>,
+}
+
+impl Fdt {
+    /// Builds the clock tree of this device tree.
+    pub fn clock_tree(&self) -> ClockTree {
+        ClockTree::new(self)
+    }
+}
+
+impl ClockTree {
+    /// Builds the clock tree of `fdt`.
+    pub fn new(fdt: &Fdt) -> Self {
+        let mut tree = Self {
+            paths: BTreeMap::new(),
+            providers: BTreeMap::new(),
+            clocks: BTreeMap::new(),
+            assigned_rates: BTreeMap::new(),
+            assigned_parents: BTreeMap::new(),
+            dangling: Vec::new(),
+        };
+
+        for id in fdt.iter_node_ids() {
+            let Some(view) = fdt.view_typed(id) else {
+                continue;
+            };
+            let node = view.as_node();
+
+            if let NodeType::Clock(clock) = view {
+                tree.providers.insert(id, clock.clock_type());
+            }
+
+            let clocks = tree.parse_links(fdt, id, "clocks");
+            if !clocks.is_empty() {
+                let names: Vec<&str> = node
+                    .get_property("clock-names")
+                    .map(|prop| prop.as_str_iter().collect())
+                    .unwrap_or_default();
+                let clocks = clocks
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, link)| ClockLink {
+                        name: names.get(i).map(|name| name.to_string()),
+                        ..link
+                    })
+                    .collect();
+                tree.clocks.insert(id, clocks);
+            }
+
+            let assigned = tree.parse_links(fdt, id, "assigned-clocks");
+            let parents = tree.parse_links(fdt, id, "assigned-clock-parents");
+            let rates: Vec<u32> = node
+                .get_property("assigned-clock-rates")
+                .map(|prop| prop.get_u32_iter().collect())
+                .unwrap_or_default();
+            for (i, link) in assigned.into_iter().enumerate() {
+                let Some(output) = link.output else {
+                    continue;
+                };
+                // A zero rate or a zero parent phandle leaves that setting alone.
+                if let Some(&rate) = rates.get(i).filter(|&&rate| rate != 0) {
+                    tree.assigned_rates.insert(output.clone(), rate as u64);
+                }
+                if let Some(parent) = parents.get(i).filter(|parent| parent.output.is_some()) {
+                    tree.assigned_parents.insert(output, parent.clone());
+                }
+            }
+
+            if tree.providers.contains_key(&id) || tree.clocks.contains_key(&id) {
+                tree.paths.insert(id, fdt.path_of(id));
+            }
+        }
+
+        // Providers only referenced from assigned-clocks, and nodes whose
+        // dangling references are reported, still need a path.
+        let referenced: Vec<NodeId> = tree
+            .assigned_rates
+            .keys()
+            .chain(tree.assigned_parents.keys())
+            .map(|output| output.provider)
+            .chain(tree.dangling.iter().map(|dangling| dangling.node))
+            .collect();
+        for id in referenced {
+            tree.paths.entry(id).or_insert_with(|| fdt.path_of(id));
+        }
+
+        tree
+    }
+
+    /// Parses a phandle-and-specifier clock property of `id`.
+    ///
+    /// Entries with a zero phandle are kept as unresolved placeholders, as
+    /// `assigned-clock-parents` uses them to skip a clock. Parsing stops at
+    /// a phandle that is not a clock provider, since the width of its
+    /// specifier is unknown.
+    fn parse_links(&mut self, fdt: &Fdt, id: NodeId, property: &'static str) -> Vec<ClockLink> {
+        let Some(prop) = fdt.node(id).and_then(|node| node.get_property(property)) else {
+            return Vec::new();
+        };
+
+        let clock_cells = |node: &Node| {
+            if node.is_clock() {
+                node.get_property("#clock-cells")?.get_u32()
+            } else {
+                None
+            }
+        };
+
+        let mut links = Vec::new();
+        for arg in PhandleArgs::new(fdt, prop, clock_cells) {
+            let (phandle, output) = match arg {
+                PhandleArg::Empty => (Phandle::from(0), None),
+                PhandleArg::Resolved(target) => (
+                    target.phandle,
+                    Some(ClockOutput {
+                        provider: target.provider,
+                        specifier: target.specifier,
+                    }),
+                ),
+                PhandleArg::Unresolved(phandle) => {
+                    self.dangling.push(DanglingClock {
+                        node: id,
+                        property,
+                        phandle,
+                    });
+                    (phandle, None)
+                }
+            };
+            links.push(ClockLink {
+                name: None,
+                phandle,
+                output,
+            });
+        }
+
+        links
+    }
+
+    /// Returns the provider nodes and their types.
+    pub fn providers(&self) -> impl Iterator<Item = (NodeId, &ClockType)> {
+        self.providers.iter().map(|(&id, kind)| (id, kind))
+    }
+
+    /// Returns the `clocks` entries of `node`, with their `clock-names`.
+    pub fn clocks_of(&self, node: NodeId) -> &[ClockLink] {
+        self.clocks
+            .get(&node)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the nodes whose `clocks` reference an output of `provider`.
+    pub fn consumers_of(&self, provider: NodeId) -> Vec<NodeId> {
+        self.clocks
+            .iter()
+            .filter(|(_, links)| {
+                links
+                    .iter()
+                    .any(|link| link.output.as_ref().is_some_and(|o| o.provider == provider))
+            })
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Returns the rate set by `assigned-clock-rates` for `output`.
+    pub fn assigned_rate(&self, output: &ClockOutput) -> Option<u64> {
+        self.assigned_rates.get(output).copied()
+    }
+
+    /// Returns the parent set by `assigned-clock-parents` for `output`.
+    pub fn assigned_parent(&self, output: &ClockOutput) -> Option<&ClockLink> {
+        self.assigned_parents.get(output)
+    }
+
+    /// Returns the clock references that do not lead to a clock provider.
+    pub fn dangling(&self) -> &[DanglingClock] {
+        &self.dangling
+    }
+
+    /// Returns the nominal rate of a consumer's clock reference.
+    pub fn rate_of(&self, link: &ClockLink) -> Result<u64, ClockError> {
+        match &link.output {
+            Some(output) => self.rate(output),
+            None => Err(ClockError::Dangling(link.phandle)),
+        }
+    }
+
+    /// Returns the nominal rate of a provider output.
+    ///
+    /// Fixed clocks give their `clock-frequency` and fixed-factor clocks
+    /// scale their parent. Other outputs take their `assigned-clock-rates`
+    /// value, or else the rate of their `assigned-clock-parents` entry.
+    pub fn rate(&self, output: &ClockOutput) -> Result<u64, ClockError> {
+        self.rate_inner(output, &mut Vec::new())
+    }
+
+    fn rate_inner(
+        &self,
+        output: &ClockOutput,
+        visiting: &mut Vec<ClockOutput>,
+    ) -> Result<u64, ClockError> {
+        if visiting.contains(output) {
+            return Err(ClockError::Cycle(self.path(output.provider).to_string()));
+        }
+
+        let kind = self.providers.get(&output.provider);
+        if let Some(ClockType::Fixed(fixed) | ClockType::GatedFixed(fixed)) = kind {
+            return Ok(fixed.frequency as u64);
+        }
+        if let Some(&rate) = self.assigned_rates.get(output) {
+            return Ok(rate);
+        }
+
+        let parent = self.parent(output).ok_or_else(|| self.unknown(output))?;
+        let parent_output = parent
+            .output
+            .as_ref()
+            .ok_or(ClockError::Dangling(parent.phandle))?;
+
+        visiting.push(output.clone());
+        let rate = self.rate_inner(parent_output, visiting);
+        visiting.pop();
+        let rate = rate?;
+
+        match kind {
+            Some(ClockType::FixedFactor(factor)) if factor.div == 0 => Err(self.unknown(output)),
+            Some(ClockType::FixedFactor(factor)) => rate
+                .checked_mul(factor.mult as u64)
+                .map(|scaled| scaled / factor.div as u64)
+                .ok_or_else(|| ClockError::Overflow(self.path(output.provider).to_string())),
+            _ => Ok(rate),
+        }
+    }
+
+    fn unknown(&self, output: &ClockOutput) -> ClockError {
+        ClockError::Unknown {
+            path: self.path(output.provider).to_string(),
+            specifier: output.specifier.clone(),
+        }
+    }
+
+    fn path(&self, id: NodeId) -> &str {
+        self.paths.get(&id).map(String::as_str).unwrap_or("?")
+    }
+
+    /// Returns the parent of `output` known from the tree: the
+    /// `assigned-clock-parents` entry, or the single input of a
+    /// fixed-factor clock.
+    pub fn parent(&self, output: &ClockOutput) -> Option<&ClockLink> {
+        if let Some(parent) = self.assigned_parents.get(output) {
+            return Some(parent);
+        }
+        match self.providers.get(&output.provider) {
+            Some(ClockType::FixedFactor(_)) => self.clocks_of(output.provider).first(),
+            _ => None,
+        }
+    }
+
+    /// Returns the outputs whose parents loop back to themselves, one list
+    /// per cycle in parent order.
+    pub fn cycles(&self) -> Vec<Vec<ClockOutput>> {
+        let mut outputs: BTreeSet<ClockOutput> = self.assigned_parents.keys().cloned().collect();
+        for (&id, kind) in &self.providers {
+            if matches!(kind, ClockType::FixedFactor(_)) {
+                outputs.insert(ClockOutput {
+                    provider: id,
+                    specifier: Vec::new(),
+                });
+            }
+        }
+
+        // Each output has at most one parent, so following the chain from
+        // every unvisited output finds each cycle exactly once.
+        let mut done = BTreeSet::new();
+        let mut cycles = Vec::new();
+        for start in outputs {
+            let mut chain: Vec<ClockOutput> = Vec::new();
+            let mut current = Some(start);
+            while let Some(output) = current {
+                if done.contains(&output) {
+                    break;
+                }
+                if let Some(pos) = chain.iter().position(|o| *o == output) {
+                    cycles.push(chain[pos..].to_vec());
+                    break;
+                }
+                current = self.parent(&output).and_then(|link| link.output.clone());
+                chain.push(output);
+            }
+            done.extend(chain);
+        }
+        cycles
+    }
+
+    /// Returns the other providers that feed `provider`.
+    fn parent_providers(&self, provider: NodeId) -> BTreeSet<NodeId> {
+        let own = self.clocks_of(provider).iter();
+        let assigned = self
+            .assigned_parents
+            .iter()
+            .filter(|(output, _)| output.provider == provider)
+            .map(|(_, link)| link);
+        own.chain(assigned)
+            .filter_map(|link| link.output.as_ref())
+            .map(|output| output.provider)
+            .filter(|&parent| parent != provider && self.providers.contains_key(&parent))
+            .collect()
+    }
+
+    fn write_provider(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        id: NodeId,
+        depth: usize,
+        printed: &mut BTreeSet<NodeId>,
+    ) -> fmt::Result {
+        printed.insert(id);
+        let kind = match self.providers.get(&id) {
+            Some(ClockType::Fixed(_)) => "fixed-clock",
+            Some(ClockType::GatedFixed(_)) => "gated-fixed-clock",
+            Some(ClockType::FixedFactor(_)) => "fixed-factor-clock",
+            _ => "provider",
+        };
+        write!(
+            f,
+            "{:indent$}{} [{}]",
+            "",
+            self.path(id),
+            kind,
+            indent = depth * 2
+        )?;
+        let single = ClockOutput {
+            provider: id,
+            specifier: Vec::new(),
+        };
+        if let Ok(rate) = self.rate(&single) {
+            write!(f, " {rate} Hz")?;
+        }
+        writeln!(f)?;
+
+        for consumer in self.consumers_of(id) {
+            for link in self.clocks_of(consumer) {
+                let Some(output) = link.output.as_ref().filter(|o| o.provider == id) else {
+                    continue;
+                };
+                if self.providers.contains_key(&consumer) && !printed.contains(&consumer) {
+                    self.write_provider(f, consumer, depth + 1, printed)?;
+                    continue;
+                }
+
+                write!(
+                    f,
+                    "{:indent$}{}",
+                    "",
+                    self.path(consumer),
+                    indent = (depth + 1) * 2
+                )?;
+                if let Some(name) = &link.name {
+                    write!(f, " \"{name}\"")?;
+                }
+                write_specifier(f, &output.specifier)?;
+                match self.rate(output) {
+                    Ok(rate) => writeln!(f, " {rate} Hz")?,
+                    Err(_) => writeln!(f, " ? Hz")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_specifier(f: &mut fmt::Formatter<'_>, specifier: &[u32]) -> fmt::Result {
+    if specifier.is_empty() {
+        return Ok(());
+    }
+    write!(f, " <")?;
+    for (i, cell) in specifier.iter().enumerate() {
+        let sep = if i == 0 { "" } else { " " };
+        write!(f, "{sep}{cell:#x}")?;
+    }
+    write!(f, ">")
+}
+
+impl fmt::Display for ClockTree {
+    /// Renders providers as an indented tree, each followed by the nodes
+    /// that consume its outputs, then lists dangling references and cycles.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut printed = BTreeSet::new();
+
+        let roots = self
+            .providers
+            .keys()
+            .filter(|&&id| self.parent_providers(id).is_empty());
+        for &id in roots {
+            self.write_provider(f, id, 0, &mut printed)?;
+        }
+        // Providers on cycles have no root to hang from.
+        for &id in self.providers.keys() {
+            if !printed.contains(&id) {
+                self.write_provider(f, id, 0, &mut printed)?;
+            }
+        }
+
+        for dangling in &self.dangling {
+            writeln!(
+                f,
+                "dangling: {} {} -> {}",
+                self.path(dangling.node),
+                dangling.property,
+                dangling.phandle
+            )?;
+        }
+        for cycle in self.cycles() {
+            write!(f, "cycle:")?;
+            for output in cycle {
+                write!(f, " {}", self.path(output.provider))?;
+                write_specifier(f, &output.specifier)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl ClockNodeView<'_> {
+    /// Returns the provider's output selected by `specifier`.
+    pub fn output(&self, specifier: &[u32]) -> ClockOutput {
+        ClockOutput {
+            provider: self.as_view().id(),
+            specifier: specifier.to_vec(),
+        }
+    }
+}
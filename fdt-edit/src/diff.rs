@@ -0,0 +1,627 @@
+//! Semantic comparison of two device trees.
+//!
+//! [`diff`] matches nodes by path and reports what it takes to turn one tree
+//! into another. Properties that hold phandles are decoded into references
+//! to the target node's path, so renumbered phandles do not show up as
+//! changes, and a [`TreeDiff`] can be applied to a third tree whose
+//! phandles differ from both.
+//!
+//! ```
+//! use fdt_edit::Fdt;
+//!
+//! let old = Fdt::from_dts("/dts-v1/; / { a { x = <1>; }; };").unwrap();
+//! let new = Fdt::from_dts("/dts-v1/; / { a { x = <2>; }; b { }; };").unwrap();
+//!
+//! let diff = fdt_edit::diff(&old, &new);
+//! assert_eq!(diff.added, ["/b"]);
+//!
+//! let mut patched = old.clone();
+//! diff.apply(&mut patched).unwrap();
+//! assert!(fdt_edit::diff(&patched, &new).is_empty());
+//! ```
+
+use core::fmt;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    Fdt, Node, NodeId, Phandle, Property,
+    dts::{as_string_list, write_string},
+};
+
+/// The differences between two trees, as returned by [`diff`].
+///
+/// Paths are those of the new tree. Applying the diff performs the moves
+/// first, so the nodes in [`removed`](Self::removed) are also given by
+/// their path after the moves.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeDiff {
+    /// Subtrees that were moved to a different parent
+    pub moved: Vec<NodeMove>,
+    /// Nodes that only exist in the new tree, parents before children
+    pub added: Vec<String>,
+    /// Nodes that only exist in the old tree, without their descendants
+    pub removed: Vec<String>,
+    /// Added, removed and changed properties, including those of added
+    /// nodes
+    pub properties: Vec<PropertyChange>,
+}
+
+/// A subtree that moved to a different parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeMove {
+    /// Path in the old tree
+    pub from: String,
+    /// Path in the new tree
+    pub to: String,
+}
+
+/// A property that was added, removed or changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyChange {
+    /// Path of the node holding the property
+    pub path: String,
+    /// Property name
+    pub name: String,
+    /// Value in the old tree, `None` if the property was added
+    pub old: Option<PropValue>,
+    /// Value in the new tree, `None` if the property was removed
+    pub new: Option<PropValue>,
+}
+
+/// A property value with phandles replaced by node paths.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropValue {
+    /// A value that holds no phandles, or could not be decoded
+    Bytes(Vec<u8>),
+    /// A list of cells, some of which refer to other nodes
+    Cells(Vec<PropCell>),
+}
+
+/// One cell of a [`PropValue::Cells`] value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropCell {
+    /// A plain 32-bit value
+    Value(u32),
+    /// The phandle of the node at this path
+    Ref(String),
+}
+
+/// Errors that can occur while applying a [`TreeDiff`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    /// A node the diff needs does not exist in the patched tree.
+    #[error("node `{0}` not found")]
+    NodeNotFound(String),
+    /// A move would replace a node that already exists.
+    #[error("node `{0}` already exists")]
+    NodeExists(String),
+}
+
+/// How a property refers to other nodes.
+enum RefLayout {
+    /// The value is the node's own phandle.
+    Own,
+    /// A list of phandles, each followed by the number of cells given by
+    /// this property of the target node, or by the default if it has none.
+    List(Option<&'static str>, Option<u32>),
+}
+
+/// Returns how property `name` refers to other nodes, if it does.
+fn ref_layout(name: &str) -> Option<RefLayout> {
+    let cells = match name {
+        "phandle" | "linux,phandle" => return Some(RefLayout::Own),
+        "interrupt-parent"
+        | "memory-region"
+        | "operating-points-v2"
+        | "next-level-cache"
+        | "cpu-idle-states"
+        | "remote-endpoint"
+        | "phy-handle"
+        | "nvmem-cells" => {
+            return Some(RefLayout::List(None, None));
+        }
+        "clocks" | "assigned-clocks" | "assigned-clock-parents" => "#clock-cells",
+        "resets" => "#reset-cells",
+        "power-domains" => "#power-domain-cells",
+        "dmas" => "#dma-cells",
+        "phys" => "#phy-cells",
+        "iommus" => "#iommu-cells",
+        "pwms" => "#pwm-cells",
+        "mboxes" => "#mbox-cells",
+        "interrupts-extended" => "#interrupt-cells",
+        "io-channels" => "#io-channel-cells",
+        "thermal-sensors" => "#thermal-sensor-cells",
+        "cooling-device" => "#cooling-cells",
+        "msi-parent" => return Some(RefLayout::List(Some("#msi-cells"), Some(0))),
+        "gpios" => "#gpio-cells",
+        _ if name.ends_with("-gpios") && !name.ends_with("nr-gpios") => "#gpio-cells",
+        _ if name.ends_with("-gpio") => "#gpio-cells",
+        _ if name.ends_with("-supply") => return Some(RefLayout::List(None, None)),
+        _ if name
+            .strip_prefix("pinctrl-")
+            .is_some_and(|index| index.bytes().all(|b| b.is_ascii_digit())) =>
+        {
+            return Some(RefLayout::List(None, None));
+        }
+        _ => return None,
+    };
+    Some(RefLayout::List(Some(cells), None))
+}
+
+/// Decodes the value of `prop` on node `id`, replacing phandles by paths.
+///
+/// Falls back to the raw bytes when the property holds no phandles or does
+/// not decode cleanly.
+fn decode(fdt: &Fdt, id: NodeId, prop: &Property) -> PropValue {
+    let cells = if !prop.data.is_empty() && prop.data.len().is_multiple_of(4) {
+        match ref_layout(&prop.name) {
+            Some(RefLayout::Own) if prop.data.len() == 4 => {
+                Some(vec![PropCell::Ref(fdt.path_of(id))])
+            }
+            Some(RefLayout::List(cells_name, default)) => {
+                let raw: Vec<u32> = prop.get_u32_iter().collect();
+                decode_list(fdt, &raw, cells_name, default)
+            }
+            _ => None,
+        }
+    } else {
+        None
+    };
+    match cells {
+        Some(cells) => PropValue::Cells(cells),
+        None => PropValue::Bytes(prop.data.clone()),
+    }
+}
+
+fn decode_list(
+    fdt: &Fdt,
+    raw: &[u32],
+    cells_name: Option<&str>,
+    default: Option<u32>,
+) -> Option<Vec<PropCell>> {
+    let mut cells = Vec::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some((&phandle, tail)) = rest.split_first() {
+        let target = fdt.get_by_phandle_id(Phandle::from(phandle))?;
+        let count = match cells_name {
+            Some(name) => match fdt.node(target)?.get_property(name) {
+                Some(prop) => prop.get_u32()?,
+                None => default?,
+            },
+            None => 0,
+        } as usize;
+        let specifier = tail.get(..count)?;
+        cells.push(PropCell::Ref(fdt.path_of(target)));
+        cells.extend(specifier.iter().copied().map(PropCell::Value));
+        rest = &tail[count..];
+    }
+    Some(cells)
+}
+
+/// Compares two trees.
+///
+/// Nodes are matched by path. A subtree that disappears from one parent
+/// and appears under another with the same node name and `compatible` is
+/// reported as moved rather than as removed and added; its contents are
+/// then compared like any other node.
+pub fn diff(old: &Fdt, new: &Fdt) -> TreeDiff {
+    let old_paths: BTreeMap<String, NodeId> = old
+        .iter_node_ids()
+        .map(|id| (old.path_of(id), id))
+        .collect();
+    let new_ids: Vec<NodeId> = new.iter_node_ids().collect();
+    let new_paths: BTreeSet<String> = new_ids.iter().map(|&id| new.path_of(id)).collect();
+
+    let moved = find_moves(old, new, &old_paths, &new_ids, &new_paths);
+    let moves = Moves(&moved);
+
+    let mut result = TreeDiff {
+        moved: moved.clone(),
+        ..TreeDiff::default()
+    };
+
+    // Added nodes and property changes, in new tree order
+    for &id in &new_ids {
+        let path = new.path_of(id);
+        let node = new.node(id).unwrap();
+        let old_id = old_paths.get(&moves.to_old(&path)).copied();
+
+        let Some(old_id) = old_id else {
+            result.added.push(path.clone());
+            for prop in node.properties() {
+                result.properties.push(PropertyChange {
+                    path: path.clone(),
+                    name: prop.name.clone(),
+                    old: None,
+                    new: Some(decode(new, id, prop)),
+                });
+            }
+            continue;
+        };
+
+        let old_node = old.node(old_id).unwrap();
+        for prop in node.properties() {
+            let value = decode(new, id, prop);
+            let old_value = old_node
+                .get_property(&prop.name)
+                .map(|old_prop| moves.to_new_value(decode(old, old_id, old_prop)));
+            if old_value.as_ref() != Some(&value) {
+                result.properties.push(PropertyChange {
+                    path: path.clone(),
+                    name: prop.name.clone(),
+                    old: old_value,
+                    new: Some(value),
+                });
+            }
+        }
+        for old_prop in old_node.properties() {
+            if node.get_property(&old_prop.name).is_none() {
+                result.properties.push(PropertyChange {
+                    path: path.clone(),
+                    name: old_prop.name.clone(),
+                    old: Some(moves.to_new_value(decode(old, old_id, old_prop))),
+                    new: None,
+                });
+            }
+        }
+    }
+
+    // Removed nodes, in old tree order, without their descendants
+    for id in old.iter_node_ids() {
+        let path = moves.to_new(&old.path_of(id));
+        if new_paths.contains(&path) {
+            continue;
+        }
+        let parent = old.parent_of(id).map(|p| moves.to_new(&old.path_of(p)));
+        if parent.is_none_or(|parent| new_paths.contains(&parent)) {
+            result.removed.push(path);
+        }
+    }
+
+    result
+}
+
+/// Pairs subtrees that left one parent with subtrees that appeared under
+/// another.
+fn find_moves(
+    old: &Fdt,
+    new: &Fdt,
+    old_paths: &BTreeMap<String, NodeId>,
+    new_ids: &[NodeId],
+    new_paths: &BTreeSet<String>,
+) -> Vec<NodeMove> {
+    // Topmost nodes missing from the other tree
+    let gone: Vec<NodeId> = old
+        .iter_node_ids()
+        .filter(|&id| {
+            !new_paths.contains(&old.path_of(id))
+                && old
+                    .parent_of(id)
+                    .is_some_and(|p| new_paths.contains(&old.path_of(p)))
+        })
+        .collect();
+    let appeared: Vec<NodeId> = new_ids
+        .iter()
+        .copied()
+        .filter(|&id| {
+            !old_paths.contains_key(&new.path_of(id))
+                && new
+                    .parent_of(id)
+                    .is_some_and(|p| old_paths.contains_key(&new.path_of(p)))
+        })
+        .collect();
+
+    let compatible = |node: &Node| node.get_property("compatible").map(|p| p.data.clone());
+    let mut used = BTreeSet::new();
+    let mut moved = Vec::new();
+    for old_id in gone {
+        let old_node = old.node(old_id).unwrap();
+        let found = appeared.iter().copied().find(|&new_id| {
+            let new_node = new.node(new_id).unwrap();
+            !used.contains(&new_id)
+                && new_node.name == old_node.name
+                && compatible(new_node) == compatible(old_node)
+        });
+        if let Some(new_id) = found {
+            used.insert(new_id);
+            moved.push(NodeMove {
+                from: old.path_of(old_id),
+                to: new.path_of(new_id),
+            });
+        }
+    }
+    moved
+}
+
+/// Path translation through a list of moves.
+struct Moves<'a>(&'a [NodeMove]);
+
+impl Moves<'_> {
+    /// Maps a path of the old tree to where it ends up after the moves.
+    fn to_new(&self, path: &str) -> String {
+        self.translate(path, |m| (&m.from, &m.to))
+    }
+
+    /// Maps a path of the new tree to where it was before the moves.
+    fn to_old(&self, path: &str) -> String {
+        self.translate(path, |m| (&m.to, &m.from))
+    }
+
+    fn translate(&self, path: &str, dir: impl Fn(&NodeMove) -> (&String, &String)) -> String {
+        for m in self.0 {
+            let (from, to) = dir(m);
+            if let Some(rest) = path.strip_prefix(from.as_str())
+                && (rest.is_empty() || rest.starts_with('/'))
+            {
+                return format!("{to}{rest}");
+            }
+        }
+        path.to_string()
+    }
+
+    /// Rewrites the references of an old tree value to new tree paths.
+    fn to_new_value(&self, value: PropValue) -> PropValue {
+        match value {
+            PropValue::Cells(cells) => PropValue::Cells(
+                cells
+                    .into_iter()
+                    .map(|cell| match cell {
+                        PropCell::Ref(path) => PropCell::Ref(self.to_new(&path)),
+                        value => value,
+                    })
+                    .collect(),
+            ),
+            bytes => bytes,
+        }
+    }
+}
+
+/// A detached copy of a subtree, used to move it.
+struct Subtree {
+    node: Node,
+    children: Vec<Subtree>,
+}
+
+impl Subtree {
+    fn take(fdt: &Fdt, id: NodeId) -> Self {
+        let source = fdt.node(id).unwrap();
+        let mut node = Node::new(&source.name);
+        for prop in source.properties() {
+            node.add_property(prop.clone());
+        }
+        let children = source
+            .children()
+            .iter()
+            .map(|&child| Self::take(fdt, child))
+            .collect();
+        Self { node, children }
+    }
+
+    fn insert(self, fdt: &mut Fdt, parent: NodeId) {
+        let id = fdt.add_node(parent, self.node);
+        for child in self.children {
+            child.insert(fdt, id);
+        }
+    }
+}
+
+/// Splits a path into its parent path and last component.
+fn split_path(path: &str) -> (&str, &str) {
+    match path.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => ("/", path),
+    }
+}
+
+impl TreeDiff {
+    /// Returns `true` if the trees are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.properties.is_empty()
+    }
+
+    /// Applies the diff to `fdt`.
+    ///
+    /// The tree does not have to be the old tree the diff was made from:
+    /// old values are not checked, nodes that already exist are reused,
+    /// and removing something that is already gone is not an error.
+    /// References are resolved by path in `fdt`, giving the target node a
+    /// new phandle if it has none.
+    ///
+    /// On error the tree may have been partially modified.
+    pub fn apply(&self, fdt: &mut Fdt) -> Result<(), PatchError> {
+        let node_id = |fdt: &Fdt, path: &str| {
+            fdt.get_by_path_id(path)
+                .ok_or_else(|| PatchError::NodeNotFound(path.to_string()))
+        };
+
+        for m in &self.moved {
+            let id = node_id(fdt, &m.from)?;
+            if fdt.get_by_path_id(&m.to).is_some() {
+                return Err(PatchError::NodeExists(m.to.clone()));
+            }
+            let (parent, name) = split_path(&m.to);
+            let parent = node_id(fdt, parent)?;
+            let mut subtree = Subtree::take(fdt, id);
+            subtree.node.name = name.to_string();
+            fdt.remove_by_path(&m.from);
+            subtree.insert(fdt, parent);
+        }
+
+        for path in &self.added {
+            if fdt.get_by_path_id(path).is_none() {
+                let (parent, name) = split_path(path);
+                let parent = node_id(fdt, parent)?;
+                fdt.add_node(parent, Node::new(name));
+            }
+        }
+
+        let mut next_phandle = fdt.max_phandle() + 1;
+        for change in &self.properties {
+            let id = node_id(fdt, &change.path)?;
+            match &change.new {
+                Some(value) => {
+                    let data = encode(fdt, value, &mut next_phandle)?;
+                    fdt.node_mut(id)
+                        .unwrap()
+                        .set_property(Property::new(&change.name, data));
+                }
+                None => {
+                    fdt.node_mut(id).unwrap().remove_property(&change.name);
+                }
+            }
+        }
+
+        for path in &self.removed {
+            fdt.remove_by_path(path);
+        }
+
+        fdt.rebuild_phandle_cache();
+        Ok(())
+    }
+}
+
+/// Encodes a value for `fdt`, resolving references to its phandles.
+///
+/// Nodes without a phandle get `next_phandle`, which is then advanced.
+fn encode(fdt: &mut Fdt, value: &PropValue, next_phandle: &mut u32) -> Result<Vec<u8>, PatchError> {
+    let cells = match value {
+        PropValue::Bytes(bytes) => return Ok(bytes.clone()),
+        PropValue::Cells(cells) => cells,
+    };
+    let mut data = Vec::with_capacity(cells.len() * 4);
+    for cell in cells {
+        let value = match cell {
+            PropCell::Value(value) => *value,
+            PropCell::Ref(path) => phandle_of(fdt, path, next_phandle)?.raw(),
+        };
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    Ok(data)
+}
+
+/// Returns the phandle of the node at `path`, allocating one if needed.
+fn phandle_of(fdt: &mut Fdt, path: &str, next_phandle: &mut u32) -> Result<Phandle, PatchError> {
+    let id = fdt
+        .get_by_path_id(path)
+        .ok_or_else(|| PatchError::NodeNotFound(path.to_string()))?;
+    if let Some(phandle) = fdt.node(id).unwrap().phandle() {
+        return Ok(phandle);
+    }
+    let phandle = Phandle::from(*next_phandle);
+    *next_phandle += 1;
+    let node = fdt.node_mut(id).unwrap();
+    node.set_property(Property::new(
+        "phandle",
+        phandle.raw().to_be_bytes().to_vec(),
+    ));
+    Ok(phandle)
+}
+
+impl fmt::Display for PropValue {
+    /// Formats the value in DTS syntax, with references as `&{/path}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        match self {
+            PropValue::Cells(cells) => {
+                out.push('<');
+                for (i, cell) in cells.iter().enumerate() {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    match cell {
+                        PropCell::Value(value) => out.push_str(&format!("{value:#x}")),
+                        PropCell::Ref(path) => out.push_str(&format!("&{{{path}}}")),
+                    }
+                }
+                out.push('>');
+            }
+            PropValue::Bytes(bytes) => {
+                if let Some(strings) = as_string_list(bytes) {
+                    for (i, s) in strings.iter().enumerate() {
+                        if i > 0 {
+                            out.push_str(", ");
+                        }
+                        write_string(&mut out, s);
+                    }
+                } else if bytes.len().is_multiple_of(4) {
+                    out.push('<');
+                    for (i, cell) in bytes.chunks_exact(4).enumerate() {
+                        if i > 0 {
+                            out.push(' ');
+                        }
+                        let value = u32::from_be_bytes(cell.try_into().unwrap());
+                        out.push_str(&format!("{value:#x}"));
+                    }
+                    out.push('>');
+                } else {
+                    out.push('[');
+                    for (i, b) in bytes.iter().enumerate() {
+                        if i > 0 {
+                            out.push(' ');
+                        }
+                        out.push_str(&format!("{b:02x}"));
+                    }
+                    out.push(']');
+                }
+            }
+        }
+        f.write_str(&out)
+    }
+}
+
+fn write_property(
+    f: &mut fmt::Formatter<'_>,
+    sign: char,
+    name: &str,
+    value: &PropValue,
+) -> fmt::Result {
+    match value {
+        PropValue::Bytes(bytes) if bytes.is_empty() => writeln!(f, "{sign}\t{name};"),
+        _ => writeln!(f, "{sign}\t{name} = {value};"),
+    }
+}
+
+impl fmt::Display for TreeDiff {
+    /// Formats the diff as unified-diff hunks of DTS property lines, one
+    /// hunk per node.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in &self.moved {
+            writeln!(f, "@@ {} moved to {} @@", m.from, m.to)?;
+        }
+
+        let mut current: Option<&str> = None;
+        for change in &self.properties {
+            if current != Some(&change.path) {
+                let added = self.added.contains(&change.path);
+                let note = if added { " (added)" } else { "" };
+                writeln!(f, "@@ {}{note} @@", change.path)?;
+                current = Some(&change.path);
+            }
+            if let Some(old) = &change.old {
+                write_property(f, '-', &change.name, old)?;
+            }
+            if let Some(new) = &change.new {
+                write_property(f, '+', &change.name, new)?;
+            }
+        }
+
+        for path in &self.added {
+            if !self.properties.iter().any(|change| &change.path == path) {
+                writeln!(f, "@@ {path} (added) @@")?;
+            }
+        }
+        for path in &self.removed {
+            writeln!(f, "@@ {path} (removed) @@")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+//! Device tree source (DTS) support.
+//!
+//! This module converts between the textual DTS format understood by `dtc`
+//! and the editable [`Fdt`](crate::Fdt) tree.
+
+mod parse;
+mod write;
+
+pub use parse::{DtsError, DtsErrorKind};
+pub use write::{DtsOptions, LabelStyle};
+pub(crate) use write::{as_string_list, write_string};
@@ -0,0 +1,909 @@
+//! DTS source parser.
+//!
+//! Supports the subset of the `dtc` source language that does not need a
+//! preprocessor or file system: `/dts-v1/`, `/memreserve/`, labels, `&label`
+//! and `&{/path}` references, cell arrays (including `/bits/` and integer
+//! expressions), byte strings, string lists, repeated root definitions,
+//! `&label { ... }` node references, `/delete-node/` and `/delete-property/`.
+//!
+//! Phandle references are resolved after the whole source has been parsed,
+//! so forward references work. Nodes that are referenced but carry no
+//! `phandle` property are assigned the lowest unused phandle value, like
+//! `dtc` does.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+
+use crate::{Fdt, MemoryReservation, Node, NodeId, Property};
+
+/// Error produced while parsing device tree source.
+///
+/// `line` and `column` are 1-based and point at the offending input.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{line}:{column}: {kind}")]
+pub struct DtsError {
+    /// Line of the error (1-based)
+    pub line: usize,
+    /// Column of the error in characters (1-based)
+    pub column: usize,
+    /// What went wrong
+    pub kind: DtsErrorKind,
+}
+
+/// The kind of a [`DtsError`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DtsErrorKind {
+    /// The source does not start with `/dts-v1/;`
+    #[error("missing `/dts-v1/;` header")]
+    MissingVersion,
+    /// The input ended in the middle of a construct
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+    /// A specific token was expected
+    #[error("expected {0}")]
+    Expected(&'static str),
+    /// An integer literal could not be parsed
+    #[error("invalid integer literal")]
+    InvalidNumber,
+    /// An integer does not fit in the cell size
+    #[error("integer value out of range")]
+    OutOfRange,
+    /// `/bits/` was given something other than 8, 16, 32 or 64
+    #[error("invalid `/bits/` size {0}")]
+    InvalidBits(u64),
+    /// A string or character literal contains a bad escape sequence
+    #[error("invalid escape sequence")]
+    InvalidEscape,
+    /// An integer expression divides by zero
+    #[error("division by zero")]
+    DivisionByZero,
+    /// A `&label` or `&{/path}` reference does not name an existing node
+    #[error("reference `{0}` does not match any node")]
+    UnresolvedReference(String),
+    /// The same label was put on two different nodes
+    #[error("label `{0}` is already defined on another node")]
+    DuplicateLabel(String),
+    /// A phandle reference was used in a non 32-bit cell array
+    #[error("references are only allowed in 32-bit cell arrays")]
+    ReferenceWidth,
+    /// A directive that needs outside context, such as `/include/`
+    #[error("`{0}` is not supported")]
+    Unsupported(&'static str),
+    /// `/delete-node/` was applied to the root node
+    #[error("cannot delete the root node")]
+    DeleteRoot,
+}
+
+impl Fdt {
+    /// Parses device tree source text into an editable tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use fdt_edit::Fdt;
+    ///
+    /// let fdt = Fdt::from_dts(r#"
+    ///     /dts-v1/;
+    ///     / {
+    ///         #address-cells = <1>;
+    ///         intc: interrupt-controller { interrupt-controller; };
+    ///         uart@1000 { interrupt-parent = <&intc>; };
+    ///     };
+    /// "#).unwrap();
+    ///
+    /// assert!(fdt.get_by_path("/uart@1000").is_some());
+    /// ```
+    pub fn from_dts(source: &str) -> core::result::Result<Fdt, DtsError> {
+        Parser::new(source).parse()
+    }
+}
+
+/// Target of a `&label` or `&{/path}` reference.
+enum Reference {
+    Label(String),
+    Path(String),
+}
+
+impl Reference {
+    fn to_display(&self) -> String {
+        match self {
+            Reference::Label(label) => label.clone(),
+            Reference::Path(path) => format!("{{{}}}", path),
+        }
+    }
+}
+
+/// How an unresolved reference is stored in property data.
+enum MarkerKind {
+    /// A 4-byte placeholder to be replaced by the target's phandle
+    Phandle,
+    /// The target's full path, inserted as a NUL-terminated string
+    Path,
+}
+
+/// An unresolved reference inside a property value.
+struct Marker {
+    /// Byte offset in the property data
+    offset: usize,
+    kind: MarkerKind,
+    target: Reference,
+    /// Source position, for error reporting
+    pos: usize,
+}
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+    fdt: Fdt,
+    labels: BTreeMap<String, NodeId>,
+    markers: BTreeMap<(NodeId, String), Vec<Marker>>,
+}
+
+type Result<T> = core::result::Result<T, DtsError>;
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str) -> Self {
+        Self {
+            src: src.as_bytes(),
+            pos: 0,
+            fdt: Fdt::new(),
+            labels: BTreeMap::new(),
+            markers: BTreeMap::new(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Fdt> {
+        self.skip_ws();
+        if !self.eat_keyword("/dts-v1/") {
+            return Err(self.error(DtsErrorKind::MissingVersion));
+        }
+        self.expect(b';', "`;`")?;
+
+        loop {
+            self.skip_ws();
+            if self.peek().is_none() {
+                break;
+            }
+            self.top_level()?;
+        }
+
+        self.resolve_references()?;
+        Ok(self.fdt)
+    }
+
+    // -----------------------------------------------------------------------
+    // Error helpers
+    // -----------------------------------------------------------------------
+
+    fn error(&self, kind: DtsErrorKind) -> DtsError {
+        self.error_at(self.pos, kind)
+    }
+
+    fn error_at(&self, pos: usize, kind: DtsErrorKind) -> DtsError {
+        let before = &self.src[..pos.min(self.src.len())];
+        let line_start = before
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+        // Count characters, not bytes: skip UTF-8 continuation bytes.
+        let column = before[line_start..]
+            .iter()
+            .filter(|&&b| b & 0xc0 != 0x80)
+            .count()
+            + 1;
+        DtsError { line, column, kind }
+    }
+
+    fn unexpected(&self, what: &'static str) -> DtsError {
+        if self.peek().is_none() {
+            self.error(DtsErrorKind::UnexpectedEof)
+        } else {
+            self.error(DtsErrorKind::Expected(what))
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Lexical helpers
+    // -----------------------------------------------------------------------
+
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.src.get(self.pos + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    /// Skips whitespace, `//` line comments and `/* */` block comments.
+    fn skip_ws(&mut self) {
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(c), _) if c.is_ascii_whitespace() => self.pos += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while let Some(c) = self.bump() {
+                        if c == b'\n' {
+                            break;
+                        }
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    self.pos += 2;
+                    while self.peek().is_some() && !self.src[self.pos..].starts_with(b"*/") {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.src.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_ws();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: u8, what: &'static str) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(what))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_ws();
+        if self.src[self.pos..].starts_with(keyword.as_bytes()) {
+            self.pos += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_name_char(c: u8) -> bool {
+        c.is_ascii_alphanumeric() || b",._+*#?@-".contains(&c)
+    }
+
+    fn is_label_char(c: u8) -> bool {
+        c.is_ascii_alphanumeric() || c == b'_'
+    }
+
+    /// Reads a node or property name.
+    fn name(&mut self) -> Result<String> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.peek().is_some_and(Self::is_name_char) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.unexpected("a node or property name"));
+        }
+        Ok(self.text(start, self.pos))
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        String::from_utf8_lossy(&self.src[start..end]).into_owned()
+    }
+
+    /// Consumes any number of `label:` prefixes.
+    fn labels(&mut self) -> Vec<(String, usize)> {
+        let mut labels = Vec::new();
+        loop {
+            self.skip_ws();
+            let start = self.pos;
+            if !self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == b'_')
+            {
+                break;
+            }
+            let mut end = start;
+            while self.src.get(end).is_some_and(|&c| Self::is_label_char(c)) {
+                end += 1;
+            }
+            if self.src.get(end) != Some(&b':') {
+                break;
+            }
+            labels.push((self.text(start, end), start));
+            self.pos = end + 1;
+        }
+        labels
+    }
+
+    /// Parses `&label` or `&{/path}`.
+    fn reference(&mut self) -> Result<Reference> {
+        self.expect(b'&', "`&`")?;
+        if self.peek() == Some(b'{') {
+            self.pos += 1;
+            let start = self.pos;
+            while self.peek().is_some_and(|c| c != b'}') {
+                self.pos += 1;
+            }
+            let path = self.text(start, self.pos);
+            self.expect(b'}', "`}`")?;
+            Ok(Reference::Path(path))
+        } else {
+            let start = self.pos;
+            while self.peek().is_some_and(Self::is_label_char) {
+                self.pos += 1;
+            }
+            if start == self.pos {
+                return Err(self.unexpected("a label"));
+            }
+            Ok(Reference::Label(self.text(start, self.pos)))
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Tree structure
+    // -----------------------------------------------------------------------
+
+    fn top_level(&mut self) -> Result<()> {
+        let pos = self.pos;
+        if self.eat_keyword("/dts-v1/") {
+            return self.expect(b';', "`;`");
+        }
+        if self.eat_keyword("/memreserve/") {
+            let address = self.integer_prim()?;
+            let size = self.integer_prim()?;
+            self.expect(b';', "`;`")?;
+            self.fdt
+                .memory_reservations
+                .push(MemoryReservation { address, size });
+            return Ok(());
+        }
+        if self.eat_keyword("/include/") {
+            return Err(self.error_at(pos, DtsErrorKind::Unsupported("/include/")));
+        }
+        if self.eat_keyword("/plugin/") {
+            return Err(self.error_at(pos, DtsErrorKind::Unsupported("/plugin/")));
+        }
+        if self.eat_keyword("/delete-node/") {
+            self.skip_ws();
+            let ref_pos = self.pos;
+            let reference = self.reference()?;
+            self.expect(b';', "`;`")?;
+            let id = self.lookup_at(&reference, ref_pos)?;
+            let parent = self
+                .fdt
+                .parent_of(id)
+                .ok_or_else(|| self.error_at(ref_pos, DtsErrorKind::DeleteRoot))?;
+            let name = self.fdt.node(id).unwrap().name.clone();
+            self.fdt.remove_node(parent, &name);
+            return Ok(());
+        }
+
+        let labels = self.labels();
+        self.skip_ws();
+        let node_pos = self.pos;
+        let id = match self.peek() {
+            Some(b'/') => {
+                self.pos += 1;
+                self.fdt.root_id()
+            }
+            Some(b'&') => {
+                let reference = self.reference()?;
+                self.lookup_at(&reference, node_pos)?
+            }
+            _ => return Err(self.unexpected("a node definition")),
+        };
+        self.add_labels(id, labels)?;
+
+        self.expect(b'{', "`{`")?;
+        self.node_body(id)?;
+        self.expect(b'}', "`}`")?;
+        self.expect(b';', "`;`")
+    }
+
+    fn node_body(&mut self, id: NodeId) -> Result<()> {
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                Some(b'}') => return Ok(()),
+                None => return Err(self.error(DtsErrorKind::UnexpectedEof)),
+                _ => {}
+            }
+
+            if self.eat_keyword("/delete-property/") {
+                let name = self.name()?;
+                self.expect(b';', "`;`")?;
+                self.fdt.node_mut(id).unwrap().remove_property(&name);
+                self.markers.remove(&(id, name));
+                continue;
+            }
+            if self.eat_keyword("/delete-node/") {
+                let name = self.name()?;
+                self.expect(b';', "`;`")?;
+                self.fdt.remove_node(id, &name);
+                continue;
+            }
+
+            let labels = self.labels();
+            let name = self.name()?;
+            self.skip_ws();
+            match self.peek() {
+                Some(b'{') => {
+                    self.pos += 1;
+                    let child = match self.fdt.node(id).unwrap().get_child(&name) {
+                        Some(child) => child,
+                        None => self.fdt.add_node(id, Node::new(&name)),
+                    };
+                    self.add_labels(child, labels)?;
+                    self.node_body(child)?;
+                    self.expect(b'}', "`}`")?;
+                    self.expect(b';', "`;`")?;
+                }
+                Some(b'=') => {
+                    self.pos += 1;
+                    let (data, markers) = self.property_value()?;
+                    self.expect(b';', "`;`")?;
+                    self.set_property(id, name, data, markers);
+                }
+                Some(b';') => {
+                    self.pos += 1;
+                    self.set_property(id, name, Vec::new(), Vec::new());
+                }
+                _ => return Err(self.unexpected("`{`, `=` or `;`")),
+            }
+        }
+    }
+
+    fn add_labels(&mut self, id: NodeId, labels: Vec<(String, usize)>) -> Result<()> {
+        for (label, pos) in labels {
+            if let Some(&existing) = self.labels.get(&label)
+                && existing != id
+                && self.fdt.node(existing).is_some()
+            {
+                return Err(self.error_at(pos, DtsErrorKind::DuplicateLabel(label)));
+            }
+            self.labels.insert(label, id);
+        }
+        Ok(())
+    }
+
+    fn set_property(&mut self, id: NodeId, name: String, data: Vec<u8>, markers: Vec<Marker>) {
+        self.fdt
+            .node_mut(id)
+            .unwrap()
+            .set_property(Property::new(&name, data));
+        if markers.is_empty() {
+            self.markers.remove(&(id, name));
+        } else {
+            self.markers.insert((id, name), markers);
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Property values
+    // -----------------------------------------------------------------------
+
+    fn property_value(&mut self) -> Result<(Vec<u8>, Vec<Marker>)> {
+        let mut data = Vec::new();
+        let mut markers = Vec::new();
+
+        loop {
+            self.labels();
+            self.skip_ws();
+            match self.peek() {
+                Some(b'"') => self.string(&mut data)?,
+                Some(b'<') => self.cells(32, &mut data, &mut markers)?,
+                Some(b'[') => self.byte_string(&mut data)?,
+                Some(b'&') => {
+                    let pos = self.pos;
+                    let target = self.reference()?;
+                    markers.push(Marker {
+                        offset: data.len(),
+                        kind: MarkerKind::Path,
+                        target,
+                        pos,
+                    });
+                }
+                Some(b'/') if self.eat_keyword("/bits/") => {
+                    let pos = self.pos;
+                    let bits = self.integer_prim()?;
+                    if !matches!(bits, 8 | 16 | 32 | 64) {
+                        return Err(self.error_at(pos, DtsErrorKind::InvalidBits(bits)));
+                    }
+                    self.skip_ws();
+                    self.cells(bits as u32, &mut data, &mut markers)?;
+                }
+                _ => return Err(self.unexpected("a property value")),
+            }
+            self.labels();
+            if !self.eat(b',') {
+                break;
+            }
+        }
+
+        Ok((data, markers))
+    }
+
+    fn string(&mut self, data: &mut Vec<u8>) -> Result<()> {
+        self.expect(b'"', "`\"`")?;
+        loop {
+            match self.bump() {
+                None => return Err(self.error(DtsErrorKind::UnexpectedEof)),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let c = self.escape()?;
+                    data.push(c);
+                }
+                Some(c) => data.push(c),
+            }
+        }
+        data.push(0);
+        Ok(())
+    }
+
+    /// Decodes the escape sequence following a backslash.
+    fn escape(&mut self) -> Result<u8> {
+        let pos = self.pos;
+        let c = self
+            .bump()
+            .ok_or_else(|| self.error(DtsErrorKind::UnexpectedEof))?;
+        Ok(match c {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            b'x' => {
+                let start = self.pos;
+                while self.pos - start < 2 && self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                    self.pos += 1;
+                }
+                let digits = core::str::from_utf8(&self.src[start..self.pos]).unwrap();
+                u8::from_str_radix(digits, 16)
+                    .map_err(|_| self.error_at(pos, DtsErrorKind::InvalidEscape))?
+            }
+            b'0'..=b'7' => {
+                let start = self.pos - 1;
+                while self.pos - start < 3
+                    && self.peek().is_some_and(|c| (b'0'..=b'7').contains(&c))
+                {
+                    self.pos += 1;
+                }
+                let digits = core::str::from_utf8(&self.src[start..self.pos]).unwrap();
+                u8::from_str_radix(digits, 8)
+                    .map_err(|_| self.error_at(pos, DtsErrorKind::InvalidEscape))?
+            }
+            c => c,
+        })
+    }
+
+    fn byte_string(&mut self, data: &mut Vec<u8>) -> Result<()> {
+        self.expect(b'[', "`[`")?;
+        loop {
+            self.labels();
+            self.skip_ws();
+            match (self.peek(), self.peek_at(1)) {
+                (Some(b']'), _) => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                (Some(hi), Some(lo)) if hi.is_ascii_hexdigit() && lo.is_ascii_hexdigit() => {
+                    let digits = core::str::from_utf8(&self.src[self.pos..self.pos + 2]).unwrap();
+                    data.push(u8::from_str_radix(digits, 16).unwrap());
+                    self.pos += 2;
+                }
+                _ => return Err(self.unexpected("a pair of hex digits or `]`")),
+            }
+        }
+    }
+
+    fn cells(&mut self, bits: u32, data: &mut Vec<u8>, markers: &mut Vec<Marker>) -> Result<()> {
+        self.expect(b'<', "`<`")?;
+        loop {
+            self.labels();
+            self.skip_ws();
+            match self.peek() {
+                Some(b'>') => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(b'&') => {
+                    let pos = self.pos;
+                    if bits != 32 {
+                        return Err(self.error(DtsErrorKind::ReferenceWidth));
+                    }
+                    let target = self.reference()?;
+                    markers.push(Marker {
+                        offset: data.len(),
+                        kind: MarkerKind::Phandle,
+                        target,
+                        pos,
+                    });
+                    data.extend_from_slice(&u32::MAX.to_be_bytes());
+                }
+                _ => {
+                    let pos = self.pos;
+                    let value = self.integer_prim()?;
+                    if bits < 64 {
+                        let mask = (1u64 << bits) - 1;
+                        if value & !mask != 0 && value | mask != u64::MAX {
+                            return Err(self.error_at(pos, DtsErrorKind::OutOfRange));
+                        }
+                    }
+                    let bytes = value.to_be_bytes();
+                    data.extend_from_slice(&bytes[8 - bits as usize / 8..]);
+                }
+            }
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Integers and expressions
+    // -----------------------------------------------------------------------
+
+    /// Parses a literal, a character literal or a parenthesized expression.
+    fn integer_prim(&mut self) -> Result<u64> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.expr()?;
+                self.expect(b')', "`)`")?;
+                Ok(value)
+            }
+            Some(b'\'') => {
+                self.pos += 1;
+                let value = match self.bump() {
+                    Some(b'\\') => self.escape()?,
+                    Some(c) => c,
+                    None => return Err(self.error(DtsErrorKind::UnexpectedEof)),
+                };
+                self.expect(b'\'', "`'`")?;
+                Ok(value as u64)
+            }
+            Some(c) if c.is_ascii_digit() => self.number(),
+            _ => Err(self.unexpected("an integer")),
+        }
+    }
+
+    fn number(&mut self) -> Result<u64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
+            self.pos += 1;
+        }
+        let text = core::str::from_utf8(&self.src[start..self.pos]).unwrap();
+        let digits = text.trim_end_matches(['u', 'U', 'l', 'L']);
+
+        let (digits, radix) = if let Some(hex) = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            (hex, 16)
+        } else if digits.len() > 1 && digits.starts_with('0') {
+            (&digits[1..], 8)
+        } else {
+            (digits, 10)
+        };
+
+        u64::from_str_radix(digits, radix).map_err(|e| {
+            let kind = match e.kind() {
+                core::num::IntErrorKind::PosOverflow => DtsErrorKind::OutOfRange,
+                _ => DtsErrorKind::InvalidNumber,
+            };
+            self.error_at(start, kind)
+        })
+    }
+
+    fn expr(&mut self) -> Result<u64> {
+        let cond = self.binary(0)?;
+        if self.eat(b'?') {
+            let if_true = self.expr()?;
+            self.expect(b':', "`:`")?;
+            let if_false = self.expr()?;
+            Ok(if cond != 0 { if_true } else { if_false })
+        } else {
+            Ok(cond)
+        }
+    }
+
+    /// Returns the binary operator at the current position and its precedence.
+    fn peek_operator(&mut self) -> Option<(&'static str, u8)> {
+        const OPERATORS: &[(&str, u8)] = &[
+            ("||", 1),
+            ("&&", 2),
+            ("==", 6),
+            ("!=", 6),
+            ("<=", 7),
+            (">=", 7),
+            ("<<", 8),
+            (">>", 8),
+            ("|", 3),
+            ("^", 4),
+            ("&", 5),
+            ("<", 7),
+            (">", 7),
+            ("+", 9),
+            ("-", 9),
+            ("*", 10),
+            ("/", 10),
+            ("%", 10),
+        ];
+        self.skip_ws();
+        OPERATORS
+            .iter()
+            .find(|(op, _)| self.src[self.pos..].starts_with(op.as_bytes()))
+            .copied()
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<u64> {
+        let mut lhs = self.unary()?;
+        while let Some((op, prec)) = self.peek_operator() {
+            if prec < min_prec {
+                break;
+            }
+            let pos = self.pos;
+            self.pos += op.len();
+            let rhs = self.binary(prec + 1)?;
+            lhs = match op {
+                "||" => (lhs != 0 || rhs != 0) as u64,
+                "&&" => (lhs != 0 && rhs != 0) as u64,
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "==" => (lhs == rhs) as u64,
+                "!=" => (lhs != rhs) as u64,
+                "<" => (lhs < rhs) as u64,
+                ">" => (lhs > rhs) as u64,
+                "<=" => (lhs <= rhs) as u64,
+                ">=" => (lhs >= rhs) as u64,
+                "<<" => lhs.checked_shl(rhs as u32).unwrap_or(0),
+                ">>" => lhs.checked_shr(rhs as u32).unwrap_or(0),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "*" => lhs.wrapping_mul(rhs),
+                "/" | "%" if rhs == 0 => {
+                    return Err(self.error_at(pos, DtsErrorKind::DivisionByZero));
+                }
+                "/" => lhs / rhs,
+                _ => lhs % rhs,
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<u64> {
+        self.skip_ws();
+        match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                Ok(self.unary()?.wrapping_neg())
+            }
+            Some(b'~') => {
+                self.pos += 1;
+                Ok(!self.unary()?)
+            }
+            Some(b'!') => {
+                self.pos += 1;
+                Ok((self.unary()? == 0) as u64)
+            }
+            _ => self.integer_prim(),
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // Reference resolution
+    // -----------------------------------------------------------------------
+
+    fn lookup(&self, reference: &Reference) -> Option<NodeId> {
+        match reference {
+            Reference::Label(label) => self
+                .labels
+                .get(label)
+                .copied()
+                .filter(|&id| self.fdt.node(id).is_some()),
+            Reference::Path(path) => self.fdt.get_by_path_id(path),
+        }
+    }
+
+    fn lookup_at(&self, reference: &Reference, pos: usize) -> Result<NodeId> {
+        self.lookup(reference).ok_or_else(|| {
+            self.error_at(
+                pos,
+                DtsErrorKind::UnresolvedReference(reference.to_display()),
+            )
+        })
+    }
+
+    /// Replaces every reference marker with the target's phandle or path.
+    ///
+    /// Properties are visited in tree order so that phandle allocation is
+    /// deterministic.
+    fn resolve_references(&mut self) -> Result<()> {
+        let markers = core::mem::take(&mut self.markers);
+        if markers.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<NodeId> = self.fdt.iter_node_ids().collect();
+        let mut used: BTreeSet<u32> = ids
+            .iter()
+            .filter_map(|&id| self.fdt.node(id).unwrap().phandle())
+            .map(|p| p.raw())
+            .collect();
+        let mut next_phandle = 1;
+
+        for id in ids {
+            let names: Vec<String> = self
+                .fdt
+                .node(id)
+                .unwrap()
+                .properties()
+                .iter()
+                .map(|p| p.name.clone())
+                .collect();
+
+            for name in names {
+                let Some(list) = markers.get(&(id, name.clone())) else {
+                    continue;
+                };
+                let old = self.fdt.node(id).unwrap().get_property(&name).unwrap();
+                let old = old.data.clone();
+                let mut data = Vec::with_capacity(old.len());
+                let mut last = 0;
+
+                for marker in list {
+                    let target = self.lookup_at(&marker.target, marker.pos)?;
+                    data.extend_from_slice(&old[last..marker.offset]);
+                    match marker.kind {
+                        MarkerKind::Path => {
+                            data.extend_from_slice(self.fdt.path_of(target).as_bytes());
+                            data.push(0);
+                            last = marker.offset;
+                        }
+                        MarkerKind::Phandle => {
+                            let phandle = self.phandle_of(target, &mut used, &mut next_phandle);
+                            data.extend_from_slice(&phandle.to_be_bytes());
+                            last = marker.offset + 4;
+                        }
+                    }
+                }
+                data.extend_from_slice(&old[last..]);
+
+                self.fdt
+                    .node_mut(id)
+                    .unwrap()
+                    .set_property(Property::new(&name, data));
+            }
+        }
+
+        self.fdt.rebuild_phandle_cache();
+        Ok(())
+    }
+
+    /// Returns the phandle of `id`, allocating the lowest free one if needed.
+    fn phandle_of(&mut self, id: NodeId, used: &mut BTreeSet<u32>, next: &mut u32) -> u32 {
+        let node = self.fdt.node_mut(id).unwrap();
+        if let Some(phandle) = node.phandle() {
+            return phandle.raw();
+        }
+        while used.contains(next) {
+            *next += 1;
+        }
+        let phandle = *next;
+        used.insert(phandle);
+        node.set_property(Property::new("phandle", phandle.to_be_bytes().to_vec()));
+        phandle
+    }
+}
@@ -0,0 +1,365 @@
+//! DTS source writer.
+//!
+//! Produces source text that [`Fdt::from_dts`] and `dtc` compile back to the
+//! same tree: every property keeps its exact bytes and position, `phandle`
+//! properties are always written out, and references are only used where
+//! they expand to the value they replace.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use fdt_raw::Phandle;
+
+use crate::{Fdt, NodeId, Property};
+
+/// Where node labels in the generated source come from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelStyle {
+    /// Emit no labels; references are written as `&{/path}`.
+    None,
+    /// Use the labels recorded in `/__symbols__`.
+    #[default]
+    Symbols,
+    /// Use `/__symbols__`, and derive a label from the node name for every
+    /// other node that has a phandle.
+    NodeNames,
+}
+
+/// Options for [`Fdt::to_dts_with`].
+#[derive(Clone, Debug)]
+pub struct DtsOptions {
+    /// How to label nodes.
+    pub labels: LabelStyle,
+    /// Render the phandles in `clocks`, `interrupt-parent` and
+    /// `interrupts-extended` as `&label` references.
+    pub phandle_refs: bool,
+    /// Print cells as hexadecimal (`0x1f`) instead of decimal (`31`).
+    pub hex: bool,
+}
+
+impl Default for DtsOptions {
+    fn default() -> Self {
+        Self {
+            labels: LabelStyle::Symbols,
+            phandle_refs: true,
+            hex: true,
+        }
+    }
+}
+
+impl Fdt {
+    /// Converts the tree to device tree source using the default [`DtsOptions`].
+    pub fn to_dts(&self) -> String {
+        self.to_dts_with(&DtsOptions::default())
+    }
+
+    /// Converts the tree to device tree source.
+    ///
+    /// The output starts with `/dts-v1/;` and one `/memreserve/` line per
+    /// entry of [`Fdt::memory_reservations`]. Compiling it again yields the
+    /// same properties, byte for byte, in the same order.
+    pub fn to_dts_with(&self, options: &DtsOptions) -> String {
+        let writer = Writer {
+            fdt: self,
+            options,
+            labels: self.dts_labels(options.labels),
+        };
+
+        let mut out = String::new();
+        out.push_str("/dts-v1/;\n\n");
+        for rsv in &self.memory_reservations {
+            let _ = writeln!(out, "/memreserve/ {:#x} {:#x};", rsv.address, rsv.size);
+        }
+        if !self.memory_reservations.is_empty() {
+            out.push('\n');
+        }
+        writer.write_node(&mut out, self.root_id(), 0);
+        out
+    }
+
+    /// Collects the labels to put on each node.
+    fn dts_labels(&self, style: LabelStyle) -> BTreeMap<NodeId, Vec<String>> {
+        let mut labels: BTreeMap<NodeId, Vec<String>> = BTreeMap::new();
+        if style == LabelStyle::None {
+            return labels;
+        }
+
+        let mut used = BTreeSet::new();
+        if let Some(symbols) = self
+            .get_by_path_id("/__symbols__")
+            .and_then(|id| self.node(id))
+        {
+            for prop in symbols.properties() {
+                if !is_label(&prop.name) {
+                    continue;
+                }
+                let Some(id) = prop.as_str().and_then(|path| self.get_by_path_id(path)) else {
+                    continue;
+                };
+                used.insert(prop.name.clone());
+                labels.entry(id).or_default().push(prop.name.clone());
+            }
+        }
+
+        if style == LabelStyle::NodeNames {
+            for id in self.iter_node_ids() {
+                let node = self.node(id).unwrap();
+                if node.phandle().is_none() || labels.contains_key(&id) {
+                    continue;
+                }
+                let base = label_from_name(&node.name);
+                let mut label = base.clone();
+                let mut n = 1;
+                while used.contains(&label) {
+                    label = format!("{}_{}", base, n);
+                    n += 1;
+                }
+                used.insert(label.clone());
+                labels.insert(id, vec![label]);
+            }
+        }
+
+        labels
+    }
+}
+
+fn is_label(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Turns a node name such as `serial@7e201000` into `serial_7e201000`.
+fn label_from_name(name: &str) -> String {
+    let mut label: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !label.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        label.insert(0, '_');
+    }
+    label
+}
+
+/// One element of a rendered cell array.
+enum Cell {
+    Value(u32),
+    Ref(Phandle),
+}
+
+struct Writer<'a> {
+    fdt: &'a Fdt,
+    options: &'a DtsOptions,
+    labels: BTreeMap<NodeId, Vec<String>>,
+}
+
+impl Writer<'_> {
+    fn write_node(&self, out: &mut String, id: NodeId, depth: usize) {
+        let node = self.fdt.node(id).unwrap();
+        indent(out, depth);
+        if let Some(labels) = self.labels.get(&id) {
+            for label in labels {
+                let _ = write!(out, "{}: ", label);
+            }
+        }
+        if id == self.fdt.root_id() {
+            out.push_str("/ {\n");
+        } else {
+            let _ = writeln!(out, "{} {{", node.name);
+        }
+
+        for prop in node.properties() {
+            indent(out, depth + 1);
+            self.write_property(out, id, prop);
+            out.push_str(";\n");
+        }
+
+        for &child in node.children() {
+            out.push('\n');
+            self.write_node(out, child, depth + 1);
+        }
+
+        indent(out, depth);
+        out.push_str("};\n");
+    }
+
+    fn write_property(&self, out: &mut String, id: NodeId, prop: &Property) {
+        out.push_str(&prop.name);
+        if prop.data.is_empty() {
+            return;
+        }
+        out.push_str(" = ");
+
+        if self.options.phandle_refs
+            && let Some(groups) = self.reference_groups(id, prop)
+        {
+            for (i, group) in groups.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                self.write_cells(out, group);
+            }
+            return;
+        }
+
+        if let Some(strings) = as_string_list(&prop.data) {
+            for (i, s) in strings.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                write_string(out, s);
+            }
+        } else if prop.data.len().is_multiple_of(4) {
+            let cells: Vec<Cell> = prop.get_u32_iter().map(Cell::Value).collect();
+            self.write_cells(out, &cells);
+        } else {
+            out.push('[');
+            for (i, b) in prop.data.iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                }
+                let _ = write!(out, "{:02x}", b);
+            }
+            out.push(']');
+        }
+    }
+
+    fn write_cells(&self, out: &mut String, cells: &[Cell]) {
+        out.push('<');
+        for (i, cell) in cells.iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            match cell {
+                Cell::Value(v) if self.options.hex => {
+                    let _ = write!(out, "{:#x}", v);
+                }
+                Cell::Value(v) => {
+                    let _ = write!(out, "{}", v);
+                }
+                Cell::Ref(phandle) => self.write_reference(out, *phandle),
+            }
+        }
+        out.push('>');
+    }
+
+    fn write_reference(&self, out: &mut String, phandle: Phandle) {
+        let id = self.fdt.get_by_phandle_id(phandle).unwrap();
+        match self.labels.get(&id).and_then(|labels| labels.first()) {
+            Some(label) => {
+                let _ = write!(out, "&{}", label);
+            }
+            None => {
+                let _ = write!(out, "&{{{}}}", self.fdt.path_of(id));
+            }
+        }
+    }
+
+    /// Splits a phandle-carrying property into `<&ref specifier...>` groups.
+    ///
+    /// Returns `None` when the property is not one we know how to decode, or
+    /// when decoding does not account for every cell or hits an unknown
+    /// phandle; the caller then prints the raw cells.
+    fn reference_groups(&self, id: NodeId, prop: &Property) -> Option<Vec<Vec<Cell>>> {
+        if !prop.data.len().is_multiple_of(4) {
+            return None;
+        }
+        let total = prop.data.len() / 4;
+
+        let groups: Vec<(Phandle, Vec<u32>)> = match prop.name.as_str() {
+            "interrupt-parent" => vec![(prop.get_u32().map(Phandle::from)?, Vec::new())],
+            "clocks" => self
+                .fdt
+                .view_typed(id)?
+                .clocks()
+                .into_iter()
+                .map(|clock| (clock.phandle, clock.specifier))
+                .collect(),
+            "interrupts-extended" => self.interrupts_extended(prop)?,
+            _ => return None,
+        };
+
+        let used: usize = groups.iter().map(|(_, spec)| 1 + spec.len()).sum();
+        if used != total
+            || groups
+                .iter()
+                .any(|(phandle, _)| self.fdt.get_by_phandle_id(*phandle).is_none())
+        {
+            return None;
+        }
+
+        Some(
+            groups
+                .into_iter()
+                .map(|(phandle, specifier)| {
+                    let mut cells = vec![Cell::Ref(phandle)];
+                    cells.extend(specifier.into_iter().map(Cell::Value));
+                    cells
+                })
+                .collect(),
+        )
+    }
+
+    /// Decodes `interrupts-extended` using each controller's `#interrupt-cells`.
+    fn interrupts_extended(&self, prop: &Property) -> Option<Vec<(Phandle, Vec<u32>)>> {
+        let mut reader = prop.as_reader();
+        let mut groups = Vec::new();
+        while let Some(raw) = reader.read_u32() {
+            let phandle = Phandle::from(raw);
+            let cells = self
+                .fdt
+                .get_by_phandle(phandle)?
+                .as_node()
+                .interrupt_cells()?;
+            let mut specifier = Vec::with_capacity(cells as usize);
+            for _ in 0..cells {
+                specifier.push(reader.read_u32()?);
+            }
+            groups.push((phandle, specifier));
+        }
+        Some(groups)
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push('\t');
+    }
+}
+
+/// Splits `data` into strings if it looks like a NUL-terminated string list.
+pub(crate) fn as_string_list(data: &[u8]) -> Option<Vec<&[u8]>> {
+    let body = data.strip_suffix(&[0])?;
+    let strings: Vec<&[u8]> = body.split(|&b| b == 0).collect();
+    let printable = |b: &u8| (0x20..0x7f).contains(b) || matches!(b, b'\t' | b'\n' | b'\r');
+    if strings
+        .iter()
+        .all(|s| !s.is_empty() && s.iter().all(printable))
+    {
+        Some(strings)
+    } else {
+        None
+    }
+}
+
+/// Writes `s` as a quoted DTS string literal.
+pub(crate) fn write_string(out: &mut String, s: &[u8]) {
+    out.push('"');
+    for &b in s {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\t' => out.push_str("\\t"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            _ => out.push(b as char),
+        }
+    }
+    out.push('"');
+}
@@ -1,14 +1,18 @@
 //! FDT 编码模块
 //!
-//! 将 Fdt 结构序列化为 DTB 二进制格式
+//! 将 Fdt 结构序列化为 DTB 二进制格式。布局、字符串表和尾部空间可以通过
+//! [`EncodeOptions`] 调整。
 
-use alloc::{string::String, vec::Vec};
+use alloc::vec::Vec;
 use core::ops::Deref;
 
 use fdt_raw::{FDT_MAGIC, Token};
 
 use crate::{Fdt, NodeId};
 
+/// header 之后、内存保留块之前的偏移，v16 的 36 字节 header 也按 8 字节对齐到这里
+const OFF_MEM_RSVMAP: u32 = 40;
+
 /// FDT 二进制数据
 #[derive(Clone, Debug)]
 pub struct FdtData(Vec<u32>);
@@ -44,55 +48,263 @@ impl AsRef<[u8]> for FdtData {
     }
 }
 
+/// 字符串块的去重方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StringDedup {
+    /// 相同的属性名只保存一次，按首次出现的顺序排列（与 dtc 的输出一致）
+    #[default]
+    Exact,
+    /// 在 `Exact` 的基础上合并后缀：若一个名称是另一个名称的后缀，
+    /// 则直接指向后者的尾部，例如 `cells` 复用 `#size-cells`
+    SuffixMerge,
+}
+
+/// 生成的 DTB 版本
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FdtVersion {
+    /// 版本 16，header 中没有 `size_dt_struct`
+    V16,
+    /// 版本 17
+    #[default]
+    V17,
+}
+
+impl FdtVersion {
+    fn number(self) -> u32 {
+        match self {
+            FdtVersion::V16 => 16,
+            FdtVersion::V17 => 17,
+        }
+    }
+}
+
+/// 结构块与字符串块的排列顺序，内存保留块总是紧跟 header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlockOrder {
+    /// 先结构块后字符串块（dtc 和 libfdt 的布局）
+    #[default]
+    StructFirst,
+    /// 先字符串块后结构块，结构块可以直接向尾部空间增长
+    StringsFirst,
+}
+
+/// [`Fdt::encode_with`] 的编码选项
+///
+/// 默认值与 [`Fdt::encode`] 相同：版本 17、精确去重、没有尾部空间。
+///
+/// ```
+/// use fdt_edit::{EncodeOptions, Fdt, FdtVersion, StringDedup};
+///
+/// let fdt = Fdt::new();
+/// let options = EncodeOptions::new()
+///     .dedup(StringDedup::SuffixMerge)
+///     .version(FdtVersion::V16)
+///     .total_size(0x1000);
+/// let dtb = fdt.encode_with(&options);
+/// assert_eq!(dtb.len(), 0x1000);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    dedup: StringDedup,
+    version: FdtVersion,
+    order: BlockOrder,
+    padding: usize,
+    total_size: usize,
+}
+
+impl EncodeOptions {
+    /// 创建默认选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置字符串块的去重方式
+    pub fn dedup(mut self, dedup: StringDedup) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// 设置 DTB 版本
+    pub fn version(mut self, version: FdtVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// 设置数据块的排列顺序
+    pub fn block_order(mut self, order: BlockOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// 在最后一个数据块之后追加 `bytes` 字节的零填充（dtc `-p`），
+    /// 向上取整到 4 字节
+    pub fn padding(mut self, bytes: usize) -> Self {
+        self.padding = bytes;
+        self
+    }
+
+    /// 用零填充把 `totalsize` 补足到至少 `bytes` 字节（dtc `-S`），
+    /// 向上取整到 4 字节。内容已经更大时不截断
+    pub fn total_size(mut self, bytes: usize) -> Self {
+        self.total_size = bytes;
+        self
+    }
+}
+
+/// 属性名表，按首次出现的顺序记录不重复的名称
+///
+/// 查找使用 FNV-1a 哈希的开放寻址表，每个属性只需一次哈希和一次比较。
+struct StringTable<'a> {
+    names: Vec<&'a str>,
+    /// 槽位中保存 `names` 的下标，`EMPTY` 表示空槽
+    slots: Vec<u32>,
+}
+
+impl<'a> StringTable<'a> {
+    const EMPTY: u32 = u32::MAX;
+
+    fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            slots: vec![Self::EMPTY; 64],
+        }
+    }
+
+    /// 返回名称的下标，首次出现时加入表中
+    fn intern(&mut self, name: &'a str) -> u32 {
+        let mask = self.slots.len() - 1;
+        let mut slot = hash(name) as usize & mask;
+        loop {
+            match self.slots[slot] {
+                Self::EMPTY => break,
+                index if self.names[index as usize] == name => return index,
+                _ => slot = (slot + 1) & mask,
+            }
+        }
+
+        let index = self.names.len() as u32;
+        self.names.push(name);
+        self.slots[slot] = index;
+        // 负载超过一半时扩容
+        if self.names.len() * 2 > self.slots.len() {
+            self.grow();
+        }
+        index
+    }
+
+    fn grow(&mut self) {
+        let len = self.slots.len() * 2;
+        self.slots = vec![Self::EMPTY; len];
+        for (index, name) in self.names.iter().enumerate() {
+            let mut slot = hash(name) as usize & (len - 1);
+            while self.slots[slot] != Self::EMPTY {
+                slot = (slot + 1) & (len - 1);
+            }
+            self.slots[slot] = index as u32;
+        }
+    }
+
+    /// 生成字符串块，并返回每个名称（按下标）在块中的偏移量
+    fn layout(&self, dedup: StringDedup) -> (Vec<u8>, Vec<u32>) {
+        let mut data = Vec::new();
+        let mut offsets = vec![0; self.names.len()];
+        match dedup {
+            StringDedup::Exact => {
+                for (offset, name) in offsets.iter_mut().zip(&self.names) {
+                    *offset = data.len() as u32;
+                    push_string(&mut data, name);
+                }
+            }
+            StringDedup::SuffixMerge => {
+                // 按反转后的字节降序排列，后缀紧跟在包含它的较长名称之后
+                let mut order: Vec<usize> = (0..self.names.len()).collect();
+                order.sort_unstable_by(|&a, &b| {
+                    let (a, b) = (self.names[a].bytes().rev(), self.names[b].bytes().rev());
+                    b.cmp(a)
+                });
+                let mut last: Option<(&str, u32)> = None;
+                for index in order {
+                    let name = self.names[index];
+                    offsets[index] = match last {
+                        Some((kept, off)) if kept.ends_with(name) => {
+                            off + (kept.len() - name.len()) as u32
+                        }
+                        _ => {
+                            let off = data.len() as u32;
+                            push_string(&mut data, name);
+                            last = Some((name, off));
+                            off
+                        }
+                    };
+                }
+            }
+        }
+        (data, offsets)
+    }
+}
+
+/// FNV-1a 哈希
+fn hash(s: &str) -> u32 {
+    s.bytes()
+        .fold(0x811c_9dc5, |h, b| (h ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+/// 追加以 NUL 结尾的字符串
+fn push_string(data: &mut Vec<u8>, s: &str) {
+    data.extend_from_slice(s.as_bytes());
+    data.push(0);
+}
+
+/// 把字节按 4 字节对齐、零填充后追加为原始字序的字
+fn push_bytes(words: &mut Vec<u32>, bytes: &[u8]) {
+    let mut chunks = bytes.chunks_exact(4);
+    for chunk in &mut chunks {
+        words.push(u32::from_ne_bytes(chunk.try_into().unwrap()));
+    }
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        let mut last = [0u8; 4];
+        last[..rest.len()].copy_from_slice(rest);
+        words.push(u32::from_ne_bytes(last));
+    }
+}
+
 /// FDT 编码器
 pub struct FdtEncoder<'a> {
     fdt: &'a Fdt,
+    options: EncodeOptions,
     struct_data: Vec<u32>,
-    strings_data: Vec<u8>,
-    string_offsets: Vec<(String, u32)>,
+    strings: StringTable<'a>,
+    /// 结构块中 nameoff 字的位置，编码时先写入名称下标，最后替换为偏移量
+    nameoffs: Vec<usize>,
 }
 
 impl<'a> FdtEncoder<'a> {
     /// 创建新的编码器
     pub fn new(fdt: &'a Fdt) -> Self {
+        Self::with_options(fdt, EncodeOptions::default())
+    }
+
+    /// 使用指定选项创建编码器
+    pub fn with_options(fdt: &'a Fdt, options: EncodeOptions) -> Self {
         Self {
             fdt,
+            options,
             struct_data: Vec::new(),
-            strings_data: Vec::new(),
-            string_offsets: Vec::new(),
+            strings: StringTable::new(),
+            nameoffs: Vec::new(),
         }
     }
 
-    /// 获取或添加字符串，返回偏移量
-    fn get_or_add_string(&mut self, s: &str) -> u32 {
-        for (existing, offset) in &self.string_offsets {
-            if existing == s {
-                return *offset;
-            }
-        }
-
-        let offset = self.strings_data.len() as u32;
-        self.strings_data.extend_from_slice(s.as_bytes());
-        self.strings_data.push(0); // null terminator
-        self.string_offsets.push((s.into(), offset));
-        offset
-    }
-
     /// 写入 BEGIN_NODE token 和节点名
     fn write_begin_node(&mut self, name: &str) {
         let begin_token: u32 = Token::BeginNode.into();
         self.struct_data.push(begin_token.to_be());
 
-        let name_bytes = name.as_bytes();
-        let name_len = name_bytes.len() + 1; // +1 for null
-        let aligned_len = (name_len + 3) & !3;
-
-        let mut name_buf = vec![0u8; aligned_len];
-        name_buf[..name_bytes.len()].copy_from_slice(name_bytes);
-
-        for chunk in name_buf.chunks(4) {
-            let word = u32::from_ne_bytes(chunk.try_into().unwrap());
-            self.struct_data.push(word);
+        push_bytes(&mut self.struct_data, name.as_bytes());
+        // 名称长度恰好是 4 的倍数时，NUL 结尾需要单独一个字
+        if name.len().is_multiple_of(4) {
+            self.struct_data.push(0);
         }
     }
 
@@ -103,25 +315,17 @@ impl<'a> FdtEncoder<'a> {
     }
 
     /// 写入属性
-    fn write_property(&mut self, name: &str, data: &[u8]) {
+    fn write_property(&mut self, name: &'a str, data: &[u8]) {
         let prop_token: u32 = Token::Prop.into();
         self.struct_data.push(prop_token.to_be());
 
         self.struct_data.push((data.len() as u32).to_be());
 
-        let nameoff = self.get_or_add_string(name);
-        self.struct_data.push(nameoff.to_be());
-
-        if !data.is_empty() {
-            let aligned_len = (data.len() + 3) & !3;
-            let mut data_buf = vec![0u8; aligned_len];
-            data_buf[..data.len()].copy_from_slice(data);
+        let index = self.strings.intern(name);
+        self.nameoffs.push(self.struct_data.len());
+        self.struct_data.push(index);
 
-            for chunk in data_buf.chunks(4) {
-                let word = u32::from_ne_bytes(chunk.try_into().unwrap());
-                self.struct_data.push(word);
-            }
-        }
+        push_bytes(&mut self.struct_data, data);
     }
 
     /// 执行编码
@@ -138,7 +342,8 @@ impl<'a> FdtEncoder<'a> {
 
     /// 递归编码节点及其子节点（适配 arena 结构）
     fn encode_node(&mut self, id: NodeId) {
-        let node = match self.fdt.node(id) {
+        let fdt = self.fdt;
+        let node = match fdt.node(id) {
             Some(n) => n,
             None => return,
         };
@@ -161,20 +366,35 @@ impl<'a> FdtEncoder<'a> {
     }
 
     /// 生成最终 FDT 数据
-    fn finalize(self) -> FdtData {
+    fn finalize(mut self) -> FdtData {
+        let (strings_data, offsets) = self.strings.layout(self.options.dedup);
+        for &pos in &self.nameoffs {
+            let index = self.struct_data[pos] as usize;
+            self.struct_data[pos] = offsets[index].to_be();
+        }
+
         let memory_reservations = &self.fdt.memory_reservations;
         let boot_cpuid_phys = self.fdt.boot_cpuid_phys;
+        let options = self.options;
 
-        let header_size = 40u32; // 10 * 4 bytes
         let mem_rsv_size = ((memory_reservations.len() + 1) * 16) as u32;
         let struct_size = (self.struct_data.len() * 4) as u32;
-        let strings_size = self.strings_data.len() as u32;
-
-        let off_mem_rsvmap = header_size;
-        let off_dt_struct = off_mem_rsvmap + mem_rsv_size;
-        let off_dt_strings = off_dt_struct + struct_size;
-        let totalsize = off_dt_strings + strings_size;
-        let totalsize_aligned = (totalsize + 3) & !3;
+        let strings_size = strings_data.len() as u32;
+        let strings_aligned = (strings_size + 3) & !3;
+
+        let blocks_start = OFF_MEM_RSVMAP + mem_rsv_size;
+        let (off_dt_struct, off_dt_strings, content_end) = match options.order {
+            BlockOrder::StructFirst => {
+                let off_dt_strings = blocks_start + struct_size;
+                (blocks_start, off_dt_strings, off_dt_strings + strings_size)
+            }
+            BlockOrder::StringsFirst => {
+                let off_dt_struct = blocks_start + strings_aligned;
+                (off_dt_struct, blocks_start, off_dt_struct + struct_size)
+            }
+        };
+        let padded = (content_end as usize + options.padding).max(options.total_size);
+        let totalsize_aligned = ((padded + 3) & !3) as u32;
 
         let mut data = Vec::with_capacity(totalsize_aligned as usize / 4);
 
@@ -183,12 +403,15 @@ impl<'a> FdtEncoder<'a> {
         data.push(totalsize_aligned.to_be());
         data.push(off_dt_struct.to_be());
         data.push(off_dt_strings.to_be());
-        data.push(off_mem_rsvmap.to_be());
-        data.push(17u32.to_be()); // version
+        data.push(OFF_MEM_RSVMAP.to_be());
+        data.push(options.version.number().to_be()); // version
         data.push(16u32.to_be()); // last_comp_version
         data.push(boot_cpuid_phys.to_be());
         data.push(strings_size.to_be());
-        data.push(struct_size.to_be());
+        match options.version {
+            FdtVersion::V16 => data.push(0), // v16 header 只有 36 字节，这里是对齐填充
+            FdtVersion::V17 => data.push(struct_size.to_be()),
+        }
 
         // Memory reservation block
         for rsv in memory_reservations {
@@ -202,24 +425,23 @@ impl<'a> FdtEncoder<'a> {
             data.push(size_lo.to_be());
         }
         // Terminator
-        data.push(0);
-        data.push(0);
-        data.push(0);
-        data.push(0);
-
-        // Struct block
-        data.extend_from_slice(&self.struct_data);
-
-        // Strings block
-        let strings_aligned_len = (self.strings_data.len() + 3) & !3;
-        let mut strings_buf = vec![0u8; strings_aligned_len];
-        strings_buf[..self.strings_data.len()].copy_from_slice(&self.strings_data);
-
-        for chunk in strings_buf.chunks(4) {
-            let word = u32::from_ne_bytes(chunk.try_into().unwrap());
-            data.push(word);
+        data.extend_from_slice(&[0; 4]);
+
+        // Struct and strings blocks
+        match options.order {
+            BlockOrder::StructFirst => {
+                data.extend_from_slice(&self.struct_data);
+                push_bytes(&mut data, &strings_data);
+            }
+            BlockOrder::StringsFirst => {
+                push_bytes(&mut data, &strings_data);
+                data.extend_from_slice(&self.struct_data);
+            }
         }
 
+        // 尾部空间
+        data.resize(totalsize_aligned as usize / 4, 0);
+
         FdtData(data)
     }
 }
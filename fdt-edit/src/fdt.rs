@@ -14,7 +14,8 @@ use alloc::{
 };
 
 use crate::{
-    FdtData, FdtEncoder, FdtError, Node, NodeId, NodeType, NodeTypeMut, NodeView, Phandle,
+    EncodeOptions, FdtData, FdtEncoder, FdtError, Node, NodeId, NodeType, NodeTypeMut, NodeView,
+    Phandle,
 };
 
 pub use fdt_raw::MemoryReservation;
@@ -216,6 +217,28 @@ impl Fdt {
         self.phandle_cache.get(&phandle).copied()
     }
 
+    /// Returns the largest phandle value used in the tree, or 0 if none.
+    pub fn max_phandle(&self) -> u32 {
+        self.phandle_cache
+            .keys()
+            .next_back()
+            .map(|p| p.raw())
+            .unwrap_or(0)
+    }
+
+    /// Rebuilds the phandle lookup cache from the `phandle` properties of all nodes.
+    ///
+    /// Call this after editing `phandle` properties directly through
+    /// [`Fdt::node_mut`], which bypasses the cache.
+    pub fn rebuild_phandle_cache(&mut self) {
+        self.phandle_cache.clear();
+        for (&id, node) in &self.nodes {
+            if let Some(phandle) = node.phandle() {
+                self.phandle_cache.insert(phandle, id);
+            }
+        }
+    }
+
     /// Computes the full path string for a node by walking up parent links.
     pub fn path_of(&self, id: NodeId) -> String {
         let mut parts: Vec<&str> = Vec::new();
@@ -304,7 +327,8 @@ impl Fdt {
         // We use a stack of (NodeId, level) to find parents.
         let mut id_stack: Vec<(NodeId, usize)> = Vec::new();
 
-        for raw_node in raw_fdt.all_nodes() {
+        let mut raw_nodes = raw_fdt.all_nodes();
+        for raw_node in raw_nodes.by_ref() {
             let level = raw_node.level();
             let node = Node::from(&raw_node);
             let node_name = node.name.clone();
@@ -341,6 +365,11 @@ impl Fdt {
             id_stack.push((node_id, level));
         }
 
+        // A truncated or malformed structure block must not load as a partial tree
+        if let Some(err) = raw_nodes.error() {
+            return Err(err.clone());
+        }
+
         Ok(fdt)
     }
 
@@ -408,6 +437,11 @@ impl Fdt {
     pub fn encode(&self) -> FdtData {
         FdtEncoder::new(self).encode()
     }
+
+    /// Encodes the FDT to DTB binary format with the given [`EncodeOptions`].
+    pub fn encode_with(&self, options: &EncodeOptions) -> FdtData {
+        FdtEncoder::with_options(self, *options).encode()
+    }
 }
 
 /// Depth-first iterator over all node IDs in the tree.
@@ -3,18 +3,32 @@
 #[macro_use]
 extern crate alloc;
 
+mod clock_tree;
+mod diff;
+mod dts;
 mod encode;
 mod fdt;
+pub mod lint;
 mod node;
+mod overlay;
 mod prop;
+mod reserved;
 
-pub use fdt_raw::{FdtError, MemoryRegion, Phandle, RegInfo, Status, data::Reader};
+pub use fdt_raw::{
+    CpuMapEntry, EnableMethod, FdtError, MemoryRegion, Parity, Phandle, RegInfo, Status,
+    StdoutOptions, TranslateError, data::Reader,
+};
 
 /// A unique identifier for a node in the `Fdt` arena.
 pub type NodeId = usize;
 
-pub use encode::{FdtData, FdtEncoder};
+pub use clock_tree::{ClockError, ClockLink, ClockOutput, ClockTree, DanglingClock};
+pub use diff::{NodeMove, PatchError, PropCell, PropValue, PropertyChange, TreeDiff, diff};
+pub use dts::{DtsError, DtsErrorKind, DtsOptions, LabelStyle};
+pub use encode::{BlockOrder, EncodeOptions, FdtData, FdtEncoder, FdtVersion, StringDedup};
 pub use fdt::*;
 pub use node::view::*;
 pub use node::*;
+pub use overlay::OverlayError;
 pub use prop::*;
+pub use reserved::{DEFAULT_RESERVED_ALIGNMENT, ReservedMemoryError, ReservedMemoryPlacement};
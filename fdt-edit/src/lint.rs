@@ -0,0 +1,494 @@
+//! Structural checks for device trees.
+//!
+//! A [`Linter`] walks an [`Fdt`] and reports [`Diagnostic`]s for common
+//! mistakes, in the spirit of `dtc`'s warnings. Every [`Check`] can be turned
+//! on or off individually, like `dtc -W<name>` / `-Wno-<name>`:
+//!
+//! ```
+//! use fdt_edit::{Fdt, lint::{Check, Linter}};
+//!
+//! let fdt = Fdt::from_dts("/dts-v1/; / { serial@1000 { }; };").unwrap();
+//!
+//! let diagnostics = Linter::new().run(&fdt);
+//! assert_eq!(diagnostics[0].check, Check::UnitAddress);
+//! assert_eq!(diagnostics[0].path, "/serial@1000");
+//!
+//! let diagnostics = Linter::new().disable(Check::UnitAddress).run(&fdt);
+//! assert!(diagnostics.is_empty());
+//! ```
+
+use core::fmt;
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::{Fdt, Node, NodeId, Phandle, Property};
+
+/// A single lint check.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Check {
+    /// `reg` length is not a multiple of the parent's
+    /// `#address-cells + #size-cells`.
+    RegFormat,
+    /// The unit address in the node name does not match the first `reg`
+    /// entry, or one of them is missing.
+    UnitAddress,
+    /// `clocks`, `interrupt-parent` or `interrupts-extended` references a
+    /// phandle that no node has.
+    DanglingPhandle,
+    /// Two nodes carry the same `phandle` value.
+    DuplicatePhandle,
+    /// A `clocks` entry does not fit the provider's `#clock-cells`.
+    ClockCells,
+    /// Two entries of a `ranges` property overlap in the child address space.
+    RangesOverlap,
+    /// An interrupt controller or nexus has no `#interrupt-cells`.
+    InterruptCells,
+}
+
+impl Check {
+    /// All checks, in the order they run.
+    pub const ALL: [Check; 7] = [
+        Check::RegFormat,
+        Check::UnitAddress,
+        Check::DanglingPhandle,
+        Check::DuplicatePhandle,
+        Check::ClockCells,
+        Check::RangesOverlap,
+        Check::InterruptCells,
+    ];
+
+    /// Returns the check's name, as used by `dtc -W` where an equivalent exists.
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::RegFormat => "reg_format",
+            Check::UnitAddress => "unit_address_vs_reg",
+            Check::DanglingPhandle => "phandle_references",
+            Check::DuplicatePhandle => "duplicate_phandle",
+            Check::ClockCells => "clocks_property",
+            Check::RangesOverlap => "ranges_overlap",
+            Check::InterruptCells => "interrupt_provider",
+        }
+    }
+
+    /// Looks up a check by its [`name`](Check::name).
+    pub fn from_name(name: &str) -> Option<Check> {
+        Check::ALL.into_iter().find(|check| check.name() == name)
+    }
+
+    fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A problem found by a [`Check`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The check that produced this diagnostic
+    pub check: Check,
+    /// Full path of the offending node
+    pub path: String,
+    /// Human readable description
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.path, self.message, self.check)
+    }
+}
+
+/// Runs a configurable set of [`Check`]s over a tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Linter {
+    enabled: u32,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Linter {
+    /// Creates a linter with every check enabled.
+    pub fn new() -> Self {
+        Self {
+            enabled: Check::ALL.iter().fold(0, |acc, c| acc | c.bit()),
+        }
+    }
+
+    /// Creates a linter with every check disabled.
+    pub fn none() -> Self {
+        Self { enabled: 0 }
+    }
+
+    /// Enables a check.
+    pub fn enable(mut self, check: Check) -> Self {
+        self.enabled |= check.bit();
+        self
+    }
+
+    /// Disables a check.
+    pub fn disable(mut self, check: Check) -> Self {
+        self.enabled &= !check.bit();
+        self
+    }
+
+    /// Returns true if `check` will run.
+    pub fn is_enabled(&self, check: Check) -> bool {
+        self.enabled & check.bit() != 0
+    }
+
+    /// Checks every node of `fdt` and returns the problems found, in tree order.
+    pub fn run(&self, fdt: &Fdt) -> Vec<Diagnostic> {
+        let mut lint = Lint {
+            fdt,
+            diagnostics: Vec::new(),
+            phandles: BTreeMap::new(),
+        };
+
+        for id in fdt.iter_node_ids() {
+            if lint.is_generated(id) {
+                continue;
+            }
+            for check in Check::ALL {
+                if self.is_enabled(check) {
+                    lint.check_node(check, id);
+                }
+            }
+        }
+
+        lint.diagnostics
+    }
+}
+
+impl Fdt {
+    /// Runs every [`Check`] over the tree.
+    ///
+    /// Use [`Linter`] to select which checks run.
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        Linter::new().run(self)
+    }
+}
+
+struct Lint<'a> {
+    fdt: &'a Fdt,
+    diagnostics: Vec<Diagnostic>,
+    /// First node seen with each phandle value
+    phandles: BTreeMap<u32, NodeId>,
+}
+
+impl Lint<'_> {
+    fn report(&mut self, check: Check, id: NodeId, message: String) {
+        self.diagnostics.push(Diagnostic {
+            check,
+            path: self.fdt.path_of(id),
+            message,
+        });
+    }
+
+    /// Returns true for `__symbols__`, `__fixups__` and `__local_fixups__`
+    /// (and their children), whose property names are labels rather than
+    /// real properties.
+    fn is_generated(&self, id: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let name = &self.node(id).name;
+            if name.len() > 4 && name.starts_with("__") && name.ends_with("__") {
+                return true;
+            }
+            current = self.fdt.parent_of(id);
+        }
+        false
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        self.fdt.node(id).unwrap()
+    }
+
+    /// Returns the parent's `(#address-cells, #size-cells)`, defaulting to (2, 1).
+    fn parent_cells(&self, id: NodeId) -> (u32, u32) {
+        self.fdt
+            .parent_of(id)
+            .and_then(|parent| self.fdt.node(parent))
+            .map(|parent| {
+                (
+                    parent.address_cells().unwrap_or(2),
+                    parent.size_cells().unwrap_or(1),
+                )
+            })
+            .unwrap_or((2, 1))
+    }
+
+    fn check_node(&mut self, check: Check, id: NodeId) {
+        match check {
+            Check::RegFormat => self.check_reg_format(id),
+            Check::UnitAddress => self.check_unit_address(id),
+            Check::DanglingPhandle => self.check_dangling_phandles(id),
+            Check::DuplicatePhandle => self.check_duplicate_phandle(id),
+            Check::ClockCells => self.check_clock_cells(id),
+            Check::RangesOverlap => self.check_ranges_overlap(id),
+            Check::InterruptCells => self.check_interrupt_cells(id),
+        }
+    }
+
+    fn check_reg_format(&mut self, id: NodeId) {
+        let Some(reg) = self.node(id).get_property("reg") else {
+            return;
+        };
+        let len = reg.data.len();
+        let (address_cells, size_cells) = self.parent_cells(id);
+        let entry = (address_cells + size_cells) as usize * 4;
+
+        if len == 0 || entry == 0 || len % entry != 0 {
+            self.report(
+                Check::RegFormat,
+                id,
+                format!(
+                    "reg property has invalid length ({} bytes) (#address-cells == {}, #size-cells == {})",
+                    len, address_cells, size_cells
+                ),
+            );
+        }
+    }
+
+    fn check_unit_address(&mut self, id: NodeId) {
+        if id == self.fdt.root_id() {
+            return;
+        }
+        let node = self.node(id);
+        let unit = node.name.split_once('@').map(|(_, unit)| unit);
+        let reg = node.get_property("reg");
+        let ranges = node
+            .get_property("ranges")
+            .filter(|prop| !prop.data.is_empty());
+
+        let message = match (unit, reg, ranges) {
+            (None, Some(_), _) | (None, None, Some(_)) => {
+                "node has a reg or ranges property, but no unit address".into()
+            }
+            (Some(_), None, None) => {
+                "node has a unit address, but no reg or ranges property".into()
+            }
+            (Some(unit), Some(reg), _) => {
+                let (address_cells, _) = self.parent_cells(id);
+                // Multi-cell bus addresses (PCI, ...) use their own unit
+                // address formats, only plain 1 or 2 cell addresses are checked.
+                if unit.contains(',') || !(1..=2).contains(&address_cells) {
+                    return;
+                }
+                let Some(address) = reg.as_reader().read_cells(address_cells as usize) else {
+                    return;
+                };
+                let expected = format!("{:x}", address);
+                if unit == expected {
+                    return;
+                }
+                format!(
+                    "unit address `{}` does not match first reg address (expected `{}`)",
+                    unit, expected
+                )
+            }
+            _ => return,
+        };
+        self.report(Check::UnitAddress, id, message);
+    }
+
+    fn check_dangling_phandles(&mut self, id: NodeId) {
+        let node = self.node(id).clone();
+
+        if let Some(prop) = node.get_property("interrupt-parent") {
+            match prop.get_u32() {
+                Some(phandle) if self.fdt.get_by_phandle_id(Phandle::from(phandle)).is_some() => {}
+                Some(phandle) => self.report(
+                    Check::DanglingPhandle,
+                    id,
+                    format!("interrupt-parent references missing phandle {:#x}", phandle),
+                ),
+                None => self.report(
+                    Check::DanglingPhandle,
+                    id,
+                    "interrupt-parent is not a single cell".into(),
+                ),
+            }
+        }
+
+        for (name, cells_name) in [
+            ("clocks", "#clock-cells"),
+            ("interrupts-extended", "#interrupt-cells"),
+        ] {
+            if let Some(prop) = node.get_property(name) {
+                self.check_phandle_args(id, prop, cells_name);
+            }
+        }
+    }
+
+    /// Walks a `<&provider args...>` list and reports unknown providers.
+    ///
+    /// Stops at the first entry whose size cannot be determined.
+    fn check_phandle_args(&mut self, id: NodeId, prop: &Property, cells_name: &str) {
+        let mut reader = prop.as_reader();
+        let mut index = 0;
+        while let Some(phandle) = reader.read_u32() {
+            // 0 and -1 are placeholders for an absent provider.
+            if phandle == 0 || phandle == u32::MAX {
+                index += 1;
+                continue;
+            }
+            let Some(provider) = self.fdt.get_by_phandle_id(Phandle::from(phandle)) else {
+                self.report(
+                    Check::DanglingPhandle,
+                    id,
+                    format!(
+                        "{} entry {} references missing phandle {:#x}",
+                        prop.name, index, phandle
+                    ),
+                );
+                return;
+            };
+            let Some(cells) = self
+                .node(provider)
+                .get_property(cells_name)
+                .and_then(|p| p.get_u32())
+            else {
+                return;
+            };
+            if reader.read_cells(cells as usize).is_none() {
+                return;
+            }
+            index += 1;
+        }
+    }
+
+    fn check_duplicate_phandle(&mut self, id: NodeId) {
+        let Some(phandle) = self.node(id).phandle() else {
+            return;
+        };
+        match self.phandles.get(&phandle.raw()) {
+            Some(&first) => {
+                let first = self.fdt.path_of(first);
+                self.report(
+                    Check::DuplicatePhandle,
+                    id,
+                    format!(
+                        "duplicate phandle {:#x} (also used by {})",
+                        phandle.raw(),
+                        first
+                    ),
+                );
+            }
+            None => {
+                self.phandles.insert(phandle.raw(), id);
+            }
+        }
+    }
+
+    fn check_clock_cells(&mut self, id: NodeId) {
+        let Some(prop) = self.node(id).get_property("clocks") else {
+            return;
+        };
+        let total = prop.data.len() / 4;
+        let Some(view) = self.fdt.view_typed(id) else {
+            return;
+        };
+        let clocks = view.clocks();
+
+        let mut used = 0;
+        for clock in &clocks {
+            // Unknown providers are reported by `DanglingPhandle`.
+            let Some(provider) = self.fdt.get_by_phandle_id(clock.phandle) else {
+                return;
+            };
+            if self.node(provider).get_property("#clock-cells").is_none() {
+                let provider = self.fdt.path_of(provider);
+                self.report(
+                    Check::ClockCells,
+                    id,
+                    format!("clock provider {} has no #clock-cells", provider),
+                );
+                return;
+            }
+            used += 1 + clock.specifier.len();
+        }
+
+        if used != total || prop.data.len() % 4 != 0 {
+            self.report(
+                Check::ClockCells,
+                id,
+                format!(
+                    "clocks property has {} bytes, but the providers' #clock-cells account for {}",
+                    prop.data.len(),
+                    used * 4
+                ),
+            );
+        }
+    }
+
+    fn check_ranges_overlap(&mut self, id: NodeId) {
+        let node = self.node(id);
+        let Some(prop) = node.get_property("ranges") else {
+            return;
+        };
+        let child_cells = node.address_cells().unwrap_or(2) as usize;
+        let size_cells = node.size_cells().unwrap_or(1) as usize;
+        let (parent_cells, _) = self.parent_cells(id);
+        let entry_cells = child_cells + parent_cells as usize + size_cells;
+        if entry_cells == 0 || child_cells > 4 || size_cells > 4 {
+            return;
+        }
+
+        // Child addresses may be up to 4 cells wide (3 for PCI), keep them
+        // in a u128 so the full address is compared.
+        let cells: Vec<u32> = prop.get_u32_iter().collect();
+        let windows: Vec<(u128, u128)> = cells
+            .chunks_exact(entry_cells)
+            .map(|entry| {
+                let start = join_cells(&entry[..child_cells]);
+                let size = join_cells(&entry[entry_cells - size_cells..]);
+                (start, start.saturating_add(size))
+            })
+            .collect();
+
+        let mut overlaps = Vec::new();
+        for (i, a) in windows.iter().enumerate() {
+            for (j, b) in windows.iter().enumerate().skip(i + 1) {
+                if a.0 < b.1 && b.0 < a.1 {
+                    overlaps.push((i, j));
+                }
+            }
+        }
+        for (i, j) in overlaps {
+            self.report(
+                Check::RangesOverlap,
+                id,
+                format!(
+                    "ranges entries {} and {} overlap in the child address space",
+                    i, j
+                ),
+            );
+        }
+    }
+
+    fn check_interrupt_cells(&mut self, id: NodeId) {
+        let node = self.node(id);
+        let is_provider = node.get_property("interrupt-controller").is_some()
+            || node.get_property("interrupt-map").is_some();
+        if is_provider && node.interrupt_cells().is_none() {
+            self.report(
+                Check::InterruptCells,
+                id,
+                "interrupt provider has no #interrupt-cells".into(),
+            );
+        }
+    }
+}
+
+fn join_cells(cells: &[u32]) -> u128 {
+    cells.iter().fold(0, |acc, &c| (acc << 32) | c as u128)
+}
@@ -201,7 +201,20 @@ impl Node {
     ///
     /// Returns a vector of range entries mapping child bus addresses to parent bus addresses.
     pub fn ranges(&self, parent_address_cells: u32) -> Option<Vec<RangesEntry>> {
-        let prop = self.get_property("ranges")?;
+        self.ranges_property("ranges", parent_address_cells)
+    }
+
+    /// Parses the `dma-ranges` property, which maps DMA addresses of
+    /// child bus masters to parent bus addresses.
+    ///
+    /// An empty property means a 1:1 mapping and yields an empty vector.
+    pub fn dma_ranges(&self, parent_address_cells: u32) -> Option<Vec<RangesEntry>> {
+        self.ranges_property("dma-ranges", parent_address_cells)
+    }
+
+    /// Parses a `ranges`-style property of `(child, parent, length)` triples.
+    fn ranges_property(&self, name: &str, parent_address_cells: u32) -> Option<Vec<RangesEntry>> {
+        let prop = self.get_property(name)?;
         let mut entries = Vec::new();
         let mut reader = prop.as_reader();
 
@@ -254,12 +267,22 @@ impl Node {
         self.name.starts_with("memory")
     }
 
+    /// Returns true if this node is a CPU (`device_type = "cpu"`).
+    pub fn is_cpu(&self) -> bool {
+        self.device_type() == Some("cpu")
+    }
+
     /// Returns true if this node is an interrupt controller.
     pub fn is_interrupt_controller(&self) -> bool {
         self.name.starts_with("interrupt-controller")
             || self.get_property("interrupt-controller").is_some()
     }
 
+    /// Returns true if this node is a GPIO controller.
+    pub fn is_gpio_controller(&self) -> bool {
+        self.get_property("gpio-controller").is_some()
+    }
+
     /// Returns the `#interrupt-cells` property value.
     pub fn interrupt_cells(&self) -> Option<u32> {
         self.get_property("#interrupt-cells")
@@ -271,6 +294,35 @@ impl Node {
         self.get_property("#clock-cells").is_some()
     }
 
+    /// Returns true if this node is a power domain provider.
+    pub fn is_power_domain_provider(&self) -> bool {
+        self.get_property("#power-domain-cells").is_some()
+    }
+
+    /// Returns true if this node is a reset controller.
+    pub fn is_reset_controller(&self) -> bool {
+        self.get_property("#reset-cells").is_some()
+    }
+
+    /// Returns true if this node is a voltage or current regulator.
+    pub fn is_regulator(&self) -> bool {
+        self.get_property("regulator-name").is_some()
+            || self
+                .compatibles()
+                .any(|compatible| compatible.starts_with("regulator-"))
+    }
+
+    /// Returns true if this node is an `operating-points-v2` table.
+    pub fn is_opp_table(&self) -> bool {
+        self.compatibles()
+            .any(|compatible| compatible == "operating-points-v2")
+    }
+
+    /// Returns true if this node is a thermal zone.
+    pub fn is_thermal_zone(&self) -> bool {
+        self.get_property("thermal-sensors").is_some()
+    }
+
     /// Returns true if this node is a PCI bridge.
     pub fn is_pci(&self) -> bool {
         self.device_type() == Some("pci")
@@ -0,0 +1,314 @@
+//! `/chosen` node view specialization.
+
+use core::ops::{Deref, Range};
+
+use alloc::vec::Vec;
+use fdt_raw::{MemoryRegion, StdoutOptions};
+
+use super::NodeView;
+use crate::{Fdt, Node, NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+// ---------------------------------------------------------------------------
+// ChosenNodeView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for the `/chosen` node.
+///
+/// Address-sized properties (`linux,elfcorehdr`,
+/// `linux,usable-memory-range`) use the root `#address-cells` and
+/// `#size-cells`.
+#[derive(Clone, Copy)]
+pub struct ChosenNodeView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for ChosenNodeView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for ChosenNodeView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ChosenNodeView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if is_chosen(view) {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `bootargs` kernel command line.
+    pub fn bootargs(&self) -> Option<&'a str> {
+        self.prop("bootargs")?.as_str()
+    }
+
+    /// Returns the raw `stdout-path`, options included.
+    pub fn stdout_path(&self) -> Option<&'a str> {
+        self.prop("stdout-path")?.as_str()
+    }
+
+    /// Returns the serial options appended to `stdout-path`, such as
+    /// `115200n8`.
+    pub fn stdout_options(&self) -> Option<StdoutOptions> {
+        let (_, options) = self.stdout_path()?.split_once(':')?;
+        StdoutOptions::parse(options)
+    }
+
+    /// Returns the physical start address of the initrd.
+    pub fn initrd_start(&self) -> Option<u64> {
+        self.prop("linux,initrd-start").and_then(read_u32_or_u64)
+    }
+
+    /// Returns the physical end address (exclusive) of the initrd.
+    pub fn initrd_end(&self) -> Option<u64> {
+        self.prop("linux,initrd-end").and_then(read_u32_or_u64)
+    }
+
+    /// Returns the physical address range of the initrd.
+    pub fn initrd(&self) -> Option<Range<u64>> {
+        Some(self.initrd_start()?..self.initrd_end()?)
+    }
+
+    /// Returns the 64-bit `kaslr-seed`.
+    pub fn kaslr_seed(&self) -> Option<u64> {
+        self.prop("kaslr-seed")?.get_u64()
+    }
+
+    /// Returns the raw bytes of `rng-seed`.
+    pub fn rng_seed(&self) -> Option<&'a [u8]> {
+        Some(&self.prop("rng-seed")?.data)
+    }
+
+    /// Returns the ELF core header of the crashed kernel.
+    pub fn elfcorehdr(&self) -> Option<MemoryRegion> {
+        self.memory_ranges("linux,elfcorehdr").into_iter().next()
+    }
+
+    /// Returns the memory ranges a crash dump kernel may use.
+    pub fn usable_memory_range(&self) -> Vec<MemoryRegion> {
+        self.memory_ranges("linux,usable-memory-range")
+    }
+
+    /// Returns true if `linux,booted-from-kexec` is present.
+    pub fn booted_from_kexec(&self) -> bool {
+        self.prop("linux,booted-from-kexec").is_some()
+    }
+
+    /// Returns the FIT configuration U-Boot booted, from `u-boot,bootconf`.
+    pub fn bootconf(&self) -> Option<&'a str> {
+        self.prop("u-boot,bootconf")?.as_str()
+    }
+
+    fn prop(&self, name: &str) -> Option<&'a Property> {
+        self.as_view().as_node().get_property(name)
+    }
+
+    fn memory_ranges(&self, name: &str) -> Vec<MemoryRegion> {
+        let Some(prop) = self.prop(name) else {
+            return Vec::new();
+        };
+        let (address_cells, size_cells) = root_cells(self.as_view().fdt());
+        let mut regions = Vec::new();
+        // Zero-width pairs carry no data, so there are none to read
+        if address_cells + size_cells == 0 {
+            return regions;
+        }
+        let mut reader = prop.as_reader();
+        while let (Some(address), Some(size)) = (
+            reader.read_cells(address_cells),
+            reader.read_cells(size_cells),
+        ) {
+            regions.push(MemoryRegion { address, size });
+        }
+        regions
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ChosenNodeViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for the `/chosen` node.
+///
+/// Obtained with [`Fdt::chosen_mut`], which creates the node if needed.
+pub struct ChosenNodeViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for ChosenNodeViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for ChosenNodeViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for ChosenNodeViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        Self { inner: node }
+    }
+}
+
+impl<'a> ChosenNodeViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if is_chosen(view) {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sets the `bootargs` kernel command line.
+    pub fn set_bootargs(&mut self, bootargs: &str) {
+        self.set_string("bootargs", bootargs);
+    }
+
+    /// Sets `stdout-path`, appending serial options if given.
+    pub fn set_stdout_path(&mut self, path: &str, options: Option<StdoutOptions>) {
+        match options {
+            Some(options) => self.set_string("stdout-path", &format!("{path}:{options}")),
+            None => self.set_string("stdout-path", path),
+        }
+    }
+
+    /// Sets `linux,initrd-start` and `linux,initrd-end`.
+    ///
+    /// Like U-Boot, the values are written as single cells when the root
+    /// `#address-cells` is 1 and both fit in 32 bits, and as 64-bit values
+    /// otherwise.
+    pub fn set_initrd(&mut self, range: Range<u64>) {
+        let (address_cells, _) = root_cells(self.as_view().fdt());
+        let narrow = address_cells == 1 && u32::try_from(range.end).is_ok();
+        for (name, value) in [
+            ("linux,initrd-start", range.start),
+            ("linux,initrd-end", range.end),
+        ] {
+            let data = if narrow {
+                (value as u32).to_be_bytes().to_vec()
+            } else {
+                value.to_be_bytes().to_vec()
+            };
+            self.node_mut().set_property(Property::new(name, data));
+        }
+    }
+
+    /// Sets the 64-bit `kaslr-seed`.
+    pub fn set_kaslr_seed(&mut self, seed: u64) {
+        let data = seed.to_be_bytes().to_vec();
+        self.node_mut()
+            .set_property(Property::new("kaslr-seed", data));
+    }
+
+    /// Sets the `rng-seed` bytes.
+    pub fn set_rng_seed(&mut self, seed: &[u8]) {
+        self.node_mut()
+            .set_property(Property::new("rng-seed", seed.to_vec()));
+    }
+
+    /// Sets `linux,elfcorehdr`, using the root cell sizes.
+    pub fn set_elfcorehdr(&mut self, region: MemoryRegion) {
+        self.set_memory_ranges("linux,elfcorehdr", &[region]);
+    }
+
+    /// Sets `linux,usable-memory-range`, using the root cell sizes.
+    pub fn set_usable_memory_range(&mut self, regions: &[MemoryRegion]) {
+        self.set_memory_ranges("linux,usable-memory-range", regions);
+    }
+
+    /// Adds or removes the `linux,booted-from-kexec` flag.
+    pub fn set_booted_from_kexec(&mut self, booted: bool) {
+        if booted {
+            self.node_mut()
+                .set_property(Property::new("linux,booted-from-kexec", Vec::new()));
+        } else {
+            self.node_mut().remove_property("linux,booted-from-kexec");
+        }
+    }
+
+    /// Sets `u-boot,bootconf`.
+    pub fn set_bootconf(&mut self, config: &str) {
+        self.set_string("u-boot,bootconf", config);
+    }
+
+    fn node_mut(&mut self) -> &'a mut Node {
+        self.inner.inner.as_node_mut()
+    }
+
+    fn set_string(&mut self, name: &str, value: &str) {
+        let mut prop = Property::new(name, Vec::new());
+        prop.set_string(value);
+        self.node_mut().set_property(prop);
+    }
+
+    fn set_memory_ranges(&mut self, name: &str, regions: &[MemoryRegion]) {
+        let (address_cells, size_cells) = root_cells(self.as_view().fdt());
+        let mut data = Vec::new();
+        for region in regions {
+            push_cells(&mut data, region.address, address_cells);
+            push_cells(&mut data, region.size, size_cells);
+        }
+        self.node_mut().set_property(Property::new(name, data));
+    }
+}
+
+impl Fdt {
+    /// Returns the `/chosen` node, if present.
+    pub fn chosen(&self) -> Option<ChosenNodeView<'_>> {
+        let id = self.get_by_path_id("/chosen")?;
+        ChosenNodeView::try_from_view(NodeView::new(self, id))
+    }
+
+    /// Returns the `/chosen` node for editing, creating it if absent.
+    pub fn chosen_mut(&mut self) -> ChosenNodeViewMut<'_> {
+        let id = match self.get_by_path_id("/chosen") {
+            Some(id) => id,
+            None => self.add_node(self.root_id(), Node::new("chosen")),
+        };
+        ChosenNodeViewMut {
+            inner: NodeGenericMut {
+                inner: NodeView::new(self, id),
+            },
+        }
+    }
+}
+
+/// Returns true for the `chosen` child of the root node.
+fn is_chosen(view: NodeView<'_>) -> bool {
+    view.name() == "chosen" && view.fdt().parent_of(view.id()) == Some(view.fdt().root_id())
+}
+
+/// Returns the root node's `#address-cells` and `#size-cells` (defaults: 2, 1).
+fn root_cells(fdt: &Fdt) -> (usize, usize) {
+    let root = fdt.node(fdt.root_id());
+    let address_cells = root.and_then(|n| n.address_cells()).unwrap_or(2);
+    let size_cells = root.and_then(|n| n.size_cells()).unwrap_or(1);
+    (address_cells as usize, size_cells as usize)
+}
+
+/// Appends `value` as `cells` big-endian cells.
+fn push_cells(data: &mut Vec<u8>, value: u64, cells: usize) {
+    for i in (0..cells).rev() {
+        let cell = if i < 2 { (value >> (32 * i)) as u32 } else { 0 };
+        data.extend_from_slice(&cell.to_be_bytes());
+    }
+}
+
+fn read_u32_or_u64(prop: &Property) -> Option<u64> {
+    prop.get_u32().map(u64::from).or_else(|| prop.get_u64())
+}
@@ -17,6 +17,10 @@ use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
 pub enum ClockType {
     /// Fixed clock
     Fixed(FixedClock),
+    /// Fixed clock with an enable GPIO (`gated-fixed-clock`)
+    GatedFixed(FixedClock),
+    /// Parent clock multiplied and divided by constants (`fixed-factor-clock`)
+    FixedFactor(FixedFactorClock),
     /// Normal clock provider
     Normal,
 }
@@ -34,6 +38,20 @@ pub struct FixedClock {
     pub accuracy: Option<u32>,
 }
 
+/// Fixed-factor clock provider.
+///
+/// Its rate is the rate of its single parent in `clocks`, multiplied by
+/// `mult` and divided by `div`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FixedFactorClock {
+    /// Optional name for the clock
+    pub name: Option<String>,
+    /// Multiplier, from `clock-mult`
+    pub mult: u32,
+    /// Divider, from `clock-div`
+    pub div: u32,
+}
+
 /// Clock reference, used to parse clocks property.
 ///
 /// According to the device tree specification, the clocks property format is:
@@ -151,36 +169,33 @@ impl<'a> ClockNodeView<'a> {
         self.clock_output_names().get(index).cloned()
     }
 
-    /// Get the clock type (Fixed or Normal).
+    /// Get the clock type from the provider's `compatible`.
     pub fn clock_type(&self) -> ClockType {
         let node = self.as_view().as_node();
-
-        // Check if this is a fixed-clock
-        let is_fixed = node
-            .get_property("compatible")
-            .and_then(|prop| prop.as_str_iter().find(|&c| c == "fixed-clock"))
-            .is_some();
-
-        if is_fixed {
-            let frequency = node
-                .get_property("clock-frequency")
-                .and_then(|prop| prop.get_u32())
-                .unwrap_or(0);
-
-            let accuracy = node
-                .get_property("clock-accuracy")
-                .and_then(|prop| prop.get_u32());
-
-            let name = self.clock_output_names().first().cloned();
-
-            ClockType::Fixed(FixedClock {
-                name,
-                frequency,
-                accuracy,
-            })
-        } else {
-            ClockType::Normal
+        let get_u32 = |name: &str| node.get_property(name).and_then(|prop| prop.get_u32());
+        let name = self.clock_output_names().first().cloned();
+        let fixed = || FixedClock {
+            name: name.clone(),
+            frequency: get_u32("clock-frequency").unwrap_or(0),
+            accuracy: get_u32("clock-accuracy"),
+        };
+
+        for compatible in node.compatibles() {
+            match compatible {
+                "fixed-clock" => return ClockType::Fixed(fixed()),
+                "gated-fixed-clock" => return ClockType::GatedFixed(fixed()),
+                "fixed-factor-clock" => {
+                    return ClockType::FixedFactor(FixedFactorClock {
+                        name,
+                        mult: get_u32("clock-mult").unwrap_or(1),
+                        div: get_u32("clock-div").unwrap_or(1),
+                    });
+                }
+                _ => {}
+            }
         }
+
+        ClockType::Normal
     }
 }
 
@@ -0,0 +1,332 @@
+//! CPU node view specialization.
+
+use core::ops::Deref;
+
+use alloc::vec::Vec;
+use fdt_raw::{CpuMapEntry, EnableMethod, MAX_CACHE_LEVELS, Phandle};
+
+use super::{NodeType, NodeView, opp::legacy_operating_points};
+use crate::{
+    Fdt, NodeGeneric, NodeGenericMut, NodeId, Opp, OppTableView, Property, ThermalZoneView,
+    ViewMutOp, ViewOp,
+};
+
+// ---------------------------------------------------------------------------
+// CpuNodeView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for CPU nodes (`device_type = "cpu"`).
+///
+/// `enable-method` and `clock-frequency` fall back to the parent `/cpus`
+/// node when the CPU does not set them itself.
+#[derive(Clone, Copy)]
+pub struct CpuNodeView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for CpuNodeView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for CpuNodeView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> CpuNodeView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_cpu() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the hardware ID of the CPU: the first `reg` entry.
+    pub fn hwid(&self) -> Option<u64> {
+        self.hwids().first().copied()
+    }
+
+    /// Returns all hardware IDs in `reg`, one per hardware thread.
+    ///
+    /// Each ID is `#address-cells` of `/cpus` wide; `#size-cells` is 0.
+    pub fn hwids(&self) -> Vec<u64> {
+        let view = self.as_view();
+        let Some(reg) = view.as_node().get_property("reg") else {
+            return Vec::new();
+        };
+        let address_cells = view
+            .parent()
+            .and_then(|parent| parent.as_view().address_cells())
+            .unwrap_or(1) as usize;
+        if address_cells == 0 {
+            return Vec::new();
+        }
+
+        let mut reader = reg.as_reader();
+        let mut hwids = Vec::new();
+        while let Some(hwid) = reader.read_cells(address_cells) {
+            hwids.push(hwid);
+        }
+        hwids
+    }
+
+    /// Returns the enable method, inherited from `/cpus` when absent.
+    pub fn enable_method(&self) -> Option<EnableMethod<'a>> {
+        let prop = self.inherited_property("enable-method")?;
+        let mut fallback = None;
+        for method in prop.as_str_iter() {
+            match method {
+                "psci" => return Some(EnableMethod::Psci),
+                "spin-table" => {
+                    return Some(EnableMethod::SpinTable {
+                        cpu_release_addr: self.cpu_release_addr(),
+                    });
+                }
+                other => {
+                    fallback.get_or_insert(EnableMethod::Other(other));
+                }
+            }
+        }
+        fallback
+    }
+
+    /// Returns the `cpu-release-addr` used by the spin-table method.
+    pub fn cpu_release_addr(&self) -> Option<u64> {
+        self.as_view()
+            .as_node()
+            .get_property("cpu-release-addr")
+            .and_then(read_u32_or_u64)
+    }
+
+    /// Returns the `clock-frequency` in Hz, inherited from `/cpus`.
+    pub fn clock_frequency(&self) -> Option<u64> {
+        self.inherited_property("clock-frequency")
+            .and_then(read_u32_or_u64)
+    }
+
+    /// Returns the `next-level-cache` phandle.
+    pub fn next_level_cache(&self) -> Option<Phandle> {
+        phandle_prop(self.as_view().as_node(), "next-level-cache")
+    }
+
+    /// Returns the cache nodes reachable through `next-level-cache`,
+    /// nearest first.
+    pub fn caches(&self) -> Vec<NodeType<'a>> {
+        let fdt = self.as_view().fdt();
+        let mut caches = Vec::new();
+        let mut next = self.next_level_cache();
+        while let Some(phandle) = next {
+            if caches.len() == MAX_CACHE_LEVELS {
+                break;
+            }
+            let Some(cache) = fdt.get_by_phandle(phandle) else {
+                break;
+            };
+            next = phandle_prop(cache.as_node(), "next-level-cache");
+            caches.push(cache);
+        }
+        caches
+    }
+
+    /// Returns the `operating-points-v2` table phandle.
+    pub fn operating_points_v2(&self) -> Option<Phandle> {
+        phandle_prop(self.as_view().as_node(), "operating-points-v2")
+    }
+
+    /// Returns the `operating-points-v2` table of this CPU.
+    pub fn opp_table(&self) -> Option<OppTableView<'a>> {
+        let fdt = self.as_view().fdt();
+        let id = fdt.get_by_phandle_id(self.operating_points_v2()?)?;
+        OppTableView::try_from_view(NodeView::new(fdt, id))
+    }
+
+    /// Returns the operating points of this CPU, from its
+    /// `operating-points-v2` table or else the legacy `operating-points`.
+    pub fn opps(&self) -> Vec<Opp> {
+        if let Some(table) = self.opp_table() {
+            return table.opps();
+        }
+        self.as_view()
+            .as_node()
+            .get_property("operating-points")
+            .map(legacy_operating_points)
+            .unwrap_or_default()
+    }
+
+    /// Returns the thermal zones whose cooling maps throttle this CPU.
+    pub fn thermal_zones(&self) -> Vec<ThermalZoneView<'a>> {
+        let Some(phandle) = self.as_view().as_node().phandle() else {
+            return Vec::new();
+        };
+        self.as_view()
+            .fdt()
+            .thermal_zones()
+            .into_iter()
+            .filter(|zone| zone.is_cooled_by(phandle))
+            .collect()
+    }
+
+    /// Returns this CPU's position in `/cpus/cpu-map`, if listed there.
+    pub fn topology(&self) -> Option<CpuMapEntry> {
+        let phandle = self.as_view().as_node().phandle()?;
+        self.as_view()
+            .fdt()
+            .cpu_map()
+            .into_iter()
+            .find(|entry| entry.cpu == phandle)
+    }
+
+    /// Looks a property up on the CPU node, then on its parent.
+    fn inherited_property(&self, name: &str) -> Option<&'a Property> {
+        let view = self.as_view();
+        view.as_node().get_property(name).or_else(|| {
+            let parent = view.parent()?;
+            parent.as_node().get_property(name)
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CpuNodeViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for CPU nodes.
+pub struct CpuNodeViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for CpuNodeViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for CpuNodeViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for CpuNodeViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        n.set_property(Property::new("device_type", b"cpu\0".to_vec()));
+        s
+    }
+}
+
+impl<'a> CpuNodeViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_cpu() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sets `enable-method`, and `cpu-release-addr` for the spin-table
+    /// method.
+    pub fn set_enable_method(&mut self, method: EnableMethod<'_>) {
+        let node = self.inner.inner.as_node_mut();
+        let name = match method {
+            EnableMethod::Psci => "psci",
+            EnableMethod::SpinTable { cpu_release_addr } => {
+                if let Some(addr) = cpu_release_addr {
+                    let mut prop = Property::new("cpu-release-addr", Vec::new());
+                    prop.set_u64(addr);
+                    node.set_property(prop);
+                }
+                "spin-table"
+            }
+            EnableMethod::Other(name) => name,
+        };
+        let mut prop = Property::new("enable-method", Vec::new());
+        prop.set_string(name);
+        node.set_property(prop);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// cpu-map
+// ---------------------------------------------------------------------------
+
+impl Fdt {
+    /// Returns the leaves of `/cpus/cpu-map`, in tree order.
+    ///
+    /// Nested clusters report the innermost cluster index; leaves without
+    /// a `coreN` ancestor are skipped.
+    pub fn cpu_map(&self) -> Vec<CpuMapEntry> {
+        let mut entries = Vec::new();
+        if let Some(id) = self.get_by_path_id("/cpus/cpu-map") {
+            let mut levels = Vec::new();
+            self.collect_cpu_map(id, &mut levels, &mut entries);
+        }
+        entries
+    }
+
+    fn collect_cpu_map(
+        &self,
+        id: NodeId,
+        levels: &mut Vec<(&'static str, u32)>,
+        entries: &mut Vec<CpuMapEntry>,
+    ) {
+        let Some(node) = self.node(id) else {
+            return;
+        };
+        for &child_id in node.children() {
+            let Some(child) = self.node(child_id) else {
+                continue;
+            };
+            let level = parse_map_level(child.name());
+            if let Some(level) = level {
+                levels.push(level);
+            }
+
+            if let Some(cpu) = phandle_prop(child, "cpu") {
+                let find = |kind| levels.iter().rev().find(|(k, _)| *k == kind);
+                if let Some(&(_, core)) = find("core") {
+                    entries.push(CpuMapEntry {
+                        socket: find("socket").map(|&(_, i)| i),
+                        cluster: find("cluster").map(|&(_, i)| i),
+                        core,
+                        thread: find("thread").map(|&(_, i)| i),
+                        cpu,
+                    });
+                }
+            }
+            self.collect_cpu_map(child_id, levels, entries);
+
+            if level.is_some() {
+                levels.pop();
+            }
+        }
+    }
+}
+
+/// Splits a `cpu-map` node name such as `cluster1` into kind and index.
+fn parse_map_level(name: &str) -> Option<(&'static str, u32)> {
+    ["socket", "cluster", "core", "thread"]
+        .into_iter()
+        .find_map(|kind| Some((kind, name.strip_prefix(kind)?.parse().ok()?)))
+}
+
+fn phandle_prop(node: &crate::Node, name: &str) -> Option<Phandle> {
+    node.get_property(name)
+        .and_then(|prop| prop.get_u32())
+        .map(Phandle::from)
+}
+
+fn read_u32_or_u64(prop: &Property) -> Option<u64> {
+    prop.get_u32().map(u64::from).or_else(|| prop.get_u64())
+}
@@ -0,0 +1,200 @@
+//! IOMMU, MSI and DMA addressing bindings.
+//!
+//! Covers `iommus`, `msi-parent`, the `iommu-map`/`msi-map` ID translation
+//! tables and `dma-ranges`. The `NodeView` methods that use these helpers
+//! live in the parent module next to the other phandle reference parsers.
+
+use alloc::vec::Vec;
+use fdt_raw::{FdtError, Phandle};
+
+use super::NodeView;
+use crate::Property;
+
+/// IOMMU reference, used to parse the `iommus` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IommuRef {
+    /// Phandle of the IOMMU.
+    pub iommu: Phandle,
+    /// Provider `#iommu-cells` value used to parse the specifier.
+    pub cells: u32,
+    /// Raw IOMMU specifier cells, usually the stream ID.
+    pub specifier: Vec<u32>,
+}
+
+/// MSI controller reference, used to parse the `msi-parent` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsiRef {
+    /// Phandle of the MSI controller.
+    pub controller: Phandle,
+    /// Provider `#msi-cells` value used to parse the specifier.
+    pub cells: u32,
+    /// Raw MSI specifier cells, usually empty or a device ID.
+    pub specifier: Vec<u32>,
+}
+
+/// One entry of an `iommu-map` or `msi-map` property.
+///
+/// Maps the input IDs `id_base..id_base + length`, such as PCI requester
+/// IDs, to `target_base..` on the `target` IOMMU or MSI controller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdMapEntry {
+    /// First input ID of the entry.
+    pub id_base: u32,
+    /// Phandle of the IOMMU or MSI controller.
+    pub target: Phandle,
+    /// Output ID for `id_base`.
+    pub target_base: u32,
+    /// Number of IDs covered.
+    pub length: u32,
+}
+
+/// An ID translated through an `iommu-map` or `msi-map`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MappedId {
+    /// Phandle of the IOMMU or MSI controller.
+    pub target: Phandle,
+    /// Stream ID or MSI device ID on that controller.
+    pub id: u32,
+}
+
+/// A window of device-visible DMA addresses and the CPU addresses they
+/// reach, from `dma-ranges`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DmaRange {
+    /// First address as seen by the DMA master.
+    pub dma_address: u64,
+    /// CPU physical address of `dma_address`.
+    pub cpu_address: u64,
+    /// Size of the window in bytes.
+    pub size: u64,
+}
+
+impl DmaRange {
+    /// Returns `cpu_address - dma_address`.
+    pub fn offset(&self) -> i64 {
+        self.cpu_address.wrapping_sub(self.dma_address) as i64
+    }
+
+    /// Translates a DMA address in this window to a CPU address.
+    pub fn dma_to_cpu(&self, address: u64) -> Option<u64> {
+        let delta = address.checked_sub(self.dma_address)?;
+        (delta < self.size)
+            .then(|| self.cpu_address.checked_add(delta))
+            .flatten()
+    }
+
+    /// Translates a CPU address in this window to a DMA address.
+    pub fn cpu_to_dma(&self, address: u64) -> Option<u64> {
+        let delta = address.checked_sub(self.cpu_address)?;
+        (delta < self.size)
+            .then(|| self.dma_address.checked_add(delta))
+            .flatten()
+    }
+}
+
+/// Parses an `iommu-map` or `msi-map` property.
+///
+/// Entries are `<id-base &target target-base length>`; the target
+/// specifier is a single cell, as the bindings require.
+pub(super) fn parse_id_map(prop: &Property) -> Vec<IdMapEntry> {
+    let mut reader = prop.as_reader();
+    let mut entries = Vec::new();
+
+    while let (Some(id_base), Some(target), Some(target_base), Some(length)) = (
+        reader.read_u32(),
+        reader.read_u32(),
+        reader.read_u32(),
+        reader.read_u32(),
+    ) {
+        entries.push(IdMapEntry {
+            id_base,
+            target: Phandle::from(target),
+            target_base,
+            length,
+        });
+    }
+
+    entries
+}
+
+/// Translates `id` through the `map` property of `view`, masking it with
+/// `<map>-mask` first.
+///
+/// Returns `FdtError::PropertyNotFound` if the node has no such map and
+/// `FdtError::NotFound` if no entry covers the ID.
+pub(super) fn map_id(
+    view: &NodeView<'_>,
+    map: &'static str,
+    id: u32,
+) -> Result<MappedId, FdtError> {
+    let node = view.as_node();
+    let prop = node
+        .get_property(map)
+        .ok_or(FdtError::PropertyNotFound(map))?;
+    let mask = node
+        .get_property(&format!("{map}-mask"))
+        .and_then(|prop| prop.get_u32())
+        .unwrap_or(u32::MAX);
+    let id = id & mask;
+
+    parse_id_map(prop)
+        .into_iter()
+        .find(|entry| id >= entry.id_base && id - entry.id_base < entry.length)
+        .map(|entry| MappedId {
+            target: entry.target,
+            id: (id - entry.id_base).wrapping_add(entry.target_base),
+        })
+        .ok_or(FdtError::NotFound)
+}
+
+/// Builds the DMA window table for masters below `bus`, composing the
+/// `dma-ranges` of `bus` and each of its ancestors.
+///
+/// A missing or empty `dma-ranges` is treated as a 1:1 mapping at that
+/// level. Windows that an upper level does not cover, or moves past the
+/// end of the address space, are dropped. `None` means DMA addresses
+/// equal CPU addresses, while an empty table means no window reaches the
+/// CPU.
+pub(super) fn dma_ranges_from(bus: NodeView<'_>) -> Option<Vec<DmaRange>> {
+    let mut table: Option<Vec<DmaRange>> = None;
+    let mut current = bus;
+
+    while let Some(parent) = current.fdt().parent_of(current.id()) {
+        let parent = NodeView::new(current.fdt(), parent);
+        let parent_cells = parent.address_cells().unwrap_or(2);
+
+        if let Some(entries) = current.as_node().dma_ranges(parent_cells)
+            && !entries.is_empty()
+        {
+            table = Some(match table {
+                None => entries
+                    .iter()
+                    .map(|entry| DmaRange {
+                        dma_address: entry.child_bus_address,
+                        cpu_address: entry.parent_bus_address,
+                        size: entry.length,
+                    })
+                    .collect(),
+                Some(table) => table
+                    .into_iter()
+                    .filter_map(|range| {
+                        let entry = entries.iter().find(|entry| {
+                            range.cpu_address >= entry.child_bus_address
+                                && range.cpu_address - entry.child_bus_address < entry.length
+                        })?;
+                        let cpu_address = (range.cpu_address - entry.child_bus_address)
+                            .checked_add(entry.parent_bus_address)?;
+                        Some(DmaRange {
+                            cpu_address,
+                            ..range
+                        })
+                    })
+                    .collect(),
+            });
+        }
+
+        current = parent;
+    }
+
+    table
+}
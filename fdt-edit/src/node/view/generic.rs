@@ -1,10 +1,14 @@
 //! Generic node view specialization.
 
 use alloc::{string::String, vec::Vec};
-use fdt_raw::{Phandle, RegInfo};
+use fdt_raw::{FdtError, Phandle, RegInfo, TranslateError};
 
 use super::NodeView;
-use crate::{ClockRef, InterruptRef, Node, NodeId, RegFixed, ViewMutOp, ViewOp};
+use crate::{
+    ClockRef, DmaRange, GpioRef, IdMapEntry, InterruptRef, IommuRef, MsiRef, Node, NodeId,
+    PinctrlState, PowerDomainRef, RegFixed, ResetRef, ResolvedInterrupt, Supplies, ViewMutOp,
+    ViewOp,
+};
 
 // ---------------------------------------------------------------------------
 // GenericNodeView
@@ -29,6 +33,12 @@ impl<'a> NodeGeneric<'a> {
         self.inner.regs()
     }
 
+    /// Translates a region of this node's address space to a CPU physical
+    /// address.
+    pub fn try_translate(&self, address: u128, size: u64) -> Result<u64, TranslateError> {
+        self.inner.try_translate(address, size)
+    }
+
     /// Returns the effective `interrupt-parent`, inheriting from ancestors.
     pub fn interrupt_parent(&self) -> Option<Phandle> {
         self.inner.interrupt_parent()
@@ -39,10 +49,78 @@ impl<'a> NodeGeneric<'a> {
         self.inner.clocks()
     }
 
-    /// Parses the `interrupts` property into interrupt references.
+    /// Parses the GPIO property for function `name` into GPIO references.
+    pub fn gpios(&self, name: &str) -> Vec<GpioRef> {
+        self.inner.gpios(name)
+    }
+
+    /// Returns the pin control states from `pinctrl-0`, `pinctrl-1`, ...
+    pub fn pinctrl_states(&self) -> Vec<PinctrlState<'a>> {
+        self.inner.pinctrl_states()
+    }
+
+    /// Returns the pin control state called `name`.
+    pub fn pinctrl_state(&self, name: &str) -> Option<PinctrlState<'a>> {
+        self.inner.pinctrl_state(name)
+    }
+
+    /// Parses the `power-domains` property into power domain references.
+    pub fn power_domains(&self) -> Vec<PowerDomainRef> {
+        self.inner.power_domains()
+    }
+
+    /// Parses the `resets` property into reset references.
+    pub fn resets(&self) -> Vec<ResetRef> {
+        self.inner.resets()
+    }
+
+    /// Resolves every `*-supply` property to its regulator.
+    pub fn supplies(&self) -> Supplies {
+        self.inner.supplies()
+    }
+
+    /// Parses the `iommus` property into IOMMU references.
+    pub fn iommus(&self) -> Vec<IommuRef> {
+        self.inner.iommus()
+    }
+
+    /// Parses the `iommu-map` property.
+    pub fn iommu_map(&self) -> Vec<IdMapEntry> {
+        self.inner.iommu_map()
+    }
+
+    /// Parses the effective `msi-parent` property, inheriting from ancestors.
+    pub fn msi_parents(&self) -> Vec<MsiRef> {
+        self.inner.msi_parents()
+    }
+
+    /// Parses the `msi-map` property.
+    pub fn msi_map(&self) -> Vec<IdMapEntry> {
+        self.inner.msi_map()
+    }
+
+    /// Returns the DMA windows of this node as a bus master, or `None` if
+    /// DMA addresses equal CPU addresses.
+    pub fn dma_ranges(&self) -> Option<Vec<DmaRange>> {
+        self.inner.dma_ranges()
+    }
+
+    /// Returns whether DMA from this node is cache coherent, inheriting
+    /// from ancestors.
+    pub fn is_dma_coherent(&self) -> bool {
+        self.inner.is_dma_coherent()
+    }
+
+    /// Parses the `interrupts-extended` or `interrupts` property into
+    /// interrupt references.
     pub fn interrupts(&self) -> Vec<InterruptRef> {
         self.inner.interrupts()
     }
+
+    /// Resolves each interrupt through `interrupt-map` nexus nodes.
+    pub fn resolve_interrupts(&self) -> Result<Vec<ResolvedInterrupt>, FdtError> {
+        self.inner.resolve_interrupts()
+    }
 }
 
 impl<'a> ViewOp<'a> for NodeGeneric<'a> {
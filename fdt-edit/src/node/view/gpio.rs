@@ -0,0 +1,273 @@
+//! GPIO controller node view specialization.
+
+use core::ops::Deref;
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use fdt_raw::Phandle;
+
+use super::NodeView;
+use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+// ---------------------------------------------------------------------------
+// GPIO types
+// ---------------------------------------------------------------------------
+
+/// Flags cell of a GPIO specifier, as defined in `dt-bindings/gpio/gpio.h`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GpioFlags(pub u32);
+
+impl GpioFlags {
+    /// `GPIO_ACTIVE_LOW`: the line is asserted when driven low.
+    pub const ACTIVE_LOW: u32 = 1 << 0;
+    /// `GPIO_SINGLE_ENDED`: the line is only driven in one direction.
+    pub const SINGLE_ENDED: u32 = 1 << 1;
+    /// `GPIO_LINE_OPEN_DRAIN`: a single-ended line drives low only.
+    pub const LINE_OPEN_DRAIN: u32 = 1 << 2;
+    /// `GPIO_TRANSITORY`: the line state may be lost in suspend.
+    pub const TRANSITORY: u32 = 1 << 3;
+    /// `GPIO_PULL_UP`: enable the internal pull-up.
+    pub const PULL_UP: u32 = 1 << 4;
+    /// `GPIO_PULL_DOWN`: enable the internal pull-down.
+    pub const PULL_DOWN: u32 = 1 << 5;
+    /// `GPIO_PULL_DISABLE`: disable internal pulls.
+    pub const PULL_DISABLE: u32 = 1 << 6;
+
+    /// Returns the raw flags cell.
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns `true` for `GPIO_ACTIVE_LOW`.
+    pub fn is_active_low(&self) -> bool {
+        self.0 & Self::ACTIVE_LOW != 0
+    }
+
+    /// Returns `true` for `GPIO_OPEN_DRAIN` (single-ended, drives low).
+    pub fn is_open_drain(&self) -> bool {
+        self.0 & (Self::SINGLE_ENDED | Self::LINE_OPEN_DRAIN)
+            == Self::SINGLE_ENDED | Self::LINE_OPEN_DRAIN
+    }
+
+    /// Returns `true` for `GPIO_OPEN_SOURCE` (single-ended, drives high).
+    pub fn is_open_source(&self) -> bool {
+        self.0 & (Self::SINGLE_ENDED | Self::LINE_OPEN_DRAIN) == Self::SINGLE_ENDED
+    }
+
+    /// Returns `true` for `GPIO_TRANSITORY`.
+    pub fn is_transitory(&self) -> bool {
+        self.0 & Self::TRANSITORY != 0
+    }
+
+    /// Returns `true` for `GPIO_PULL_UP`.
+    pub fn pull_up(&self) -> bool {
+        self.0 & Self::PULL_UP != 0
+    }
+
+    /// Returns `true` for `GPIO_PULL_DOWN`.
+    pub fn pull_down(&self) -> bool {
+        self.0 & Self::PULL_DOWN != 0
+    }
+}
+
+/// GPIO reference, used to parse `gpios` and `*-gpios` properties.
+///
+/// The format is `<&controller line flags>` for the usual
+/// `#gpio-cells = <2>`; the number of cells comes from the controller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GpioRef {
+    /// Phandle of the GPIO controller
+    pub controller: Phandle,
+    /// Line number on the controller, the first specifier cell
+    pub line: u32,
+    /// Flags, the second specifier cell (0 if the controller has only one)
+    pub flags: GpioFlags,
+    /// All specifier cells, for controllers with more than two
+    pub specifier: Vec<u32>,
+}
+
+/// One entry of a controller's `gpio-ranges` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GpioRange {
+    /// Phandle of the pin controller
+    pub pinctrl: Phandle,
+    /// First GPIO line of the range
+    pub gpio_offset: u32,
+    /// First pin of the range on the pin controller
+    pub pin_offset: u32,
+    /// Number of lines in the range
+    pub count: u32,
+}
+
+// ---------------------------------------------------------------------------
+// GpioControllerView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for GPIO controller nodes (`gpio-controller`).
+///
+/// GPIO banks are often interrupt controllers as well; such nodes are
+/// classified as GPIO controllers.
+#[derive(Clone, Copy)]
+pub struct GpioControllerView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for GpioControllerView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for GpioControllerView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> GpioControllerView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_gpio_controller() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `#gpio-cells` property value (default 2).
+    pub fn gpio_cells(&self) -> u32 {
+        self.as_view()
+            .as_node()
+            .get_property("#gpio-cells")
+            .and_then(|prop| prop.get_u32())
+            .unwrap_or(2)
+    }
+
+    /// Returns the `ngpios` property: how many lines are usable.
+    pub fn ngpios(&self) -> Option<u32> {
+        self.as_view()
+            .as_node()
+            .get_property("ngpios")
+            .and_then(|prop| prop.get_u32())
+    }
+
+    /// Returns the `gpio-line-names` property, one entry per line.
+    ///
+    /// Unnamed lines are empty strings.
+    pub fn line_names(&self) -> Vec<String> {
+        self.as_view()
+            .as_node()
+            .get_property("gpio-line-names")
+            .map(|prop| prop.as_str_iter().map(|s| s.to_owned()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of `line`, if it has one.
+    pub fn line_name(&self, line: u32) -> Option<String> {
+        self.line_names()
+            .into_iter()
+            .nth(line as usize)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Parses the `gpio-ranges` property.
+    ///
+    /// Each entry is `<&pinctrl gpio_offset pin_offset count>`; parsing
+    /// stops at an entry whose pin controller does not use three cells.
+    pub fn gpio_ranges(&self) -> Vec<GpioRange> {
+        let view = self.as_view();
+        let Some(prop) = view.as_node().get_property("gpio-ranges") else {
+            return Vec::new();
+        };
+
+        let mut reader = prop.as_reader();
+        let mut ranges = Vec::new();
+        while let Some(raw) = reader.read_u32() {
+            let pinctrl = Phandle::from(raw);
+            let cells = view
+                .fdt()
+                .get_by_phandle(pinctrl)
+                .and_then(|provider| provider.as_node().get_property("#gpio-range-cells"))
+                .and_then(|prop| prop.get_u32())
+                .unwrap_or(3);
+            if cells != 3 {
+                break;
+            }
+            let (Some(gpio_offset), Some(pin_offset), Some(count)) =
+                (reader.read_u32(), reader.read_u32(), reader.read_u32())
+            else {
+                break;
+            };
+            ranges.push(GpioRange {
+                pinctrl,
+                gpio_offset,
+                pin_offset,
+                count,
+            });
+        }
+        ranges
+    }
+
+    /// Returns `true` if the controller also provides interrupts.
+    pub fn is_interrupt_controller(&self) -> bool {
+        self.as_view().as_node().is_interrupt_controller()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// GpioControllerViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for GPIO controller nodes.
+pub struct GpioControllerViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for GpioControllerViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for GpioControllerViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for GpioControllerViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        n.set_property(Property::new("gpio-controller", Vec::new()));
+        n.set_property(Property::new("#gpio-cells", 2u32.to_be_bytes().to_vec()));
+        s
+    }
+}
+
+impl<'a> GpioControllerViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_gpio_controller() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sets the `ngpios` property.
+    pub fn set_ngpios(&mut self, ngpios: u32) {
+        let n = self.inner.inner.as_node_mut();
+        n.set_property(Property::new("ngpios", ngpios.to_be_bytes().to_vec()));
+    }
+
+    /// Sets the `gpio-line-names` property.
+    pub fn set_line_names(&mut self, names: &[&str]) {
+        let mut prop = Property::new("gpio-line-names", Vec::new());
+        prop.set_string_ls(names);
+        self.inner.inner.as_node_mut().set_property(prop);
+    }
+}
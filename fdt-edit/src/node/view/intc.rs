@@ -7,10 +7,14 @@ use alloc::{
     vec::Vec,
 };
 
-use fdt_raw::Phandle;
+use fdt_raw::{FdtError, Phandle};
 
 use super::NodeView;
-use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+use crate::{Fdt, GpioControllerView, NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+/// Maximum number of controllers and nexus nodes an interrupt may pass
+/// through, guarding against `interrupt-map` cycles.
+const MAX_INTERRUPT_HOPS: usize = 16;
 
 /// Interrupt reference, used to parse the `interrupts` property.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -42,6 +46,178 @@ impl InterruptRef {
     }
 }
 
+/// One step on the path of an interrupt to its controller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterruptHop {
+    /// Path of the controller or `interrupt-map` nexus node.
+    pub controller: String,
+    /// Phandle of that node.
+    pub phandle: Phandle,
+    /// Interrupt specifier in that node's `#interrupt-cells` format.
+    pub specifier: Vec<u32>,
+}
+
+/// An interrupt followed through `interrupt-map` nexus nodes.
+///
+/// `hops` starts at the node's interrupt parent and ends at the interrupt
+/// controller that finally receives the interrupt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedInterrupt {
+    /// Optional interrupt name from `interrupt-names`.
+    pub name: Option<String>,
+    /// Nodes the interrupt passes through, in order.
+    pub hops: Vec<InterruptHop>,
+}
+
+impl ResolvedInterrupt {
+    /// Returns the final hop: the interrupt controller and its specifier.
+    ///
+    /// `None` only for a value built without any hops.
+    pub fn controller(&self) -> Option<&InterruptHop> {
+        self.hops.last()
+    }
+}
+
+/// One entry of an `interrupt-map` property.
+struct InterruptMapEntry {
+    child_address: Vec<u32>,
+    child_specifier: Vec<u32>,
+    parent: Phandle,
+    parent_address: Vec<u32>,
+    parent_specifier: Vec<u32>,
+}
+
+/// Parses the `interrupt-map` of a nexus node whose child unit addresses
+/// and specifiers use `address_cells` and `interrupt_cells` cells.
+fn interrupt_map(
+    view: NodeView<'_>,
+    address_cells: usize,
+    interrupt_cells: usize,
+) -> Result<Vec<InterruptMapEntry>, FdtError> {
+    let prop = view
+        .as_node()
+        .get_property("interrupt-map")
+        .ok_or(FdtError::PropertyNotFound("interrupt-map"))?;
+
+    let mut reader = prop.as_reader();
+    let mut entries = Vec::new();
+    while !reader.remain().is_empty() {
+        let mut read_cells = |count: usize| -> Result<Vec<u32>, FdtError> {
+            (0..count)
+                .map(|_| reader.read_u32().ok_or(FdtError::InvalidInput))
+                .collect()
+        };
+
+        let child_address = read_cells(address_cells)?;
+        let child_specifier = read_cells(interrupt_cells)?;
+        let parent = Phandle::from(read_cells(1)?[0]);
+
+        let provider = view
+            .fdt()
+            .get_by_phandle(parent)
+            .ok_or(FdtError::NodeNotFound("interrupt-map parent"))?
+            .as_view();
+        let parent_address_cells = provider.address_cells().unwrap_or(0) as usize;
+        let parent_interrupt_cells = provider
+            .as_node()
+            .interrupt_cells()
+            .ok_or(FdtError::PropertyNotFound("#interrupt-cells"))?
+            as usize;
+
+        entries.push(InterruptMapEntry {
+            child_address,
+            child_specifier,
+            parent,
+            parent_address: read_cells(parent_address_cells)?,
+            parent_specifier: read_cells(parent_interrupt_cells)?,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Follows an interrupt from `parent` through `interrupt-map` nexus nodes
+/// until it reaches an interrupt controller.
+///
+/// `address` holds the unit address of the interrupt source, usually the
+/// raw cells of its `reg`; each nexus uses as many leading cells as its
+/// `#address-cells` says, padding with zeros. A nexus with no matching
+/// entry yields `FdtError::NotFound`.
+pub(super) fn resolve_interrupt(
+    fdt: &Fdt,
+    address: &[u32],
+    parent: Phandle,
+    specifier: Vec<u32>,
+) -> Result<Vec<InterruptHop>, FdtError> {
+    let mut address = address.to_vec();
+    let mut parent = parent;
+    let mut specifier = specifier;
+    let mut hops = Vec::new();
+
+    for _ in 0..MAX_INTERRUPT_HOPS {
+        let view = fdt
+            .get_by_phandle(parent)
+            .ok_or(FdtError::NodeNotFound("interrupt-parent"))?
+            .as_view();
+        hops.push(InterruptHop {
+            controller: view.path(),
+            phandle: parent,
+            specifier: specifier.clone(),
+        });
+
+        let node = view.as_node();
+        if node.get_property("interrupt-map").is_none() {
+            // Like Linux, an `interrupt-map` takes precedence over
+            // `interrupt-controller` on nodes that have both.
+            if node.is_interrupt_controller() {
+                return Ok(hops);
+            }
+            return Err(FdtError::PropertyNotFound("interrupt-map"));
+        }
+
+        let address_cells = node.address_cells().unwrap_or(0) as usize;
+        let interrupt_cells =
+            node.interrupt_cells()
+                .ok_or(FdtError::PropertyNotFound("#interrupt-cells"))? as usize;
+        if specifier.len() != interrupt_cells {
+            return Err(FdtError::InvalidInput);
+        }
+
+        let key: Vec<u32> = (0..address_cells)
+            .map(|i| address.get(i).copied().unwrap_or(0))
+            .chain(specifier.iter().copied())
+            .collect();
+        let mask: Vec<u32> = node
+            .get_property("interrupt-map-mask")
+            .map(|prop| prop.get_u32_iter().collect())
+            .unwrap_or_default();
+
+        let entry = interrupt_map(view, address_cells, interrupt_cells)?
+            .into_iter()
+            .find(|entry| {
+                entry
+                    .child_address
+                    .iter()
+                    .chain(entry.child_specifier.iter())
+                    .zip(key.iter())
+                    .enumerate()
+                    .all(|(i, (child, key))| {
+                        let mask = mask.get(i).copied().unwrap_or(u32::MAX);
+                        (child ^ key) & mask == 0
+                    })
+            })
+            .ok_or(FdtError::NotFound)?;
+
+        address = entry.parent_address;
+        parent = entry.parent;
+        specifier = entry.parent_specifier;
+    }
+
+    Err(FdtError::TooDeep {
+        max_depth: MAX_INTERRUPT_HOPS,
+    })
+}
+
 // ---------------------------------------------------------------------------
 // IntcNodeView
 // ---------------------------------------------------------------------------
@@ -86,6 +262,11 @@ impl<'a> IntcNodeView<'a> {
         self.as_view().as_node().address_cells()
     }
 
+    /// Returns the GPIO controller view if the node is also a GPIO bank.
+    pub fn gpio_controller(&self) -> Option<GpioControllerView<'a>> {
+        GpioControllerView::try_from_view(self.as_view())
+    }
+
     /// This is always `true` for `IntcNodeView` (type-level guarantee).
     pub fn is_interrupt_controller(&self) -> bool {
         true
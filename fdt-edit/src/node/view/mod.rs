@@ -5,26 +5,53 @@
 //! type-specialized views such as `MemoryNodeView` and `IntcNodeView`.
 
 // Specialized node view modules
+mod chosen;
 mod clock;
+mod cpu;
+mod dma;
 mod generic;
+mod gpio;
 mod intc;
 mod memory;
+mod opp;
 mod pci;
+mod phandle;
+mod pinctrl;
+mod power_domain;
+mod regulator;
+mod reset;
+mod thermal;
 
 use core::fmt::Display;
 
 use alloc::{borrow::ToOwned, string::String, vec::Vec};
 use enum_dispatch::enum_dispatch;
-use fdt_raw::Phandle;
+use fdt_raw::{FdtError, Phandle, TranslateError};
 
 use crate::{Fdt, Node, NodeId, Property, RangesEntry};
+pub(crate) use phandle::{PhandleArg, PhandleArgs, cells_property};
 
 // Re-export specialized view types
-pub use clock::{ClockNodeView, ClockNodeViewMut, ClockRef, ClockType, FixedClock};
+pub use chosen::{ChosenNodeView, ChosenNodeViewMut};
+pub use clock::{
+    ClockNodeView, ClockNodeViewMut, ClockRef, ClockType, FixedClock, FixedFactorClock,
+};
+pub use cpu::{CpuNodeView, CpuNodeViewMut};
+pub use dma::{DmaRange, IdMapEntry, IommuRef, MappedId, MsiRef};
 pub use generic::{NodeGeneric, NodeGenericMut};
-pub use intc::{IntcNodeView, IntcNodeViewMut, InterruptRef};
+pub use gpio::{GpioControllerView, GpioControllerViewMut, GpioFlags, GpioRange, GpioRef};
+pub use intc::{IntcNodeView, IntcNodeViewMut, InterruptHop, InterruptRef, ResolvedInterrupt};
 pub use memory::{MemoryNodeView, MemoryNodeViewMut};
+pub use opp::{Opp, OppMicrovolt, OppTableView, OppTableViewMut};
 pub use pci::{PciInterruptInfo, PciInterruptMap, PciNodeView, PciNodeViewMut, PciRange, PciSpace};
+pub use pinctrl::{PinBias, PinctrlConfigView, PinctrlState, RockchipPin};
+pub use power_domain::{PowerDomainRef, PowerDomainView, PowerDomainViewMut};
+pub use regulator::{RegulatorView, RegulatorViewMut, Supplies, SupplyRef};
+pub use reset::{ResetControllerView, ResetControllerViewMut, ResetRef};
+pub use thermal::{
+    CoolingDeviceRef, CoolingMap, THERMAL_NO_LIMIT, ThermalSensorRef, ThermalTrip, ThermalZoneView,
+    ThermalZoneViewMut, TripType,
+};
 
 #[enum_dispatch]
 pub(crate) trait ViewOp<'a> {
@@ -192,11 +219,291 @@ impl<'a> NodeView<'a> {
         refs
     }
 
-    /// Parses the `interrupts` property into interrupt references.
+    /// Parses the GPIO property for function `name` into GPIO references.
     ///
-    /// The specifier width is derived from the effective `interrupt-parent`
-    /// provider's `#interrupt-cells` value.
+    /// Looks up `<name>-gpios` and then the legacy `<name>-gpio`, or
+    /// `gpios` and `gpio` when `name` is empty. Empty entries (a zero
+    /// phandle) are skipped, and parsing stops at a controller that cannot
+    /// be resolved.
+    pub fn gpios(&self, name: &str) -> Vec<GpioRef> {
+        let node = self.as_node();
+        let prop = if name.is_empty() {
+            node.get_property("gpios")
+                .or_else(|| node.get_property("gpio"))
+        } else {
+            node.get_property(&format!("{name}-gpios"))
+                .or_else(|| node.get_property(&format!("{name}-gpio")))
+        };
+        let Some(prop) = prop else {
+            return Vec::new();
+        };
+
+        PhandleArgs::new(self.fdt(), prop, cells_property("#gpio-cells"))
+            .resolved()
+            .map(|target| GpioRef {
+                controller: target.phandle,
+                line: target.specifier.first().copied().unwrap_or(0),
+                flags: GpioFlags(target.specifier.get(1).copied().unwrap_or(0)),
+                specifier: target.specifier,
+            })
+            .collect()
+    }
+
+    /// Returns the pin control states from `pinctrl-0`, `pinctrl-1`, ...
+    ///
+    /// States are numbered consecutively; the list ends at the first
+    /// missing index. Names come from `pinctrl-names`.
+    pub fn pinctrl_states(&self) -> Vec<PinctrlState<'a>> {
+        let node = self.as_node();
+        let names: Vec<&str> = node
+            .get_property("pinctrl-names")
+            .map(|prop| prop.as_str_iter().collect())
+            .unwrap_or_default();
+
+        let mut states = Vec::new();
+        for index in 0u32.. {
+            let Some(prop) = node.get_property(&format!("pinctrl-{index}")) else {
+                break;
+            };
+
+            let mut configs = Vec::new();
+            let mut unresolved = Vec::new();
+            for raw in prop.get_u32_iter() {
+                let phandle = Phandle::from(raw);
+                match self.fdt().get_by_phandle_id(phandle) {
+                    Some(id) => {
+                        configs.push(PinctrlConfigView::from_view(NodeView::new(self.fdt(), id)))
+                    }
+                    None => unresolved.push(phandle),
+                }
+            }
+
+            states.push(PinctrlState {
+                index,
+                name: names.get(index as usize).map(|name| (*name).to_owned()),
+                configs,
+                unresolved,
+            });
+        }
+        states
+    }
+
+    /// Returns the pin control state called `name`.
+    pub fn pinctrl_state(&self, name: &str) -> Option<PinctrlState<'a>> {
+        self.pinctrl_states()
+            .into_iter()
+            .find(|state| state.name.as_deref() == Some(name))
+    }
+
+    /// Parses the `power-domains` property into power domain references.
+    ///
+    /// Each entry's width comes from the provider's `#power-domain-cells`;
+    /// parsing stops at a provider without it.
+    pub fn power_domains(&self) -> Vec<PowerDomainRef> {
+        self.provider_refs("power-domains", "power-domain-names", "#power-domain-cells")
+            .into_iter()
+            .map(|(name, provider, cells, specifier)| PowerDomainRef {
+                name,
+                provider,
+                cells,
+                specifier,
+            })
+            .collect()
+    }
+
+    /// Parses the `resets` property into reset references.
+    ///
+    /// Each entry's width comes from the controller's `#reset-cells`;
+    /// parsing stops at a controller without it.
+    pub fn resets(&self) -> Vec<ResetRef> {
+        self.provider_refs("resets", "reset-names", "#reset-cells")
+            .into_iter()
+            .map(|(name, controller, cells, specifier)| ResetRef {
+                name,
+                controller,
+                cells,
+                specifier,
+            })
+            .collect()
+    }
+
+    /// Parses a `<&provider specifier...>` list together with its names
+    /// property.
+    fn provider_refs(
+        &self,
+        property: &str,
+        names: &str,
+        cells: &str,
+    ) -> Vec<(Option<String>, Phandle, u32, Vec<u32>)> {
+        let node = self.as_node();
+        let Some(prop) = node.get_property(property) else {
+            return Vec::new();
+        };
+        let names: Vec<String> = node
+            .get_property(names)
+            .map(|prop| prop.as_str_iter().map(|s| s.to_owned()).collect())
+            .unwrap_or_default();
+
+        PhandleArgs::new(self.fdt(), prop, cells_property(cells))
+            .resolved_indexed()
+            .map(|(index, target)| {
+                (
+                    names.get(index).cloned(),
+                    target.phandle,
+                    target.cells,
+                    target.specifier,
+                )
+            })
+            .collect()
+    }
+
+    /// Resolves every `*-supply` property to its regulator.
+    ///
+    /// Any node a supply points to is taken as the regulator, as Linux
+    /// does, so PMIC outputs without `regulator-name` and drivers such as
+    /// `pwm-regulator` are covered. Phandles that lead nowhere are listed
+    /// in [`Supplies::unresolved`].
+    pub fn supplies(&self) -> Supplies {
+        let mut supplies = Supplies::default();
+        for prop in self.as_node().properties() {
+            let Some(name) = prop.name().strip_suffix("-supply") else {
+                continue;
+            };
+            let Some(regulator) = prop.get_u32().map(Phandle::from) else {
+                continue;
+            };
+            match self.fdt().get_by_phandle_id(regulator) {
+                Some(id) => {
+                    let view = RegulatorView {
+                        inner: NodeGeneric {
+                            inner: NodeView::new(self.fdt(), id),
+                        },
+                    };
+                    supplies.resolved.push(view.supply_ref(name, regulator));
+                }
+                None => supplies.unresolved.push((name.to_owned(), regulator)),
+            }
+        }
+        supplies
+    }
+
+    /// Parses the `iommus` property into IOMMU references.
+    ///
+    /// Each entry's width comes from the IOMMU's `#iommu-cells`; parsing
+    /// stops at an IOMMU that cannot be resolved or an incomplete entry.
+    pub fn iommus(&self) -> Vec<IommuRef> {
+        let Some(prop) = self.as_node().get_property("iommus") else {
+            return Vec::new();
+        };
+
+        PhandleArgs::new(self.fdt(), prop, cells_property("#iommu-cells"))
+            .resolved()
+            .map(|target| IommuRef {
+                iommu: target.phandle,
+                cells: target.cells,
+                specifier: target.specifier,
+            })
+            .collect()
+    }
+
+    /// Parses the effective `msi-parent` property into MSI controller
+    /// references.
+    ///
+    /// A node without `msi-parent` uses the one of its closest ancestor
+    /// bus. Each entry's width comes from the controller's `#msi-cells`,
+    /// which defaults to 0.
+    pub fn msi_parents(&self) -> Vec<MsiRef> {
+        let mut current = Some(self.id);
+        let prop = loop {
+            let Some(node_id) = current else {
+                return Vec::new();
+            };
+            let Some(node) = self.fdt().node(node_id) else {
+                return Vec::new();
+            };
+            if let Some(prop) = node.get_property("msi-parent") {
+                break prop;
+            }
+            current = self.fdt().parent_of(node_id);
+        };
+
+        let msi_cells = |node: &Node| {
+            Some(
+                node.get_property("#msi-cells")
+                    .and_then(|prop| prop.get_u32())
+                    .unwrap_or(0),
+            )
+        };
+        PhandleArgs::new(self.fdt(), prop, msi_cells)
+            .resolved()
+            .map(|target| MsiRef {
+                controller: target.phandle,
+                cells: target.cells,
+                specifier: target.specifier,
+            })
+            .collect()
+    }
+
+    /// Parses the `iommu-map` property.
+    pub fn iommu_map(&self) -> Vec<IdMapEntry> {
+        self.as_node()
+            .get_property("iommu-map")
+            .map(dma::parse_id_map)
+            .unwrap_or_default()
+    }
+
+    /// Parses the `msi-map` property.
+    pub fn msi_map(&self) -> Vec<IdMapEntry> {
+        self.as_node()
+            .get_property("msi-map")
+            .map(dma::parse_id_map)
+            .unwrap_or_default()
+    }
+
+    /// Returns the DMA windows of this node as a bus master.
+    ///
+    /// Composes the `dma-ranges` of every ancestor bus. `None` means DMA
+    /// addresses equal CPU addresses; an empty vector means no window
+    /// reaches the CPU, so the node cannot master DMA.
+    pub fn dma_ranges(&self) -> Option<Vec<DmaRange>> {
+        let parent = self.fdt().parent_of(self.id)?;
+        dma::dma_ranges_from(NodeView::new(self.fdt(), parent))
+    }
+
+    /// Returns whether DMA from this node is cache coherent.
+    ///
+    /// Like Linux, the closest `dma-coherent` or `dma-noncoherent` on the
+    /// node or an ancestor decides; without either, DMA is non-coherent.
+    pub fn is_dma_coherent(&self) -> bool {
+        let mut current = Some(self.id);
+
+        while let Some(node_id) = current {
+            let Some(node) = self.fdt().node(node_id) else {
+                break;
+            };
+            if node.get_property("dma-coherent").is_some() {
+                return true;
+            }
+            if node.get_property("dma-noncoherent").is_some() {
+                return false;
+            }
+            current = self.fdt().parent_of(node_id);
+        }
+
+        false
+    }
+
+    /// Parses the `interrupts-extended` property, or else `interrupts`, into
+    /// interrupt references.
+    ///
+    /// Each `interrupts-extended` entry names its own provider; `interrupts`
+    /// entries all go to the effective `interrupt-parent`. The specifier
+    /// width is the provider's `#interrupt-cells` value.
     pub fn interrupts(&self) -> Vec<InterruptRef> {
+        if let Some(prop) = self.as_node().get_property("interrupts-extended") {
+            return self.interrupts_extended(prop);
+        }
+
         let Some(prop) = self.as_node().get_property("interrupts") else {
             return Vec::new();
         };
@@ -216,11 +523,7 @@ impl<'a> NodeView<'a> {
             return Vec::new();
         }
 
-        let interrupt_names: Vec<String> = self
-            .as_node()
-            .get_property("interrupt-names")
-            .map(|prop| prop.as_str_iter().map(|s| s.to_owned()).collect())
-            .unwrap_or_default();
+        let interrupt_names = self.interrupt_names();
 
         let mut reader = prop.as_reader();
         let mut refs = Vec::new();
@@ -256,9 +559,66 @@ impl<'a> NodeView<'a> {
         refs
     }
 
+    /// Parses `interrupts-extended`, stopping at a provider without
+    /// `#interrupt-cells` or an incomplete entry.
+    fn interrupts_extended(&self, prop: &Property) -> Vec<InterruptRef> {
+        let interrupt_names = self.interrupt_names();
+
+        PhandleArgs::new(self.fdt(), prop, Node::interrupt_cells)
+            .resolved_indexed()
+            .map(|(index, target)| {
+                InterruptRef::with_name(
+                    interrupt_names.get(index).cloned(),
+                    target.phandle,
+                    target.cells,
+                    target.specifier,
+                )
+            })
+            .collect()
+    }
+
+    fn interrupt_names(&self) -> Vec<String> {
+        self.as_node()
+            .get_property("interrupt-names")
+            .map(|prop| prop.as_str_iter().map(|s| s.to_owned()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Resolves each interrupt through `interrupt-map` nexus nodes to the
+    /// controller that receives it.
+    ///
+    /// The node's `reg` cells serve as the unit address matched against
+    /// each `interrupt-map`. Fails if any interrupt cannot be resolved, for
+    /// example with `FdtError::NotFound` when a nexus has no matching entry.
+    pub fn resolve_interrupts(&self) -> Result<Vec<ResolvedInterrupt>, FdtError> {
+        let address: Vec<u32> = self
+            .as_node()
+            .get_property("reg")
+            .map(|prop| prop.get_u32_iter().collect())
+            .unwrap_or_default();
+
+        self.interrupts()
+            .into_iter()
+            .map(|irq| {
+                let hops = intc::resolve_interrupt(
+                    self.fdt(),
+                    &address,
+                    irq.interrupt_parent,
+                    irq.specifier,
+                )?;
+                Ok(ResolvedInterrupt {
+                    name: irq.name,
+                    hops,
+                })
+            })
+            .collect()
+    }
+
     /// Parses the `reg` property and returns corrected register entries.
     ///
-    /// Uses parent node's `ranges` property to translate bus addresses to CPU addresses.
+    /// Each entry is translated through the `ranges` of every ancestor bus.
+    /// An entry that cannot be translated keeps its bus address and records
+    /// why in [`RegFixed::error`].
     pub fn regs(&self) -> Vec<RegFixed> {
         let node = self.as_node();
         let reg = match node.get_property("reg") {
@@ -269,42 +629,73 @@ impl<'a> NodeView<'a> {
         // Get address-cells and size-cells from parent (or default 2/1)
         let (addr_cells, size_cells) = self.parent_cells();
 
-        // Get parent's ranges for address translation
-        let ranges = self.parent_ranges();
-
         let mut reader = reg.as_reader();
         let mut results = Vec::new();
 
-        while let Some(child_bus_address) = reader.read_cells(addr_cells) {
+        // Read addresses at full width so PCI `phys.hi` reaches translation.
+        // Entries are read only while data is left, since zero-width
+        // addresses always read successfully.
+        while !reader.remain().is_empty() {
+            let Some(bus_address) = (0..addr_cells).try_fold(0u128, |acc, _| {
+                Some((acc << 32) | reader.read_u32()? as u128)
+            }) else {
+                break;
+            };
             let size = if size_cells > 0 {
                 reader.read_cells(size_cells)
             } else {
                 None
             };
 
-            // Convert bus address to CPU address using ranges
-            let mut address = child_bus_address;
-            if let Some(ref ranges) = ranges {
-                for r in ranges {
-                    if child_bus_address >= r.child_bus_address
-                        && child_bus_address < r.child_bus_address + r.length
-                    {
-                        address = child_bus_address - r.child_bus_address + r.parent_bus_address;
-                        break;
-                    }
-                }
-            }
+            let child_bus_address = bus_address as u64;
+            let (address, error) = match self.try_translate(bus_address, size.unwrap_or(0)) {
+                Ok(address) => (address, None),
+                Err(e) => (child_bus_address, Some(e)),
+            };
 
             results.push(RegFixed {
                 address,
                 child_bus_address,
                 size,
+                error,
             });
         }
 
         results
     }
 
+    /// Translates the region `[address, address + size)` of this node's
+    /// address space to a CPU physical address.
+    ///
+    /// Applies the `ranges` of every ancestor bus below the root, following
+    /// the rules of [`fdt_raw::translate_ranges`]. For 3-cell (PCI)
+    /// addresses, bits 64..96 of `address` hold `phys.hi`.
+    pub fn try_translate(&self, address: u128, size: u64) -> Result<u64, TranslateError> {
+        let fdt = self.fdt();
+        let mut address = address;
+        let mut bus = fdt.parent_of(self.id);
+
+        // The root's children already use CPU addresses
+        while let Some(bus_id) = bus {
+            let Some(upper) = fdt.parent_of(bus_id) else {
+                break;
+            };
+            let node = fdt.node(bus_id).ok_or(TranslateError::NodeNotFound)?;
+            let parent_cells = fdt.node(upper).and_then(|n| n.address_cells()).unwrap_or(2);
+            address = fdt_raw::translate_ranges(
+                node.get_property("ranges").map(|p| p.data.as_slice()),
+                node.address_cells().unwrap_or(2) as usize,
+                parent_cells as usize,
+                node.size_cells().unwrap_or(1) as usize,
+                address,
+                size,
+            )?;
+            bus = Some(upper);
+        }
+
+        u64::try_from(address).map_err(|_| TranslateError::Overflow)
+    }
+
     /// Returns (address_cells, size_cells) from the parent node (defaults: 2, 1).
     fn parent_cells(&self) -> (usize, usize) {
         if let Some(parent) = self.parent() {
@@ -408,10 +799,42 @@ impl<'a> NodeView<'a> {
             return NodeType::Memory(node);
         }
 
+        if let Some(node) = ChosenNodeView::try_from_view(*self) {
+            return NodeType::Chosen(node);
+        }
+
+        if let Some(node) = CpuNodeView::try_from_view(*self) {
+            return NodeType::Cpu(node);
+        }
+
         if let Some(node) = IntcNodeView::try_from_view(*self) {
             return NodeType::InterruptController(node);
         }
 
+        if let Some(node) = GpioControllerView::try_from_view(*self) {
+            return NodeType::GpioController(node);
+        }
+
+        if let Some(node) = PowerDomainView::try_from_view(*self) {
+            return NodeType::PowerDomain(node);
+        }
+
+        if let Some(node) = ResetControllerView::try_from_view(*self) {
+            return NodeType::ResetController(node);
+        }
+
+        if let Some(node) = RegulatorView::try_from_view(*self) {
+            return NodeType::Regulator(node);
+        }
+
+        if let Some(node) = OppTableView::try_from_view(*self) {
+            return NodeType::OppTable(node);
+        }
+
+        if let Some(node) = ThermalZoneView::try_from_view(*self) {
+            return NodeType::ThermalZone(node);
+        }
+
         NodeType::Generic(NodeGeneric { inner: *self })
     }
 
@@ -428,10 +851,42 @@ impl<'a> NodeView<'a> {
             return NodeTypeMut::Memory(node);
         }
 
+        if let Some(node) = ChosenNodeViewMut::try_from_view(*self) {
+            return NodeTypeMut::Chosen(node);
+        }
+
+        if let Some(node) = CpuNodeViewMut::try_from_view(*self) {
+            return NodeTypeMut::Cpu(node);
+        }
+
         if let Some(node) = IntcNodeViewMut::try_from_view(*self) {
             return NodeTypeMut::InterruptController(node);
         }
 
+        if let Some(node) = GpioControllerViewMut::try_from_view(*self) {
+            return NodeTypeMut::GpioController(node);
+        }
+
+        if let Some(node) = PowerDomainViewMut::try_from_view(*self) {
+            return NodeTypeMut::PowerDomain(node);
+        }
+
+        if let Some(node) = ResetControllerViewMut::try_from_view(*self) {
+            return NodeTypeMut::ResetController(node);
+        }
+
+        if let Some(node) = RegulatorViewMut::try_from_view(*self) {
+            return NodeTypeMut::Regulator(node);
+        }
+
+        if let Some(node) = OppTableViewMut::try_from_view(*self) {
+            return NodeTypeMut::OppTable(node);
+        }
+
+        if let Some(node) = ThermalZoneViewMut::try_from_view(*self) {
+            return NodeTypeMut::ThermalZone(node);
+        }
+
         NodeTypeMut::Generic(NodeGenericMut { inner: *self })
     }
 }
@@ -478,8 +933,28 @@ pub enum NodeType<'a> {
     Clock(ClockNodeView<'a>),
     /// A memory node (`device_type = "memory"` or name starts with "memory").
     Memory(MemoryNodeView<'a>),
+    /// The `/chosen` node.
+    Chosen(ChosenNodeView<'a>),
+    /// A CPU node (`device_type = "cpu"`).
+    Cpu(CpuNodeView<'a>),
     /// An interrupt controller node (has the `interrupt-controller` property).
     InterruptController(IntcNodeView<'a>),
+    /// A GPIO controller node (has the `gpio-controller` property).
+    ///
+    /// GPIO banks that are also interrupt controllers are classified as
+    /// `InterruptController`; reach their GPIO side through
+    /// [`IntcNodeView::gpio_controller`].
+    GpioController(GpioControllerView<'a>),
+    /// A power domain provider node (has `#power-domain-cells`).
+    PowerDomain(PowerDomainView<'a>),
+    /// A reset controller node (has `#reset-cells`).
+    ResetController(ResetControllerView<'a>),
+    /// A regulator node (has `regulator-name` or a `regulator-*` compatible).
+    Regulator(RegulatorView<'a>),
+    /// An `operating-points-v2` table node.
+    OppTable(OppTableView<'a>),
+    /// A thermal zone node (has `thermal-sensors`).
+    ThermalZone(ThermalZoneView<'a>),
     /// A PCI bridge node (`device_type = "pci"`).
     Pci(PciNodeView<'a>),
     /// A generic node (no special classification).
@@ -516,20 +991,84 @@ impl<'a> NodeType<'a> {
         self.as_view().regs()
     }
 
+    /// Translates a region of this node's address space to a CPU physical
+    /// address.
+    pub fn try_translate(&self, address: u128, size: u64) -> Result<u64, TranslateError> {
+        self.as_view().try_translate(address, size)
+    }
+
     /// Returns the effective `interrupt-parent`, inheriting from ancestors.
     pub fn interrupt_parent(&self) -> Option<Phandle> {
         self.as_view().interrupt_parent()
     }
 
-    /// Parses the `interrupts` property into interrupt references.
+    /// Parses the `interrupts-extended` or `interrupts` property into
+    /// interrupt references.
     pub fn interrupts(&self) -> Vec<InterruptRef> {
         self.as_view().interrupts()
     }
 
+    /// Resolves each interrupt through `interrupt-map` nexus nodes.
+    pub fn resolve_interrupts(&self) -> Result<Vec<ResolvedInterrupt>, FdtError> {
+        self.as_view().resolve_interrupts()
+    }
+
     /// Parses the `clocks` property into clock references.
     pub fn clocks(&self) -> Vec<ClockRef> {
         self.as_view().clocks()
     }
+
+    /// Parses the GPIO property for function `name` into GPIO references.
+    pub fn gpios(&self, name: &str) -> Vec<GpioRef> {
+        self.as_view().gpios(name)
+    }
+
+    /// Returns the pin control states from `pinctrl-0`, `pinctrl-1`, ...
+    pub fn pinctrl_states(&self) -> Vec<PinctrlState<'a>> {
+        self.as_view().pinctrl_states()
+    }
+
+    /// Returns the pin control state called `name`.
+    pub fn pinctrl_state(&self, name: &str) -> Option<PinctrlState<'a>> {
+        self.as_view().pinctrl_state(name)
+    }
+
+    /// Parses the `power-domains` property into power domain references.
+    pub fn power_domains(&self) -> Vec<PowerDomainRef> {
+        self.as_view().power_domains()
+    }
+
+    /// Parses the `resets` property into reset references.
+    pub fn resets(&self) -> Vec<ResetRef> {
+        self.as_view().resets()
+    }
+
+    /// Resolves every `*-supply` property to its regulator.
+    pub fn supplies(&self) -> Supplies {
+        self.as_view().supplies()
+    }
+
+    /// Parses the `iommus` property into IOMMU references.
+    pub fn iommus(&self) -> Vec<IommuRef> {
+        self.as_view().iommus()
+    }
+
+    /// Parses the effective `msi-parent` property, inheriting from ancestors.
+    pub fn msi_parents(&self) -> Vec<MsiRef> {
+        self.as_view().msi_parents()
+    }
+
+    /// Returns the DMA windows of this node as a bus master, or `None` if
+    /// DMA addresses equal CPU addresses.
+    pub fn dma_ranges(&self) -> Option<Vec<DmaRange>> {
+        self.as_view().dma_ranges()
+    }
+
+    /// Returns whether DMA from this node is cache coherent, inheriting
+    /// from ancestors.
+    pub fn is_dma_coherent(&self) -> bool {
+        self.as_view().is_dma_coherent()
+    }
 }
 
 impl core::fmt::Display for NodeType<'_> {
@@ -547,7 +1086,15 @@ impl core::fmt::Display for NodeType<'_> {
 pub enum NodeTypeMut<'a> {
     Clock(ClockNodeViewMut<'a>),
     Memory(MemoryNodeViewMut<'a>),
+    Chosen(ChosenNodeViewMut<'a>),
+    Cpu(CpuNodeViewMut<'a>),
     InterruptController(IntcNodeViewMut<'a>),
+    GpioController(GpioControllerViewMut<'a>),
+    PowerDomain(PowerDomainViewMut<'a>),
+    ResetController(ResetControllerViewMut<'a>),
+    Regulator(RegulatorViewMut<'a>),
+    OppTable(OppTableViewMut<'a>),
+    ThermalZone(ThermalZoneViewMut<'a>),
     Pci(PciNodeViewMut<'a>),
     Generic(NodeGenericMut<'a>),
 }
@@ -597,9 +1144,37 @@ impl<'a> NodeGenericMut<'a> {
         self.add_child(name)
     }
 
+    pub fn add_child_cpu(&mut self, name: &str) -> CpuNodeViewMut<'a> {
+        self.add_child(name)
+    }
+
     pub fn add_child_interrupt_controller(&mut self, name: &str) -> IntcNodeViewMut<'a> {
         self.add_child(name)
     }
+
+    pub fn add_child_gpio_controller(&mut self, name: &str) -> GpioControllerViewMut<'a> {
+        self.add_child(name)
+    }
+
+    pub fn add_child_power_domain(&mut self, name: &str) -> PowerDomainViewMut<'a> {
+        self.add_child(name)
+    }
+
+    pub fn add_child_reset_controller(&mut self, name: &str) -> ResetControllerViewMut<'a> {
+        self.add_child(name)
+    }
+
+    pub fn add_child_regulator(&mut self, name: &str) -> RegulatorViewMut<'a> {
+        self.add_child(name)
+    }
+
+    pub fn add_child_opp_table(&mut self, name: &str) -> OppTableViewMut<'a> {
+        self.add_child(name)
+    }
+
+    pub fn add_child_thermal_zone(&mut self, name: &str) -> ThermalZoneViewMut<'a> {
+        self.add_child(name)
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -607,4 +1182,7 @@ pub struct RegFixed {
     pub address: u64,
     pub child_bus_address: u64,
     pub size: Option<u64>,
+    /// Why `address` could not be translated; it then equals
+    /// `child_bus_address`.
+    pub error: Option<TranslateError>,
 }
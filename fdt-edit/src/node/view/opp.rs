@@ -0,0 +1,313 @@
+//! Operating performance point table node view specialization.
+//!
+//! Covers `operating-points-v2` tables and the legacy `operating-points`
+//! property, which lists `<kHz uV>` pairs directly in the device node.
+
+use core::ops::Deref;
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+use super::NodeView;
+use crate::{Node, NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+/// Voltage of one supply at an operating point, in microvolts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OppMicrovolt {
+    /// Target voltage
+    pub target: u32,
+    /// Lowest acceptable voltage
+    pub min: u32,
+    /// Highest acceptable voltage
+    pub max: u32,
+}
+
+/// One operating performance point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Opp {
+    /// Node name for `operating-points-v2` entries
+    pub name: Option<String>,
+    /// Frequencies in Hz, one per clock of the device
+    pub hz: Vec<u64>,
+    /// Raw `opp-microvolt` cells, decoded by [`Opp::voltages`]
+    pub microvolt: Vec<u32>,
+    /// Raw `opp-microvolt-<name>` cells, by name
+    pub named_microvolt: Vec<(String, Vec<u32>)>,
+    /// `opp-supported-hw` cells; empty means all hardware
+    pub supported_hw: Vec<u32>,
+    /// `clock-latency-ns`
+    pub clock_latency_ns: Option<u32>,
+    /// The point is only for boost (`turbo-mode`)
+    pub turbo: bool,
+    /// The point to use during suspend (`opp-suspend`)
+    pub suspend: bool,
+}
+
+impl Opp {
+    /// Returns the frequency of the first clock, in Hz.
+    pub fn rate(&self) -> Option<u64> {
+        self.hz.first().copied()
+    }
+
+    /// Returns the voltage of each of the device's `supplies` regulators.
+    ///
+    /// `opp-microvolt` holds either one target or one `<target min max>`
+    /// triplet per supply, and only the supply count tells the two apart,
+    /// so `None` is returned if the cells fit neither form.
+    pub fn voltages(&self, supplies: usize) -> Option<Vec<OppMicrovolt>> {
+        parse_microvolt(&self.microvolt, supplies)
+    }
+
+    /// Returns the voltages for the `name` variant, falling back to
+    /// `opp-microvolt` when the point has no such variant.
+    pub fn voltages_for(&self, name: &str, supplies: usize) -> Option<Vec<OppMicrovolt>> {
+        let cells = self
+            .named_microvolt
+            .iter()
+            .find(|(variant, _)| variant == name)
+            .map(|(_, cells)| cells)
+            .unwrap_or(&self.microvolt);
+        parse_microvolt(cells, supplies)
+    }
+
+    /// Returns `true` if `opp-supported-hw` allows the hardware `versions`.
+    ///
+    /// The property holds one or more groups of `versions.len()` cells;
+    /// a group matches when every cell shares a bit with its version.
+    pub fn is_supported(&self, versions: &[u32]) -> bool {
+        if self.supported_hw.is_empty() || versions.is_empty() {
+            return true;
+        }
+        self.supported_hw
+            .chunks_exact(versions.len())
+            .any(|group| group.iter().zip(versions).all(|(hw, v)| hw & v != 0))
+    }
+}
+
+/// Parses `opp-microvolt` style cells for `supplies` regulators.
+///
+/// As in Linux, `supplies` cells are single targets and `3 * supplies`
+/// cells are `<target min max>` triplets; any other count is rejected.
+fn parse_microvolt(cells: &[u32], supplies: usize) -> Option<Vec<OppMicrovolt>> {
+    if cells.len() == supplies {
+        Some(
+            cells
+                .iter()
+                .map(|&v| OppMicrovolt {
+                    target: v,
+                    min: v,
+                    max: v,
+                })
+                .collect(),
+        )
+    } else if cells.len() == supplies * 3 {
+        Some(
+            cells
+                .chunks_exact(3)
+                .map(|c| OppMicrovolt {
+                    target: c[0],
+                    min: c[1],
+                    max: c[2],
+                })
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
+
+/// Parses a legacy `operating-points` property of `<kHz uV>` pairs.
+pub(super) fn legacy_operating_points(prop: &Property) -> Vec<Opp> {
+    let cells: Vec<u32> = prop.get_u32_iter().collect();
+    cells
+        .chunks_exact(2)
+        .map(|pair| Opp {
+            name: None,
+            hz: vec![pair[0] as u64 * 1000],
+            microvolt: vec![pair[1]],
+            named_microvolt: Vec::new(),
+            supported_hw: Vec::new(),
+            clock_latency_ns: None,
+            turbo: false,
+            suspend: false,
+        })
+        .collect()
+}
+
+fn parse_opp(node: &Node) -> Option<Opp> {
+    let prop = node.get_property("opp-hz")?;
+    // `opp-hz` is 64-bit per clock, but accept 32-bit cells as well.
+    let cell_count = if prop.data.len().is_multiple_of(8) {
+        2
+    } else {
+        1
+    };
+    let mut reader = prop.as_reader();
+    let mut hz = Vec::new();
+    while let Some(value) = reader.read_cells(cell_count) {
+        hz.push(value);
+    }
+    if hz.is_empty() {
+        return None;
+    }
+
+    let cells = |prop: &Property| prop.get_u32_iter().collect::<Vec<u32>>();
+    let microvolt = node
+        .get_property("opp-microvolt")
+        .map(cells)
+        .unwrap_or_default();
+    let named_microvolt = node
+        .properties()
+        .iter()
+        .filter_map(|prop| {
+            let name = prop.name().strip_prefix("opp-microvolt-")?;
+            Some((name.to_owned(), cells(prop)))
+        })
+        .collect();
+
+    Some(Opp {
+        name: Some(node.name().to_owned()),
+        hz,
+        microvolt,
+        named_microvolt,
+        supported_hw: node
+            .get_property("opp-supported-hw")
+            .map(cells)
+            .unwrap_or_default(),
+        clock_latency_ns: node
+            .get_property("clock-latency-ns")
+            .and_then(|prop| prop.get_u32()),
+        turbo: node.get_property("turbo-mode").is_some(),
+        suspend: node.get_property("opp-suspend").is_some(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// OppTableView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for `operating-points-v2` table nodes.
+#[derive(Clone, Copy)]
+pub struct OppTableView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for OppTableView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for OppTableView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> OppTableView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_opp_table() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the devices using this table share their clock
+    /// and supply (`opp-shared`), like the CPUs of one cluster.
+    pub fn is_shared(&self) -> bool {
+        self.as_view()
+            .as_node()
+            .get_property("opp-shared")
+            .is_some()
+    }
+
+    /// Returns the operating points in node order.
+    ///
+    /// Children without a non-empty `opp-hz` are skipped.
+    pub fn opps(&self) -> Vec<Opp> {
+        let view = self.as_view();
+        view.as_node()
+            .children()
+            .iter()
+            .filter_map(|&id| parse_opp(view.fdt().node(id)?))
+            .collect()
+    }
+
+    /// Returns the operating points the hardware `versions` support.
+    pub fn supported_opps(&self, versions: &[u32]) -> Vec<Opp> {
+        self.opps()
+            .into_iter()
+            .filter(|opp| opp.is_supported(versions))
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OppTableViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for `operating-points-v2` table nodes.
+pub struct OppTableViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for OppTableViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for OppTableViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for OppTableViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        let mut prop = Property::new("compatible", Vec::new());
+        prop.set_string("operating-points-v2");
+        n.set_property(prop);
+        s
+    }
+}
+
+impl<'a> OppTableViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_opp_table() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Adds or removes `opp-shared`.
+    pub fn set_shared(&mut self, shared: bool) {
+        let n = self.inner.inner.as_node_mut();
+        if shared {
+            n.set_property(Property::new("opp-shared", Vec::new()));
+        } else {
+            n.remove_property("opp-shared");
+        }
+    }
+
+    /// Adds an `opp-<hz>` entry with a single-supply target voltage.
+    pub fn add_opp(&mut self, hz: u64, microvolt: u32) -> NodeGenericMut<'a> {
+        let mut child = self.inner.add_child_generic(&format!("opp-{hz}"));
+        let n = child.inner.as_node_mut();
+        n.set_property(Property::new("opp-hz", hz.to_be_bytes().to_vec()));
+        n.set_property(Property::new(
+            "opp-microvolt",
+            microvolt.to_be_bytes().to_vec(),
+        ));
+        child
+    }
+}
@@ -5,8 +5,8 @@ use core::ops::{Deref, Range};
 use alloc::vec::Vec;
 use fdt_raw::{FdtError, Phandle};
 
-use super::NodeView;
-use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+use super::{NodeView, dma};
+use crate::{DmaRange, MappedId, NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
 
 // ---------------------------------------------------------------------------
 // PCI types
@@ -60,7 +60,9 @@ pub struct PciInterruptMap {
 /// Contains the resolved interrupt information for a PCI device.
 #[derive(Clone, Debug, PartialEq)]
 pub struct PciInterruptInfo {
-    /// List of IRQ numbers
+    /// Phandle of the interrupt controller the pin is routed to
+    pub interrupt_parent: Phandle,
+    /// Interrupt specifier in the controller's `#interrupt-cells` format
     pub irqs: Vec<u32>,
 }
 
@@ -214,12 +216,8 @@ impl<'a> PciNodeView<'a> {
             .get_property("interrupt-map")
             .ok_or(FdtError::NotFound)?;
 
-        // 将 mask 转换为 Vec 以便索引访问
-        let mask: Vec<u32> = self
-            .interrupt_map_mask()
-            .ok_or(FdtError::NotFound)?
-            .into_iter()
-            .collect();
+        // 没有 interrupt-map-mask 时，所有位都参与匹配
+        let mask: Vec<u32> = self.interrupt_map_mask().unwrap_or_default();
 
         let mut data = prop.as_reader();
         let mut mappings = Vec::new();
@@ -257,22 +255,17 @@ impl<'a> PciNodeView<'a> {
 
             // 通过 phandle 查找中断父节点以获取其 #address-cells 和 #interrupt-cells
             // 根据 devicetree 规范，interrupt-map 中的 parent unit address 使用中断父节点的 #address-cells
-            let (parent_addr_cells, parent_irq_cells) =
-                if let Some(irq_parent) = self.as_view().fdt().get_by_phandle(interrupt_parent) {
-                    // 直接使用中断父节点的 #address-cells
-                    let addr_cells = irq_parent.as_view().address_cells().unwrap_or(0) as usize;
-
-                    let irq_cells = irq_parent
-                        .as_view()
-                        .as_node()
-                        .get_property("#interrupt-cells")
-                        .and_then(|p| p.get_u32())
-                        .unwrap_or(3) as usize;
-                    (addr_cells, irq_cells)
-                } else {
-                    // 默认值：address_cells=0, interrupt_cells=3 (GIC 格式)
-                    (0, 3)
-                };
+            let irq_parent = self
+                .as_view()
+                .fdt()
+                .get_by_phandle(interrupt_parent)
+                .ok_or(FdtError::NodeNotFound("interrupt-map parent"))?;
+            let parent_addr_cells = irq_parent.as_view().address_cells().unwrap_or(0) as usize;
+            let parent_irq_cells = irq_parent
+                .as_node()
+                .interrupt_cells()
+                .ok_or(FdtError::PropertyNotFound("#interrupt-cells"))?
+                as usize;
 
             // 跳过父地址 cells
             for _ in 0..parent_addr_cells {
@@ -320,8 +313,17 @@ impl<'a> PciNodeView<'a> {
         }
     }
 
-    /// 获取 PCI 设备的中断信息
-    /// 参数: bus, device, function, pin (1=INTA, 2=INTB, 3=INTC, 4=INTD)
+    /// Returns the interrupt routed to a function's legacy INTx pin.
+    ///
+    /// `interrupt_pin` is the function's Interrupt Pin register (1 = INTA
+    /// to 4 = INTD). A function on a bus below this host bridge is followed
+    /// through the PCI-PCI bridge nodes in the tree: each bridge without an
+    /// `interrupt-map` swizzles the pin by the device number, as the PCI
+    /// bridge specification requires, and the closest `interrupt-map`
+    /// translates it.
+    ///
+    /// Fails with `FdtError::NotFound` if the pin is 0, no bridge node leads
+    /// to `bus`, or no `interrupt-map` entry matches.
     pub fn child_interrupts(
         &self,
         bus: u8,
@@ -329,75 +331,156 @@ impl<'a> PciNodeView<'a> {
         function: u8,
         interrupt_pin: u8,
     ) -> Result<PciInterruptInfo, FdtError> {
-        // 获取 interrupt-map 和 mask
-        let interrupt_map = self.interrupt_map()?;
+        match interrupt_pin {
+            0 => return Err(FdtError::NotFound),
+            1..=4 => {}
+            _ => return Err(FdtError::InvalidInput),
+        }
 
-        // 将 mask 转换为 Vec 以便索引访问
-        let mask: Vec<u32> = self
-            .interrupt_map_mask()
-            .ok_or(FdtError::NotFound)?
-            .into_iter()
-            .collect();
+        let mut address = pci_child_address(bus, device, function);
+        let mut device = device as u32;
+        let mut pin = interrupt_pin as u32;
 
-        // 构造 PCI 设备的子地址
-        // 格式: [bus_num, device_num, func_num] 在适当的位
-        let child_addr_high = ((bus as u32 & 0xff) << 16)
-            | ((device as u32 & 0x1f) << 11)
-            | ((function as u32 & 0x07) << 8);
-        let child_addr_mid = 0u32;
-        let child_addr_low = 0u32;
+        for bridge in self.bridges_to(bus as u32)?.iter().rev() {
+            if bridge
+                .as_view()
+                .as_node()
+                .get_property("interrupt-map")
+                .is_some()
+            {
+                return bridge.map_interrupt(address, pin);
+            }
 
-        let child_addr_cells = self.as_view().address_cells().unwrap_or(3) as usize;
-        let child_irq_cells = self.interrupt_cells() as usize;
+            pin = (pin - 1 + device) % 4 + 1;
+            address = bridge
+                .as_view()
+                .as_node()
+                .get_property("reg")
+                .and_then(|prop| prop.get_u32_iter().next())
+                .ok_or(FdtError::PropertyNotFound("reg"))?
+                & 0x00ff_ff00;
+            device = (address >> 11) & 0x1f;
+        }
 
-        let encoded_address = [child_addr_high, child_addr_mid, child_addr_low];
-        let mut masked_child_address = Vec::with_capacity(child_addr_cells);
+        self.map_interrupt(address, pin)
+    }
+
+    /// Translates a function's requester ID to an IOMMU stream ID through
+    /// `iommu-map` and `iommu-map-mask`.
+    ///
+    /// Fails with `FdtError::PropertyNotFound` if the bridge has no
+    /// `iommu-map` and `FdtError::NotFound` if no entry covers the ID.
+    pub fn iommu_stream_id(&self, bus: u8, device: u8, function: u8) -> Result<MappedId, FdtError> {
+        dma::map_id(
+            &self.as_view(),
+            "iommu-map",
+            requester_id(bus, device, function),
+        )
+    }
 
-        // 应用 mask 到子地址
-        for (idx, value) in encoded_address.iter().take(child_addr_cells).enumerate() {
-            let mask_value = mask.get(idx).copied().unwrap_or(0xffff_ffff);
-            masked_child_address.push(value & mask_value);
+    /// Translates a function's requester ID to an MSI device ID.
+    ///
+    /// Uses `msi-map` and `msi-map-mask` when present. Otherwise the
+    /// requester ID is passed unchanged to the first controller of the
+    /// effective `msi-parent`, as Linux does.
+    pub fn msi_device_id(&self, bus: u8, device: u8, function: u8) -> Result<MappedId, FdtError> {
+        let rid = requester_id(bus, device, function);
+        let view = self.as_view();
+        if view.as_node().get_property("msi-map").is_some() {
+            return dma::map_id(&view, "msi-map", rid);
         }
 
-        // 如果 encoded_address 比 child_addr_cells 短，填充 0
-        let remaining = child_addr_cells.saturating_sub(encoded_address.len());
-        masked_child_address.extend(core::iter::repeat_n(0, remaining));
+        view.msi_parents()
+            .first()
+            .map(|parent| MappedId {
+                target: parent.controller,
+                id: rid,
+            })
+            .ok_or(FdtError::NotFound)
+    }
 
-        let encoded_irq = [interrupt_pin as u32];
-        let mut masked_child_irq = Vec::with_capacity(child_irq_cells);
+    /// Returns the DMA windows of functions below this bridge, composing
+    /// its own `dma-ranges` with those of its ancestors.
+    ///
+    /// `None` means DMA addresses equal CPU addresses.
+    pub fn child_dma_ranges(&self) -> Option<Vec<DmaRange>> {
+        dma::dma_ranges_from(self.as_view())
+    }
 
-        // 应用 mask 到子 IRQ
-        for (idx, value) in encoded_irq.iter().take(child_irq_cells).enumerate() {
-            let mask_value = mask
-                .get(child_addr_cells + idx)
-                .copied()
-                .unwrap_or(0xffff_ffff);
-            masked_child_irq.push(value & mask_value);
+    /// Returns the bridge nodes from this host bridge down to the one whose
+    /// secondary bus is `bus`, or nothing if `bus` is the root bus.
+    fn bridges_to(&self, bus: u32) -> Result<Vec<PciNodeView<'a>>, FdtError> {
+        let root_bus = self.bus_range().map(|range| range.start).unwrap_or(0);
+        let mut bridges = Vec::new();
+        let mut current = *self;
+
+        while current
+            .bus_range()
+            .map(|range| range.start)
+            .unwrap_or(root_bus)
+            != bus
+        {
+            let view = current.as_view();
+            // `bus-range` holds an inclusive end.
+            let next = view
+                .as_node()
+                .children()
+                .iter()
+                .filter_map(|&id| PciNodeView::try_from_view(NodeView::new(view.fdt(), id)))
+                .find(|bridge| {
+                    bridge
+                        .bus_range()
+                        .is_some_and(|range| range.start <= bus && bus <= range.end)
+                })
+                .ok_or(FdtError::NotFound)?;
+            bridges.push(next);
+            current = next;
         }
 
-        // 如果 encoded_irq 比 child_irq_cells 短，填充 0
-        let remaining_irq = child_irq_cells.saturating_sub(encoded_irq.len());
-        masked_child_irq.extend(core::iter::repeat_n(0, remaining_irq));
+        Ok(bridges)
+    }
 
-        // 在 interrupt-map 中查找匹配的条目
-        for mapping in &interrupt_map {
-            if mapping.child_address == masked_child_address
-                && mapping.child_irq == masked_child_irq
-            {
-                return Ok(PciInterruptInfo {
-                    irqs: mapping.parent_irq.clone(),
-                });
-            }
-        }
+    /// Looks up a child unit address and pin in this node's `interrupt-map`.
+    fn map_interrupt(&self, address: u32, pin: u32) -> Result<PciInterruptInfo, FdtError> {
+        let mask = self.interrupt_map_mask().unwrap_or_default();
+        let masked =
+            |index: usize, value: u32| value & mask.get(index).copied().unwrap_or(u32::MAX);
+
+        let child_addr_cells = self.as_view().address_cells().unwrap_or(3) as usize;
+        let child_irq_cells = self.interrupt_cells() as usize;
 
-        // 回退到简单的 IRQ 计算
-        let simple_irq = (device as u32 * 4 + interrupt_pin as u32) % 32;
-        Ok(PciInterruptInfo {
-            irqs: vec![simple_irq],
-        })
+        // phys.hi carries bus, device and function; the rest of the unit
+        // address and any further interrupt cells are zero.
+        let child_address: Vec<u32> = (0..child_addr_cells)
+            .map(|i| masked(i, if i == 0 { address } else { 0 }))
+            .collect();
+        let child_irq: Vec<u32> = (0..child_irq_cells)
+            .map(|i| masked(child_addr_cells + i, if i == 0 { pin } else { 0 }))
+            .collect();
+
+        self.interrupt_map()?
+            .into_iter()
+            .find(|mapping| {
+                mapping.child_address == child_address && mapping.child_irq == child_irq
+            })
+            .map(|mapping| PciInterruptInfo {
+                interrupt_parent: mapping.interrupt_parent,
+                irqs: mapping.parent_irq,
+            })
+            .ok_or(FdtError::NotFound)
     }
 }
 
+/// Encodes bus, device and function as a 16-bit PCI requester ID.
+fn requester_id(bus: u8, device: u8, function: u8) -> u32 {
+    ((bus as u32) << 8) | ((device as u32 & 0x1f) << 3) | (function as u32 & 0x07)
+}
+
+/// Encodes bus, device and function as the phys.hi cell of a PCI address.
+fn pci_child_address(bus: u8, device: u8, function: u8) -> u32 {
+    ((bus as u32) << 16) | ((device as u32 & 0x1f) << 11) | ((function as u32 & 0x07) << 8)
+}
+
 // ---------------------------------------------------------------------------
 // PciNodeViewMut
 // ---------------------------------------------------------------------------
@@ -0,0 +1,123 @@
+//! Parsing of `<&provider specifier...>` list properties.
+//!
+//! `clocks`, `*-gpios`, `interrupts-extended`, `iommus`, `msi-parent`,
+//! `thermal-sensors`, `cooling-device` and many more share one layout:
+//! each entry is a provider phandle followed by as many specifier cells
+//! as the provider's `#<name>-cells` property says.
+
+use alloc::vec::Vec;
+use fdt_raw::{Phandle, data::Reader};
+
+use crate::{Fdt, Node, NodeId, Property};
+
+/// An entry whose provider was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PhandleTarget {
+    pub phandle: Phandle,
+    pub provider: NodeId,
+    pub cells: u32,
+    pub specifier: Vec<u32>,
+}
+
+/// One entry of a phandle list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PhandleArg {
+    /// A zero phandle, which bindings use to leave a slot empty.
+    Empty,
+    /// A provider and its specifier.
+    Resolved(PhandleTarget),
+    /// A phandle that is not a provider, so the width of its specifier
+    /// and of everything after it is unknown. Always the last entry.
+    Unresolved(Phandle),
+}
+
+/// Iterator over the entries of a phandle list property.
+///
+/// `cells_of` returns the specifier width of a provider node, or `None`
+/// if the node is not a provider of this kind. Iteration ends after an
+/// unresolved phandle and at an incomplete trailing entry.
+pub(crate) struct PhandleArgs<'a, F> {
+    fdt: &'a Fdt,
+    reader: Reader<'a>,
+    cells_of: F,
+    done: bool,
+}
+
+impl<'a, F: Fn(&Node) -> Option<u32>> PhandleArgs<'a, F> {
+    pub(crate) fn new(fdt: &'a Fdt, prop: &'a Property, cells_of: F) -> Self {
+        Self {
+            fdt,
+            reader: prop.as_reader(),
+            cells_of,
+            done: false,
+        }
+    }
+
+    /// Returns the resolved entries, skipping empty ones and stopping at
+    /// the first unresolved phandle.
+    pub(crate) fn resolved(self) -> impl Iterator<Item = PhandleTarget> + 'a
+    where
+        F: 'a,
+    {
+        self.resolved_indexed().map(|(_, target)| target)
+    }
+
+    /// Like [`PhandleArgs::resolved`], but pairs each entry with its index
+    /// in the list, empty entries included, for `*-names` lookups.
+    pub(crate) fn resolved_indexed(self) -> impl Iterator<Item = (usize, PhandleTarget)> + 'a
+    where
+        F: 'a,
+    {
+        self.map_while(|arg| match arg {
+            PhandleArg::Unresolved(_) => None,
+            arg => Some(arg),
+        })
+        .enumerate()
+        .filter_map(|(index, arg)| match arg {
+            PhandleArg::Resolved(target) => Some((index, target)),
+            _ => None,
+        })
+    }
+}
+
+impl<F: Fn(&Node) -> Option<u32>> Iterator for PhandleArgs<'_, F> {
+    type Item = PhandleArg;
+
+    fn next(&mut self) -> Option<PhandleArg> {
+        if self.done {
+            return None;
+        }
+        let raw = self.reader.read_u32()?;
+        if raw == 0 {
+            return Some(PhandleArg::Empty);
+        }
+
+        let phandle = Phandle::from(raw);
+        let provider = self.fdt.get_by_phandle_id(phandle);
+        let cells = provider
+            .and_then(|id| self.fdt.node(id))
+            .and_then(&self.cells_of);
+        let (Some(provider), Some(cells)) = (provider, cells) else {
+            self.done = true;
+            return Some(PhandleArg::Unresolved(phandle));
+        };
+
+        let specifier: Option<Vec<u32>> = (0..cells).map(|_| self.reader.read_u32()).collect();
+        let Some(specifier) = specifier else {
+            self.done = true;
+            return None;
+        };
+        Some(PhandleArg::Resolved(PhandleTarget {
+            phandle,
+            provider,
+            cells,
+            specifier,
+        }))
+    }
+}
+
+/// Returns a `cells_of` function reading the `#<name>-cells` property
+/// `cells_property`.
+pub(crate) fn cells_property(cells_property: &str) -> impl Fn(&Node) -> Option<u32> + '_ {
+    move |node| node.get_property(cells_property)?.get_u32()
+}
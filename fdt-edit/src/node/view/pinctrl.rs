@@ -0,0 +1,217 @@
+//! Pin control state and configuration node views.
+//!
+//! Devices select pin setups through `pinctrl-<N>` properties, each a list
+//! of phandles to configuration nodes under the pin controller, named by
+//! the matching `pinctrl-names` entry. Configuration nodes use the generic
+//! pinconf properties, vendor-specific cells, or both.
+
+use core::ops::Deref;
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use fdt_raw::Phandle;
+
+use super::NodeView;
+use crate::{NodeGeneric, ViewOp};
+
+/// A pin control state of a device, from `pinctrl-<index>`.
+#[derive(Clone)]
+pub struct PinctrlState<'a> {
+    /// The `<index>` of `pinctrl-<index>`
+    pub index: u32,
+    /// State name from `pinctrl-names`, such as `default` or `sleep`
+    pub name: Option<String>,
+    /// Configuration nodes to apply, in order
+    pub configs: Vec<PinctrlConfigView<'a>>,
+    /// Phandles in the state that do not resolve to a node
+    pub unresolved: Vec<Phandle>,
+}
+
+/// Pin bias setting of a generic pinconf node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinBias {
+    /// `bias-disable`
+    Disable,
+    /// `bias-high-impedance`
+    HighImpedance,
+    /// `bias-bus-hold`
+    BusHold,
+    /// `bias-pull-up`, with the optional pull strength argument
+    PullUp(Option<u32>),
+    /// `bias-pull-down`, with the optional pull strength argument
+    PullDown(Option<u32>),
+    /// `bias-pull-pin-default`
+    PullPinDefault,
+}
+
+/// One `rockchip,pins` entry: `<bank pin mux &config>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RockchipPin {
+    /// GPIO bank number
+    pub bank: u32,
+    /// Pin number within the bank
+    pub pin: u32,
+    /// Mux function, 0 for GPIO
+    pub mux: u32,
+    /// Phandle of the `pcfg-*` node holding the pin configuration
+    pub config: Phandle,
+}
+
+// ---------------------------------------------------------------------------
+// PinctrlConfigView
+// ---------------------------------------------------------------------------
+
+/// View of a pin configuration node referenced from a `pinctrl-<N>` state.
+#[derive(Clone, Copy)]
+pub struct PinctrlConfigView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for PinctrlConfigView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for PinctrlConfigView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> PinctrlConfigView<'a> {
+    pub(super) fn from_view(view: NodeView<'a>) -> Self {
+        Self {
+            inner: NodeGeneric { inner: view },
+        }
+    }
+
+    fn strings(&self, name: &str) -> Vec<String> {
+        self.as_view()
+            .as_node()
+            .get_property(name)
+            .map(|prop| prop.as_str_iter().map(|s| s.to_owned()).collect())
+            .unwrap_or_default()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.as_view().as_node().get_property(name).is_some()
+    }
+
+    fn u32_value(&self, name: &str) -> Option<u32> {
+        self.as_view()
+            .as_node()
+            .get_property(name)
+            .and_then(|prop| prop.get_u32())
+    }
+
+    /// Returns the `pins` property: the pin names this node applies to.
+    pub fn pins(&self) -> Vec<String> {
+        self.strings("pins")
+    }
+
+    /// Returns the `groups` property: the pin group names this node
+    /// applies to.
+    pub fn groups(&self) -> Vec<String> {
+        self.strings("groups")
+    }
+
+    /// Returns the `function` property: the mux function to select.
+    pub fn function(&self) -> Option<String> {
+        self.strings("function").into_iter().next()
+    }
+
+    /// Returns the bias setting, if the node has one.
+    pub fn bias(&self) -> Option<PinBias> {
+        let node = self.as_view().as_node();
+        let argument = |name: &str| {
+            node.get_property(name)
+                .map(|prop| prop.get_u32().filter(|&value| value != 0))
+        };
+
+        if self.flag("bias-disable") {
+            Some(PinBias::Disable)
+        } else if self.flag("bias-high-impedance") {
+            Some(PinBias::HighImpedance)
+        } else if self.flag("bias-bus-hold") {
+            Some(PinBias::BusHold)
+        } else if let Some(strength) = argument("bias-pull-up") {
+            Some(PinBias::PullUp(strength))
+        } else if let Some(strength) = argument("bias-pull-down") {
+            Some(PinBias::PullDown(strength))
+        } else if self.flag("bias-pull-pin-default") {
+            Some(PinBias::PullPinDefault)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `drive-strength` property, in mA or a vendor level.
+    pub fn drive_strength(&self) -> Option<u32> {
+        self.u32_value("drive-strength")
+    }
+
+    /// Returns the `drive-strength-microamp` property.
+    pub fn drive_strength_microamp(&self) -> Option<u32> {
+        self.u32_value("drive-strength-microamp")
+    }
+
+    /// Returns `true` if the node has `input-enable`.
+    pub fn input_enable(&self) -> bool {
+        self.flag("input-enable")
+    }
+
+    /// Returns `true` if the node has `input-schmitt-enable`.
+    pub fn input_schmitt_enable(&self) -> bool {
+        self.flag("input-schmitt-enable")
+    }
+
+    /// Returns the level set by `output-high` (`true`) or `output-low`
+    /// (`false`).
+    pub fn output_level(&self) -> Option<bool> {
+        if self.flag("output-high") {
+            Some(true)
+        } else if self.flag("output-low") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `slew-rate` property.
+    pub fn slew_rate(&self) -> Option<u32> {
+        self.u32_value("slew-rate")
+    }
+
+    /// Returns the cells of a vendor-specific property such as
+    /// `pinmux` or `fsl,pins`.
+    pub fn raw_cells(&self, name: &str) -> Vec<u32> {
+        self.as_view()
+            .as_node()
+            .get_property(name)
+            .map(|prop| prop.get_u32_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Decodes the Rockchip `rockchip,pins` property.
+    ///
+    /// A trailing partial entry is ignored.
+    pub fn rockchip_pins(&self) -> Vec<RockchipPin> {
+        self.raw_cells("rockchip,pins")
+            .chunks_exact(4)
+            .map(|entry| RockchipPin {
+                bank: entry[0],
+                pin: entry[1],
+                mux: entry[2],
+                config: Phandle::from(entry[3]),
+            })
+            .collect()
+    }
+
+    /// Returns the `pcfg-*` configuration node of a Rockchip pin entry.
+    pub fn rockchip_config(&self, pin: &RockchipPin) -> Option<PinctrlConfigView<'a>> {
+        let fdt = self.as_view().fdt();
+        let id = fdt.get_by_phandle_id(pin.config)?;
+        Some(Self::from_view(NodeView::new(fdt, id)))
+    }
+}
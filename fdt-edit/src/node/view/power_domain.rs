@@ -0,0 +1,115 @@
+//! Power domain provider node view specialization.
+
+use core::ops::Deref;
+
+use alloc::{string::String, vec::Vec};
+use fdt_raw::Phandle;
+
+use super::NodeView;
+use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+/// Power domain reference, used to parse the `power-domains` property.
+///
+/// The format is `<&provider specifier...>`; the number of specifier cells
+/// comes from the provider's `#power-domain-cells`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerDomainRef {
+    /// Domain name, from the `power-domain-names` property
+    pub name: Option<String>,
+    /// Phandle of the power domain provider
+    pub provider: Phandle,
+    /// `#power-domain-cells` value of the provider
+    pub cells: u32,
+    /// Specifier cells, usually the domain index
+    pub specifier: Vec<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// PowerDomainView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for power domain providers (`#power-domain-cells`).
+#[derive(Clone, Copy)]
+pub struct PowerDomainView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for PowerDomainView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for PowerDomainView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> PowerDomainView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_power_domain_provider() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `#power-domain-cells` property value.
+    pub fn power_domain_cells(&self) -> u32 {
+        self.as_view()
+            .as_node()
+            .get_property("#power-domain-cells")
+            .and_then(|prop| prop.get_u32())
+            .unwrap_or(0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PowerDomainViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for power domain providers.
+pub struct PowerDomainViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for PowerDomainViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for PowerDomainViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for PowerDomainViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        n.set_property(Property::new(
+            "#power-domain-cells",
+            1u32.to_be_bytes().to_vec(),
+        ));
+        s
+    }
+}
+
+impl<'a> PowerDomainViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_power_domain_provider() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+}
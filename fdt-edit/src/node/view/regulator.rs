@@ -0,0 +1,215 @@
+//! Regulator node view specialization.
+
+use core::ops::Deref;
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use fdt_raw::Phandle;
+
+use super::NodeView;
+use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+/// Supply reference, used to parse `*-supply` properties.
+///
+/// Each property holds a single regulator phandle; the reference carries
+/// the regulator's constraints so consumers need not look it up again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SupplyRef {
+    /// Supply name, the property name without `-supply`
+    pub name: String,
+    /// Phandle of the regulator
+    pub regulator: Phandle,
+    /// `regulator-name` of the regulator
+    pub regulator_name: Option<String>,
+    /// `regulator-min-microvolt` of the regulator
+    pub min_microvolt: Option<u32>,
+    /// `regulator-max-microvolt` of the regulator
+    pub max_microvolt: Option<u32>,
+    /// The regulator has `regulator-always-on`
+    pub always_on: bool,
+    /// The regulator has `regulator-boot-on`
+    pub boot_on: bool,
+}
+
+/// The `*-supply` properties of a node.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Supplies {
+    /// Supplies whose phandle leads to a node, in property order
+    pub resolved: Vec<SupplyRef>,
+    /// Supply names and phandles that do not resolve to a node
+    pub unresolved: Vec<(String, Phandle)>,
+}
+
+// ---------------------------------------------------------------------------
+// RegulatorView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for regulator nodes.
+///
+/// A node is a regulator if it has `regulator-name` or a `regulator-*`
+/// compatible such as `regulator-fixed`.
+#[derive(Clone, Copy)]
+pub struct RegulatorView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for RegulatorView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for RegulatorView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> RegulatorView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_regulator() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `regulator-name` property.
+    pub fn regulator_name(&self) -> Option<String> {
+        self.as_view()
+            .as_node()
+            .get_property("regulator-name")
+            .and_then(|prop| prop.as_str())
+            .map(|s| s.to_owned())
+    }
+
+    /// Returns the `regulator-min-microvolt` property.
+    pub fn min_microvolt(&self) -> Option<u32> {
+        self.as_view()
+            .as_node()
+            .get_property("regulator-min-microvolt")
+            .and_then(|prop| prop.get_u32())
+    }
+
+    /// Returns the `regulator-max-microvolt` property.
+    pub fn max_microvolt(&self) -> Option<u32> {
+        self.as_view()
+            .as_node()
+            .get_property("regulator-max-microvolt")
+            .and_then(|prop| prop.get_u32())
+    }
+
+    /// Returns `true` if the regulator must never be disabled.
+    pub fn always_on(&self) -> bool {
+        self.as_view()
+            .as_node()
+            .get_property("regulator-always-on")
+            .is_some()
+    }
+
+    /// Returns `true` if the bootloader left the regulator enabled.
+    pub fn boot_on(&self) -> bool {
+        self.as_view()
+            .as_node()
+            .get_property("regulator-boot-on")
+            .is_some()
+    }
+
+    pub(super) fn supply_ref(&self, name: &str, regulator: Phandle) -> SupplyRef {
+        SupplyRef {
+            name: name.to_owned(),
+            regulator,
+            regulator_name: self.regulator_name(),
+            min_microvolt: self.min_microvolt(),
+            max_microvolt: self.max_microvolt(),
+            always_on: self.always_on(),
+            boot_on: self.boot_on(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RegulatorViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for regulator nodes.
+pub struct RegulatorViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for RegulatorViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for RegulatorViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for RegulatorViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        let mut prop = Property::new("regulator-name", Vec::new());
+        prop.set_string(n.name());
+        n.set_property(prop);
+        s
+    }
+}
+
+impl<'a> RegulatorViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_regulator() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sets the `regulator-name` property.
+    pub fn set_regulator_name(&mut self, name: &str) {
+        let mut prop = Property::new("regulator-name", Vec::new());
+        prop.set_string(name);
+        self.inner.inner.as_node_mut().set_property(prop);
+    }
+
+    /// Sets `regulator-min-microvolt` and `regulator-max-microvolt`.
+    pub fn set_microvolt_range(&mut self, min: u32, max: u32) {
+        let n = self.inner.inner.as_node_mut();
+        n.set_property(Property::new(
+            "regulator-min-microvolt",
+            min.to_be_bytes().to_vec(),
+        ));
+        n.set_property(Property::new(
+            "regulator-max-microvolt",
+            max.to_be_bytes().to_vec(),
+        ));
+    }
+
+    /// Adds or removes `regulator-always-on`.
+    pub fn set_always_on(&mut self, always_on: bool) {
+        self.set_flag("regulator-always-on", always_on);
+    }
+
+    /// Adds or removes `regulator-boot-on`.
+    pub fn set_boot_on(&mut self, boot_on: bool) {
+        self.set_flag("regulator-boot-on", boot_on);
+    }
+
+    fn set_flag(&mut self, name: &str, value: bool) {
+        let n = self.inner.inner.as_node_mut();
+        if value {
+            n.set_property(Property::new(name, Vec::new()));
+        } else {
+            n.remove_property(name);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Reset controller node view specialization.
+
+use core::ops::Deref;
+
+use alloc::{string::String, vec::Vec};
+use fdt_raw::Phandle;
+
+use super::NodeView;
+use crate::{NodeGeneric, NodeGenericMut, Property, ViewMutOp, ViewOp};
+
+/// Reset reference, used to parse the `resets` property.
+///
+/// The format is `<&controller specifier...>`; the number of specifier
+/// cells comes from the controller's `#reset-cells`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResetRef {
+    /// Reset name, from the `reset-names` property
+    pub name: Option<String>,
+    /// Phandle of the reset controller
+    pub controller: Phandle,
+    /// `#reset-cells` value of the controller
+    pub cells: u32,
+    /// Specifier cells, usually the reset line
+    pub specifier: Vec<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// ResetControllerView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for reset controllers (`#reset-cells`).
+///
+/// Clock controllers often provide resets as well; such nodes are
+/// classified as clock providers.
+#[derive(Clone, Copy)]
+pub struct ResetControllerView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for ResetControllerView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for ResetControllerView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ResetControllerView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_reset_controller() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the `#reset-cells` property value.
+    pub fn reset_cells(&self) -> u32 {
+        self.as_view()
+            .as_node()
+            .get_property("#reset-cells")
+            .and_then(|prop| prop.get_u32())
+            .unwrap_or(0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ResetControllerViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for reset controllers.
+pub struct ResetControllerViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for ResetControllerViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for ResetControllerViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for ResetControllerViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        n.set_property(Property::new("#reset-cells", 1u32.to_be_bytes().to_vec()));
+        s
+    }
+}
+
+impl<'a> ResetControllerViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_reset_controller() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+}
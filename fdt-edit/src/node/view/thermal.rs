@@ -0,0 +1,347 @@
+//! Thermal zone node view specialization.
+//!
+//! Thermal zones live under `/thermal-zones`. Each zone reads one or more
+//! sensors, defines trip points under `trips` and binds trips to cooling
+//! devices under `cooling-maps`.
+
+use core::ops::Deref;
+
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use fdt_raw::Phandle;
+
+use super::{NodeView, PhandleArgs, cells_property};
+use crate::{Fdt, NodeGeneric, NodeGenericMut, NodeId, Property, ViewMutOp, ViewOp};
+
+/// `cooling-device` state value meaning "no limit".
+pub const THERMAL_NO_LIMIT: u32 = u32::MAX;
+
+/// Thermal sensor reference, used to parse the `thermal-sensors` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThermalSensorRef {
+    /// Phandle of the sensor
+    pub sensor: Phandle,
+    /// `#thermal-sensor-cells` value of the sensor
+    pub cells: u32,
+    /// Specifier cells, usually the sensor channel
+    pub specifier: Vec<u32>,
+}
+
+/// Kind of a trip point, from its `type` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TripType {
+    /// `active`: turn on active cooling such as a fan
+    Active,
+    /// `passive`: throttle the cooling devices
+    Passive,
+    /// `hot`: notify the system
+    Hot,
+    /// `critical`: shut the system down
+    Critical,
+    /// Any other value
+    Other(String),
+}
+
+/// A trip point of a thermal zone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThermalTrip {
+    /// Node name of the trip
+    pub name: String,
+    /// Phandle of the trip node, used by `cooling-maps`
+    pub phandle: Option<Phandle>,
+    /// Trip temperature in millicelsius
+    pub temperature: i32,
+    /// Hysteresis in millicelsius
+    pub hysteresis: u32,
+    /// Trip kind
+    pub kind: TripType,
+}
+
+/// One entry of a cooling map's `cooling-device` property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoolingDeviceRef {
+    /// Phandle of the cooling device, such as a CPU or fan
+    pub device: Phandle,
+    /// Specifier cells, sized by the device's `#cooling-cells`
+    pub specifier: Vec<u32>,
+}
+
+impl CoolingDeviceRef {
+    /// Returns the lowest cooling state to use, `None` for no limit.
+    pub fn min_state(&self) -> Option<u32> {
+        self.specifier
+            .first()
+            .copied()
+            .filter(|&s| s != THERMAL_NO_LIMIT)
+    }
+
+    /// Returns the highest cooling state to use, `None` for no limit.
+    pub fn max_state(&self) -> Option<u32> {
+        self.specifier
+            .get(1)
+            .copied()
+            .filter(|&s| s != THERMAL_NO_LIMIT)
+    }
+}
+
+/// A cooling map of a thermal zone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoolingMap {
+    /// Node name of the map
+    pub name: String,
+    /// Phandle of the trip that activates the map
+    pub trip: Option<Phandle>,
+    /// Cooling devices driven by the map
+    pub devices: Vec<CoolingDeviceRef>,
+    /// Relative weight among the maps of the zone
+    pub contribution: Option<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// ThermalZoneView
+// ---------------------------------------------------------------------------
+
+/// Specialized view for thermal zone nodes (with `thermal-sensors`).
+#[derive(Clone, Copy)]
+pub struct ThermalZoneView<'a> {
+    pub(super) inner: NodeGeneric<'a>,
+}
+
+impl<'a> Deref for ThermalZoneView<'a> {
+    type Target = NodeGeneric<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for ThermalZoneView<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ThermalZoneView<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_thermal_zone() {
+            Some(Self {
+                inner: NodeGeneric { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    fn u32_value(&self, name: &str) -> Option<u32> {
+        self.as_view()
+            .as_node()
+            .get_property(name)
+            .and_then(|prop| prop.get_u32())
+    }
+
+    /// Returns the `polling-delay` in milliseconds.
+    pub fn polling_delay(&self) -> Option<u32> {
+        self.u32_value("polling-delay")
+    }
+
+    /// Returns the `polling-delay-passive` in milliseconds, used while
+    /// passive cooling is active.
+    pub fn polling_delay_passive(&self) -> Option<u32> {
+        self.u32_value("polling-delay-passive")
+    }
+
+    /// Returns the `sustainable-power` in milliwatts.
+    pub fn sustainable_power(&self) -> Option<u32> {
+        self.u32_value("sustainable-power")
+    }
+
+    /// Parses the `thermal-sensors` property.
+    ///
+    /// Each entry's width comes from the sensor's `#thermal-sensor-cells`;
+    /// parsing stops at a sensor without it.
+    pub fn thermal_sensors(&self) -> Vec<ThermalSensorRef> {
+        let view = self.as_view();
+        let Some(prop) = view.as_node().get_property("thermal-sensors") else {
+            return Vec::new();
+        };
+
+        PhandleArgs::new(view.fdt(), prop, cells_property("#thermal-sensor-cells"))
+            .resolved()
+            .map(|target| ThermalSensorRef {
+                sensor: target.phandle,
+                cells: target.cells,
+                specifier: target.specifier,
+            })
+            .collect()
+    }
+
+    fn subnodes(&self, name: &str) -> Vec<NodeId> {
+        let view = self.as_view();
+        let fdt = view.fdt();
+        view.as_node()
+            .children()
+            .iter()
+            .find(|&&id| fdt.node(id).is_some_and(|node| node.name() == name))
+            .and_then(|&id| fdt.node(id))
+            .map(|node| node.children().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Returns the trip points under `trips`.
+    ///
+    /// Trips without a `temperature` are skipped.
+    pub fn trips(&self) -> Vec<ThermalTrip> {
+        let fdt = self.as_view().fdt();
+        self.subnodes("trips")
+            .into_iter()
+            .filter_map(|id| {
+                let node = fdt.node(id)?;
+                let temperature = node.get_property("temperature")?.get_u32()? as i32;
+                let kind = match node.get_property("type").and_then(|prop| prop.as_str()) {
+                    Some("active") => TripType::Active,
+                    Some("passive") => TripType::Passive,
+                    Some("hot") => TripType::Hot,
+                    Some("critical") => TripType::Critical,
+                    other => TripType::Other(other.unwrap_or_default().to_owned()),
+                };
+                Some(ThermalTrip {
+                    name: node.name().to_owned(),
+                    phandle: node.phandle(),
+                    temperature,
+                    hysteresis: node
+                        .get_property("hysteresis")
+                        .and_then(|prop| prop.get_u32())
+                        .unwrap_or(0),
+                    kind,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the cooling maps under `cooling-maps`.
+    ///
+    /// Each `cooling-device` entry's width comes from the device's
+    /// `#cooling-cells`; parsing of a map stops at a device without it.
+    pub fn cooling_maps(&self) -> Vec<CoolingMap> {
+        let fdt = self.as_view().fdt();
+        self.subnodes("cooling-maps")
+            .into_iter()
+            .filter_map(|id| {
+                let node = fdt.node(id)?;
+                let devices = node
+                    .get_property("cooling-device")
+                    .map(|prop| {
+                        PhandleArgs::new(fdt, prop, cells_property("#cooling-cells"))
+                            .resolved()
+                            .map(|target| CoolingDeviceRef {
+                                device: target.phandle,
+                                specifier: target.specifier,
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(CoolingMap {
+                    name: node.name().to_owned(),
+                    trip: node
+                        .get_property("trip")
+                        .and_then(|prop| prop.get_u32())
+                        .map(Phandle::from),
+                    devices,
+                    contribution: node
+                        .get_property("contribution")
+                        .and_then(|prop| prop.get_u32()),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the trip with phandle `phandle`, as referenced by a cooling
+    /// map.
+    pub fn trip(&self, phandle: Phandle) -> Option<ThermalTrip> {
+        self.trips()
+            .into_iter()
+            .find(|trip| trip.phandle == Some(phandle))
+    }
+
+    /// Returns `true` if a cooling map of this zone drives `device`.
+    pub fn is_cooled_by(&self, device: Phandle) -> bool {
+        self.cooling_maps()
+            .iter()
+            .any(|map| map.devices.iter().any(|dev| dev.device == device))
+    }
+}
+
+impl Fdt {
+    /// Returns the thermal zones under `/thermal-zones`, in tree order.
+    pub fn thermal_zones(&self) -> Vec<ThermalZoneView<'_>> {
+        let Some(id) = self.get_by_path_id("/thermal-zones") else {
+            return Vec::new();
+        };
+        self.node(id)
+            .map(|node| node.children())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&child| ThermalZoneView::try_from_view(NodeView::new(self, child)))
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ThermalZoneViewMut
+// ---------------------------------------------------------------------------
+
+/// Mutable view for thermal zone nodes.
+pub struct ThermalZoneViewMut<'a> {
+    pub(super) inner: NodeGenericMut<'a>,
+}
+
+impl<'a> Deref for ThermalZoneViewMut<'a> {
+    type Target = NodeGenericMut<'a>;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ViewOp<'a> for ThermalZoneViewMut<'a> {
+    fn as_view(&self) -> NodeView<'a> {
+        self.inner.as_view()
+    }
+}
+
+impl<'a> ViewMutOp<'a> for ThermalZoneViewMut<'a> {
+    fn new(node: NodeGenericMut<'a>) -> Self {
+        let mut s = Self { inner: node };
+        let n = s.inner.inner.as_node_mut();
+        n.set_property(Property::new("thermal-sensors", Vec::new()));
+        s
+    }
+}
+
+impl<'a> ThermalZoneViewMut<'a> {
+    pub(crate) fn try_from_view(view: NodeView<'a>) -> Option<Self> {
+        if view.as_node().is_thermal_zone() {
+            Some(Self {
+                inner: NodeGenericMut { inner: view },
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Sets `polling-delay` and `polling-delay-passive`, in milliseconds.
+    pub fn set_polling_delays(&mut self, delay: u32, passive: u32) {
+        let n = self.inner.inner.as_node_mut();
+        n.set_property(Property::new("polling-delay", delay.to_be_bytes().to_vec()));
+        n.set_property(Property::new(
+            "polling-delay-passive",
+            passive.to_be_bytes().to_vec(),
+        ));
+    }
+
+    /// Sets the `thermal-sensors` property to one sensor reference.
+    pub fn set_thermal_sensor(&mut self, sensor: Phandle, specifier: &[u32]) {
+        let mut prop = Property::new("thermal-sensors", Vec::new());
+        let mut cells = vec![sensor.raw()];
+        cells.extend_from_slice(specifier);
+        prop.set_u32_ls(&cells);
+        self.inner.inner.as_node_mut().set_property(prop);
+    }
+}
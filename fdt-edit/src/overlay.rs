@@ -0,0 +1,333 @@
+//! Device tree overlay application.
+//!
+//! Implements the same algorithm as libfdt's `fdt_overlay_apply`:
+//!
+//! 1. Every `phandle` in the overlay is shifted past the largest phandle of
+//!    the base tree, and the references listed in `__local_fixups__` are
+//!    shifted by the same amount.
+//! 2. External references listed in `__fixups__` are resolved against the
+//!    base tree's `__symbols__` node.
+//! 3. The `__overlay__` subtree of every `fragment@N` is merged into the node
+//!    selected by its `target` phandle or `target-path` string.
+//! 4. Labels exported by the overlay's `__symbols__` are rewritten to their
+//!    final location and added to the base tree's `__symbols__`.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{Fdt, Node, NodeId, Phandle, Property};
+
+/// Errors that can occur while applying a device tree overlay.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum OverlayError {
+    /// A fragment has neither a `target` nor a `target-path` property.
+    #[error("fragment `{0}` has no `target` or `target-path`")]
+    MissingTarget(String),
+    /// The node selected by a fragment's target does not exist in the base tree.
+    #[error("target `{target}` of fragment `{fragment}` not found")]
+    TargetNotFound {
+        /// Path of the fragment in the overlay
+        fragment: String,
+        /// Target phandle or path, as written in the fragment
+        target: String,
+    },
+    /// A label referenced by `__fixups__` is not defined in the base `__symbols__`.
+    #[error("label `{0}` not found in base `__symbols__`")]
+    LabelNotFound(String),
+    /// The node a label points to does not carry a `phandle` property.
+    #[error("node `{path}` referenced by label `{label}` has no phandle")]
+    MissingPhandle {
+        /// The label being resolved
+        label: String,
+        /// Path of the labelled node in the base tree
+        path: String,
+    },
+    /// A `__fixups__` entry is not of the form `path:property:offset`.
+    #[error("malformed fixup `{0}`")]
+    InvalidFixup(String),
+    /// A `__fixups__` or `__local_fixups__` entry points outside its property.
+    #[error("fixup for `{path}:{property}` at offset {offset} is out of bounds")]
+    FixupOutOfBounds {
+        /// Path of the patched node in the overlay
+        path: String,
+        /// Name of the patched property
+        property: String,
+        /// Byte offset of the phandle cell
+        offset: usize,
+    },
+}
+
+impl Fdt {
+    /// Applies a device tree overlay (DTBO) on top of this tree.
+    ///
+    /// The overlay must have been compiled with symbol support (`dtc -@`) if
+    /// it references labels of the base tree. References are resolved through
+    /// the base tree's `__symbols__` node, so the base must also carry one.
+    ///
+    /// On error the base tree may have been partially modified.
+    pub fn apply_overlay(&mut self, overlay: &Fdt) -> Result<(), OverlayError> {
+        let mut overlay = overlay.clone();
+
+        let delta = self.max_phandle();
+        overlay.adjust_local_phandles(delta);
+        overlay.update_local_references(delta)?;
+        overlay.fixup_phandles(self)?;
+
+        let root = overlay.root_id();
+        let fragments: Vec<NodeId> = overlay.node(root).unwrap().children().to_vec();
+        let mut targets: Vec<(String, String)> = Vec::new();
+
+        for fragment_id in fragments {
+            let fragment = overlay.node(fragment_id).unwrap();
+            let Some(overlay_id) = fragment.get_child("__overlay__") else {
+                continue;
+            };
+            let target_id = self.overlay_target(&overlay, fragment_id)?;
+            self.merge_overlay_node(target_id, &overlay, overlay_id);
+            targets.push((overlay.path_of(fragment_id), self.path_of(target_id)));
+        }
+
+        self.rebuild_phandle_cache();
+        self.merge_overlay_symbols(&overlay, &targets);
+        Ok(())
+    }
+
+    /// Shifts every `phandle`/`linux,phandle` value in the tree by `delta`.
+    fn adjust_local_phandles(&mut self, delta: u32) {
+        let ids: Vec<NodeId> = self.iter_node_ids().collect();
+        for id in ids {
+            let node = self.node_mut(id).unwrap();
+            for name in ["phandle", "linux,phandle"] {
+                if let Some(prop) = node.get_property_mut(name)
+                    && let Some(value) = prop.get_u32()
+                {
+                    prop.set_u32_ls(&[value.wrapping_add(delta)]);
+                }
+            }
+        }
+        self.rebuild_phandle_cache();
+    }
+
+    /// Shifts the phandle cells listed in `__local_fixups__` by `delta`.
+    fn update_local_references(&mut self, delta: u32) -> Result<(), OverlayError> {
+        let Some(fixups_id) = self.get_by_path_id("/__local_fixups__") else {
+            return Ok(());
+        };
+        let root = self.root_id();
+        self.update_local_references_node(fixups_id, root, delta)
+    }
+
+    fn update_local_references_node(
+        &mut self,
+        fixups_id: NodeId,
+        target_id: NodeId,
+        delta: u32,
+    ) -> Result<(), OverlayError> {
+        let fixups = self.node(fixups_id).unwrap().clone();
+
+        for fixup in fixups.properties() {
+            let offsets: Vec<usize> = fixup.get_u32_iter().map(|v| v as usize).collect();
+            for offset in offsets {
+                self.add_to_cell(target_id, &fixup.name, offset, delta)?;
+            }
+        }
+
+        for &child_fixups in fixups.children() {
+            let name = self.node(child_fixups).unwrap().name.clone();
+            let Some(child_target) = self.node(target_id).unwrap().get_child(&name) else {
+                continue;
+            };
+            self.update_local_references_node(child_fixups, child_target, delta)?;
+        }
+        Ok(())
+    }
+
+    /// Adds `delta` to the big-endian u32 cell at `offset` in a property.
+    fn add_to_cell(
+        &mut self,
+        id: NodeId,
+        prop_name: &str,
+        offset: usize,
+        delta: u32,
+    ) -> Result<(), OverlayError> {
+        let path = self.path_of(id);
+        let out_of_bounds = || OverlayError::FixupOutOfBounds {
+            path: path.clone(),
+            property: prop_name.to_string(),
+            offset,
+        };
+        let prop = self
+            .node_mut(id)
+            .unwrap()
+            .get_property_mut(prop_name)
+            .ok_or_else(out_of_bounds)?;
+        let cell = offset
+            .checked_add(4)
+            .and_then(|end| prop.data.get_mut(offset..end))
+            .ok_or_else(out_of_bounds)?;
+        let value = u32::from_be_bytes([cell[0], cell[1], cell[2], cell[3]]);
+        cell.copy_from_slice(&value.wrapping_add(delta).to_be_bytes());
+        Ok(())
+    }
+
+    /// Resolves the references listed in `__fixups__` against `base`'s `__symbols__`.
+    fn fixup_phandles(&mut self, base: &Fdt) -> Result<(), OverlayError> {
+        let Some(fixups_id) = self.get_by_path_id("/__fixups__") else {
+            return Ok(());
+        };
+        let fixups = self.node(fixups_id).unwrap().clone();
+        let symbols = base
+            .get_by_path_id("/__symbols__")
+            .and_then(|id| base.node(id));
+
+        for fixup in fixups.properties() {
+            let label = fixup.name();
+            let path = symbols
+                .and_then(|s| s.get_property(label))
+                .and_then(|p| p.as_str())
+                .ok_or_else(|| OverlayError::LabelNotFound(label.to_string()))?;
+            let phandle = base
+                .get_by_path_id(path)
+                .and_then(|id| base.node(id))
+                .ok_or_else(|| OverlayError::LabelNotFound(label.to_string()))?
+                .phandle()
+                .ok_or_else(|| OverlayError::MissingPhandle {
+                    label: label.to_string(),
+                    path: path.to_string(),
+                })?;
+
+            for entry in fixup.as_str_iter() {
+                self.apply_fixup(entry, phandle)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `phandle` at the location described by a `path:property:offset` entry.
+    fn apply_fixup(&mut self, entry: &str, phandle: Phandle) -> Result<(), OverlayError> {
+        let invalid = || OverlayError::InvalidFixup(entry.to_string());
+        let (rest, offset) = entry.rsplit_once(':').ok_or_else(invalid)?;
+        let (path, prop_name) = rest.rsplit_once(':').ok_or_else(invalid)?;
+        let offset: usize = offset.parse().map_err(|_| invalid())?;
+        let id = self.get_by_path_id(path).ok_or_else(invalid)?;
+
+        let prop = self
+            .node_mut(id)
+            .unwrap()
+            .get_property_mut(prop_name)
+            .ok_or_else(invalid)?;
+        let cell = offset
+            .checked_add(4)
+            .and_then(|end| prop.data.get_mut(offset..end))
+            .ok_or_else(|| OverlayError::FixupOutOfBounds {
+                path: path.to_string(),
+                property: prop_name.to_string(),
+                offset,
+            })?;
+        cell.copy_from_slice(&phandle.raw().to_be_bytes());
+        Ok(())
+    }
+
+    /// Finds the base-tree node a fragment applies to.
+    fn overlay_target(&self, overlay: &Fdt, fragment_id: NodeId) -> Result<NodeId, OverlayError> {
+        let fragment = overlay.node(fragment_id).unwrap();
+        let fragment_path = overlay.path_of(fragment_id);
+
+        if let Some(phandle) = fragment.get_property("target").and_then(|p| p.get_u32()) {
+            return self
+                .get_by_phandle_id(Phandle::from(phandle))
+                .ok_or_else(|| OverlayError::TargetNotFound {
+                    fragment: fragment_path,
+                    target: format!("<{:#x}>", phandle),
+                });
+        }
+
+        if let Some(path) = fragment
+            .get_property("target-path")
+            .and_then(|p| p.as_str())
+        {
+            return self
+                .get_by_path_id(path)
+                .ok_or_else(|| OverlayError::TargetNotFound {
+                    fragment: fragment_path,
+                    target: path.to_string(),
+                });
+        }
+
+        Err(OverlayError::MissingTarget(fragment_path))
+    }
+
+    /// Recursively merges the overlay node `overlay_id` into `target_id`.
+    fn merge_overlay_node(&mut self, target_id: NodeId, overlay: &Fdt, overlay_id: NodeId) {
+        let overlay_node = overlay.node(overlay_id).unwrap();
+
+        let target = self.node_mut(target_id).unwrap();
+        for prop in overlay_node.properties() {
+            target.set_property(prop.clone());
+        }
+
+        for &child in overlay_node.children() {
+            let name = &overlay.node(child).unwrap().name;
+            let child_target = match self.node(target_id).unwrap().get_child(name) {
+                Some(id) => id,
+                None => self.add_node(target_id, Node::new(name)),
+            };
+            self.merge_overlay_node(child_target, overlay, child);
+        }
+    }
+
+    /// Copies the overlay's exported labels into the base `__symbols__` node.
+    ///
+    /// `targets` maps each applied fragment path to its target path in the base.
+    fn merge_overlay_symbols(&mut self, overlay: &Fdt, targets: &[(String, String)]) {
+        let Some(symbols) = overlay
+            .get_by_path_id("/__symbols__")
+            .and_then(|id| overlay.node(id))
+        else {
+            return;
+        };
+
+        let mut resolved = Vec::new();
+        for prop in symbols.properties() {
+            let Some(path) = prop.as_str() else {
+                continue;
+            };
+            for (fragment, target) in targets {
+                let Some(rest) = path
+                    .strip_prefix(fragment.as_str())
+                    .and_then(|p| p.strip_prefix("/__overlay__"))
+                else {
+                    continue;
+                };
+                if !rest.is_empty() && !rest.starts_with('/') {
+                    continue;
+                }
+                let new_path = if target == "/" && !rest.is_empty() {
+                    rest.to_string()
+                } else {
+                    format!("{}{}", target, rest)
+                };
+                resolved.push((prop.name.clone(), new_path));
+                break;
+            }
+        }
+
+        if resolved.is_empty() {
+            return;
+        }
+
+        let root = self.root_id();
+        let symbols_id = match self.node(root).unwrap().get_child("__symbols__") {
+            Some(id) => id,
+            None => self.add_node(root, Node::new("__symbols__")),
+        };
+        let node = self.node_mut(symbols_id).unwrap();
+        for (label, path) in resolved {
+            let mut prop = Property::new(&label, Vec::new());
+            prop.set_string(&path);
+            node.set_property(prop);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Placement of dynamic `/reserved-memory` regions.
+//!
+//! A dynamic region only gives a `size`, optionally with an `alignment` and
+//! a list of `alloc-ranges` it must fall in. Firmware that hands a tree to
+//! the OS can place such regions itself and record the result as a `reg`,
+//! turning them into static regions.
+//!
+//! Placement follows the Linux early allocator: regions are handled in
+//! tree order and each one goes at the highest suitable address of the
+//! memory that is still free. Free memory is every `/memory` range minus
+//! the `/memreserve/` entries, the static regions and the regions placed
+//! before it.
+
+use alloc::{string::String, vec::Vec};
+
+use fdt_raw::RegInfo;
+
+use crate::{Fdt, NodeId, NodeType, Property};
+
+/// Alignment used for dynamic regions without an `alignment` property.
+pub const DEFAULT_RESERVED_ALIGNMENT: u64 = 0x1000;
+
+/// Errors that can occur while placing dynamic reserved memory regions.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReservedMemoryError {
+    /// The tree has no `/memory` node with a non-empty range.
+    #[error("no memory described by `/memory` nodes")]
+    NoMemory,
+    /// No free range can hold a dynamic region.
+    #[error("no room for `{path}` ({size:#x} bytes, aligned to {alignment:#x})")]
+    NoSpace {
+        /// Path of the dynamic region
+        path: String,
+        /// Requested size in bytes
+        size: u64,
+        /// Requested alignment in bytes
+        alignment: u64,
+    },
+}
+
+/// Address chosen for a dynamic reserved memory region.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReservedMemoryPlacement {
+    /// Path of the region node
+    pub path: String,
+    /// Chosen base address
+    pub address: u64,
+    /// Size of the region in bytes
+    pub size: u64,
+}
+
+/// Half-open address range `[start, end)`.
+#[derive(Clone, Copy, Debug)]
+struct Span {
+    start: u64,
+    end: u64,
+}
+
+impl Span {
+    fn new(address: u64, size: u64) -> Self {
+        Self {
+            start: address,
+            end: address.saturating_add(size),
+        }
+    }
+}
+
+/// A dynamic region waiting for an address.
+struct DynamicRegion {
+    id: NodeId,
+    size: u64,
+    alignment: u64,
+    alloc_ranges: Vec<Span>,
+}
+
+impl Fdt {
+    /// Computes addresses for the dynamic regions of `/reserved-memory`
+    /// without modifying the tree.
+    ///
+    /// Regions with `status = "disabled"` are ignored.
+    pub fn plan_reserved_memory(
+        &self,
+    ) -> Result<Vec<ReservedMemoryPlacement>, ReservedMemoryError> {
+        let mut free: Vec<Span> = self
+            .all_nodes()
+            .filter_map(|node| match node {
+                NodeType::Memory(memory) => Some(memory.regions()),
+                _ => None,
+            })
+            .flatten()
+            .filter(|region| region.size > 0)
+            .map(|region| Span::new(region.address, region.size))
+            .collect();
+        if free.is_empty() {
+            return Err(ReservedMemoryError::NoMemory);
+        }
+        free.sort_by_key(|span| span.start);
+
+        for rsv in &self.memory_reservations {
+            subtract(&mut free, Span::new(rsv.address, rsv.size));
+        }
+
+        let (statics, dynamics) = self.reserved_memory_nodes();
+        for span in statics {
+            subtract(&mut free, span);
+        }
+
+        let mut placements = Vec::new();
+        for region in dynamics {
+            let address = place(&free, &region).ok_or_else(|| ReservedMemoryError::NoSpace {
+                path: self.path_of(region.id),
+                size: region.size,
+                alignment: region.alignment,
+            })?;
+            subtract(&mut free, Span::new(address, region.size));
+            placements.push(ReservedMemoryPlacement {
+                path: self.path_of(region.id),
+                address,
+                size: region.size,
+            });
+        }
+        Ok(placements)
+    }
+
+    /// Places the dynamic regions of `/reserved-memory` and writes each
+    /// chosen address back as the region's `reg`.
+    ///
+    /// See [`Fdt::plan_reserved_memory`] for how addresses are chosen. The
+    /// tree is left unchanged on error.
+    pub fn allocate_reserved_memory(
+        &mut self,
+    ) -> Result<Vec<ReservedMemoryPlacement>, ReservedMemoryError> {
+        let placements = self.plan_reserved_memory()?;
+        for placement in &placements {
+            if let Some(mut node) = self.get_by_path_mut(&placement.path) {
+                node.set_regs(&[RegInfo::new(placement.address, Some(placement.size))]);
+            }
+        }
+        Ok(placements)
+    }
+
+    /// Splits the available children of `/reserved-memory` into the
+    /// ranges of static regions and the dynamic regions, in tree order.
+    fn reserved_memory_nodes(&self) -> (Vec<Span>, Vec<DynamicRegion>) {
+        let mut statics = Vec::new();
+        let mut dynamics = Vec::new();
+        let Some(parent_id) = self.get_by_path_id("/reserved-memory") else {
+            return (statics, dynamics);
+        };
+        let parent = self.node(parent_id).unwrap();
+        let address_cells = parent.address_cells().unwrap_or(2) as usize;
+        let size_cells = parent.size_cells().unwrap_or(1) as usize;
+
+        for &id in parent.children() {
+            let Some(node) = self.node(id) else {
+                continue;
+            };
+            if node.status() == Some(fdt_raw::Status::Disabled) {
+                continue;
+            }
+
+            if let Some(reg) = node.get_property("reg") {
+                statics.extend(read_spans(reg, address_cells, size_cells));
+            } else if let Some(size) = read_size(node.get_property("size"), size_cells) {
+                let alignment = read_size(node.get_property("alignment"), size_cells)
+                    .filter(|&a| a > 0)
+                    .unwrap_or(DEFAULT_RESERVED_ALIGNMENT);
+                let alloc_ranges = node
+                    .get_property("alloc-ranges")
+                    .map(|prop| read_spans(prop, address_cells, size_cells))
+                    .unwrap_or_default();
+                dynamics.push(DynamicRegion {
+                    id,
+                    size,
+                    alignment,
+                    alloc_ranges,
+                });
+            }
+        }
+        (statics, dynamics)
+    }
+}
+
+/// Reads `(address, size)` pairs from a `reg`-style property.
+///
+/// Zero-width pairs carry no data, so there are none to read.
+fn read_spans(prop: &Property, address_cells: usize, size_cells: usize) -> Vec<Span> {
+    let mut spans = Vec::new();
+    if address_cells + size_cells == 0 {
+        return spans;
+    }
+    let mut reader = prop.as_reader();
+    while let (Some(address), Some(size)) = (
+        reader.read_cells(address_cells),
+        reader.read_cells(size_cells),
+    ) {
+        spans.push(Span::new(address, size));
+    }
+    spans
+}
+
+/// Reads a single `#size-cells` wide value.
+fn read_size(prop: Option<&Property>, size_cells: usize) -> Option<u64> {
+    let prop = prop?;
+    if size_cells == 0 || prop.data.len() != size_cells * 4 {
+        return None;
+    }
+    prop.as_reader().read_cells(size_cells)
+}
+
+/// Removes `hole` from the sorted, disjoint `free` list.
+fn subtract(free: &mut Vec<Span>, hole: Span) {
+    let mut result = Vec::with_capacity(free.len() + 1);
+    for span in free.iter() {
+        if hole.end <= span.start || hole.start >= span.end {
+            result.push(*span);
+            continue;
+        }
+        if span.start < hole.start {
+            result.push(Span {
+                start: span.start,
+                end: hole.start,
+            });
+        }
+        if hole.end < span.end {
+            result.push(Span {
+                start: hole.end,
+                end: span.end,
+            });
+        }
+    }
+    *free = result;
+}
+
+/// Finds the highest aligned address where `region` fits in `free`.
+fn place(free: &[Span], region: &DynamicRegion) -> Option<u64> {
+    let anywhere = [Span {
+        start: 0,
+        end: u64::MAX,
+    }];
+    let windows = if region.alloc_ranges.is_empty() {
+        &anywhere[..]
+    } else {
+        &region.alloc_ranges[..]
+    };
+
+    let mut best: Option<u64> = None;
+    for span in free {
+        for window in windows {
+            let start = span.start.max(window.start);
+            let end = span.end.min(window.end);
+            let Some(top) = end.checked_sub(region.size) else {
+                continue;
+            };
+            let address = top - top % region.alignment;
+            if address >= start && best.is_none_or(|b| address > b) {
+                best = Some(address);
+            }
+        }
+    }
+    best
+}
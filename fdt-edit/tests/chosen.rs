@@ -0,0 +1,132 @@
+//! `/chosen` node view tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, MemoryRegion, NodeType, Parity, StdoutOptions};
+
+#[test]
+fn test_chosen_view() {
+    let raw_data = fdt_phytium();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let Some(NodeType::Chosen(chosen)) = fdt.get_by_path("/chosen") else {
+        panic!("/chosen should be classified as Chosen");
+    };
+    assert!(chosen.bootargs().unwrap().contains("console=ttyAMA1"));
+    assert_eq!(chosen.stdout_path(), Some("serial1:115200n8"));
+    let options = chosen.stdout_options().unwrap();
+    assert_eq!(
+        (options.baud, options.parity, options.bits),
+        (115200, Parity::None, 8)
+    );
+
+    let raw_data = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+    let chosen = fdt.chosen().unwrap();
+    assert_eq!(chosen.kaslr_seed(), Some(0x1b3e_3a33_2187_3fc3));
+    assert_eq!(chosen.rng_seed().unwrap().len(), 32);
+}
+
+#[test]
+fn test_chosen_mut_creates_node() {
+    let mut fdt =
+        Fdt::from_dts("/dts-v1/; / { #address-cells = <1>; #size-cells = <1>; };").unwrap();
+    assert!(fdt.chosen().is_none());
+
+    let mut chosen = fdt.chosen_mut();
+    chosen.set_bootargs("console=ttyS0");
+    chosen.set_stdout_path(
+        "/serial@1000",
+        Some(StdoutOptions {
+            baud: 9600,
+            parity: Parity::Even,
+            bits: 7,
+            flow_control: false,
+        }),
+    );
+    chosen.set_initrd(0x4800_0000..0x4880_0000);
+    chosen.set_kaslr_seed(0x0123_4567_89ab_cdef);
+    chosen.set_rng_seed(&[1, 2, 3, 4]);
+    chosen.set_elfcorehdr(MemoryRegion {
+        address: 0x7000_0000,
+        size: 0x1000,
+    });
+    chosen.set_booted_from_kexec(true);
+    chosen.set_bootconf("conf-1");
+
+    // Reuses the node instead of adding a second one
+    fdt.chosen_mut().set_bootargs("console=ttyS1");
+    let root = fdt.get_by_path("/").unwrap();
+    assert_eq!(root.as_node().children().len(), 1);
+
+    // Single cells under #address-cells = <1>
+    let node = fdt.get_by_path("/chosen").unwrap();
+    let start = node.as_node().get_property("linux,initrd-start").unwrap();
+    assert_eq!(start.data.len(), 4);
+    let elfcorehdr = node.as_node().get_property("linux,elfcorehdr").unwrap();
+    assert_eq!(elfcorehdr.data.len(), 8);
+
+    // Round trip through the blob and the zero-copy parser
+    let data = fdt.encode();
+    let raw = fdt_raw::Fdt::from_bytes(&data).unwrap();
+    let chosen = raw.chosen().unwrap();
+    assert_eq!(chosen.bootargs(), Some("console=ttyS1"));
+    assert_eq!(chosen.stdout_path(), Some("/serial@1000:9600e7"));
+    assert_eq!(chosen.initrd(), Some(0x4800_0000..0x4880_0000));
+    assert_eq!(chosen.kaslr_seed(), Some(0x0123_4567_89ab_cdef));
+    assert_eq!(chosen.rng_seed(), Some(&[1u8, 2, 3, 4][..]));
+    assert_eq!(
+        chosen.elfcorehdr(),
+        Some(fdt_raw::MemoryRegion {
+            address: 0x7000_0000,
+            size: 0x1000
+        })
+    );
+    assert!(chosen.booted_from_kexec());
+    assert_eq!(chosen.bootconf(), Some("conf-1"));
+}
+
+#[test]
+fn test_chosen_mut_wide_cells() {
+    let mut fdt =
+        Fdt::from_dts("/dts-v1/; / { #address-cells = <2>; #size-cells = <2>; chosen { }; };")
+            .unwrap();
+
+    let mut chosen = fdt.chosen_mut();
+    chosen.set_initrd(0x1_0000_0000..0x1_0100_0000);
+    chosen.set_usable_memory_range(&[
+        MemoryRegion {
+            address: 0x8000_0000,
+            size: 0x1000_0000,
+        },
+        MemoryRegion {
+            address: 0x1_0000_0000,
+            size: 0x2000_0000,
+        },
+    ]);
+    chosen.set_booted_from_kexec(false);
+
+    let node = fdt.get_by_path("/chosen").unwrap();
+    let start = node.as_node().get_property("linux,initrd-start").unwrap();
+    assert_eq!(start.data.len(), 8);
+
+    let chosen = fdt.chosen().unwrap();
+    assert_eq!(chosen.initrd(), Some(0x1_0000_0000..0x1_0100_0000));
+    let ranges = chosen.usable_memory_range();
+    assert_eq!(ranges.len(), 2);
+    assert_eq!(ranges[1].address, 0x1_0000_0000);
+    assert_eq!(ranges[1].size, 0x2000_0000);
+    assert!(!chosen.booted_from_kexec());
+}
+
+#[test]
+fn test_chosen_zero_cells() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/; / { #address-cells = <0>; #size-cells = <0>; \
+         chosen { linux,usable-memory-range = <>; linux,elfcorehdr = <>; }; };",
+    )
+    .unwrap();
+
+    let chosen = fdt.chosen().unwrap();
+    assert!(chosen.usable_memory_range().is_empty());
+    assert_eq!(chosen.elfcorehdr(), None);
+}
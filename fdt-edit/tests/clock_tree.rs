@@ -0,0 +1,267 @@
+//! Clock tree graph and rate computation tests.
+
+use dtb_file::*;
+use fdt_edit::{ClockError, ClockOutput, Fdt, Phandle};
+
+#[test]
+fn test_clock_tree_rk3568() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+    let tree = fdt.clock_tree();
+
+    let cru = fdt.get_by_path_id("/clock-controller@fdd20000").unwrap();
+    let pmucru = fdt.get_by_path_id("/clock-controller@fdd00000").unwrap();
+
+    // The UART baud clock is only set by the driver.
+    let uart = fdt.get_by_path_id("/serial@fe660000").unwrap();
+    let clocks = tree.clocks_of(uart);
+    assert_eq!(clocks.len(), 2);
+    assert_eq!(clocks[0].name.as_deref(), Some("baudclk"));
+    assert_eq!(
+        clocks[0].output,
+        Some(ClockOutput {
+            provider: cru,
+            specifier: vec![0x123],
+        })
+    );
+    assert!(matches!(
+        tree.rate_of(&clocks[0]),
+        Err(ClockError::Unknown { .. })
+    ));
+
+    // Set up at boot by the CRU's own assigned-clock-rates.
+    let aclk = ClockOutput {
+        provider: cru,
+        specifier: vec![0x106],
+    };
+    assert_eq!(tree.rate(&aclk), Ok(300_000_000));
+    assert_eq!(
+        tree.rate(&ClockOutput {
+            provider: pmucru,
+            specifier: vec![0x5],
+        }),
+        Ok(32768)
+    );
+    assert_eq!(
+        tree.assigned_parent(&aclk).unwrap().phandle,
+        Phandle::from(0x23)
+    );
+
+    let gmac = fdt.get_by_phandle_id(Phandle::from(0x92)).unwrap();
+    assert_eq!(
+        tree.rate(&ClockOutput {
+            provider: gmac,
+            specifier: vec![],
+        }),
+        Ok(125_000_000)
+    );
+
+    assert!(tree.consumers_of(cru).contains(&uart));
+    assert!(tree.dangling().is_empty());
+    assert!(tree.cycles().is_empty());
+
+    let text = tree.to_string();
+    assert!(text.contains("/serial@fe660000 \"baudclk\" <0x123> ? Hz"));
+}
+
+const CLOCK_DTS: &str = "/dts-v1/;
+/ {
+    osc: osc {
+        compatible = \"fixed-clock\";
+        #clock-cells = <0>;
+        clock-frequency = <24000000>;
+    };
+
+    gated: gated {
+        compatible = \"gated-fixed-clock\";
+        #clock-cells = <0>;
+        clock-frequency = <100000000>;
+    };
+
+    pll: pll {
+        compatible = \"fixed-factor-clock\";
+        #clock-cells = <0>;
+        clocks = <&osc>;
+        clock-mult = <50>;
+        clock-div = <1>;
+    };
+
+    cru: cru {
+        #clock-cells = <1>;
+        clocks = <&pll>, <&gated>;
+        assigned-clocks = <&cru 1>, <&cru 2>, <&cru 3>;
+        assigned-clock-parents = <&pll>, <0>, <&cru 1>;
+        assigned-clock-rates = <0>, <200000000>;
+    };
+
+    loop_a: loop-a {
+        compatible = \"fixed-factor-clock\";
+        #clock-cells = <0>;
+        clocks = <&loop_b>;
+    };
+
+    loop_b: loop-b {
+        compatible = \"fixed-factor-clock\";
+        #clock-cells = <0>;
+        clocks = <&loop_a>;
+    };
+
+    uart {
+        clocks = <&cru 1>, <&cru 2>, <&cru 3>, <&cru 4>;
+        clock-names = \"baud\", \"bus\", \"ref\", \"debug\";
+    };
+
+    broken {
+        clocks = <&osc>, <0x1234 1>, <&gated>;
+    };
+
+    looped {
+        clocks = <&loop_a>;
+    };
+};";
+
+#[test]
+fn test_clock_tree_rates() {
+    let fdt = Fdt::from_dts(CLOCK_DTS).unwrap();
+    let tree = fdt.clock_tree();
+
+    let uart = fdt.get_by_path_id("/uart").unwrap();
+    let rates: Vec<_> = tree
+        .clocks_of(uart)
+        .iter()
+        .map(|link| tree.rate_of(link))
+        .collect();
+    // Reparented to the PLL, assigned a rate, reparented to another output.
+    assert_eq!(rates[0], Ok(1_200_000_000));
+    assert_eq!(rates[1], Ok(200_000_000));
+    assert_eq!(rates[2], Ok(1_200_000_000));
+    assert!(
+        matches!(rates[3], Err(ClockError::Unknown { ref path, ref specifier })
+        if path == "/cru" && specifier == &[4])
+    );
+
+    let gated = fdt.get_by_path_id("/gated").unwrap();
+    assert_eq!(
+        tree.rate(&ClockOutput {
+            provider: gated,
+            specifier: vec![],
+        }),
+        Ok(100_000_000)
+    );
+}
+
+#[test]
+fn test_clock_tree_problems() {
+    let fdt = Fdt::from_dts(CLOCK_DTS).unwrap();
+    let tree = fdt.clock_tree();
+
+    let broken = fdt.get_by_path_id("/broken").unwrap();
+    let dangling = tree.dangling();
+    assert_eq!(dangling.len(), 1);
+    assert_eq!(dangling[0].node, broken);
+    assert_eq!(dangling[0].property, "clocks");
+    assert_eq!(dangling[0].phandle, Phandle::from(0x1234));
+
+    // Parsing stops at the unknown provider, whose cell count is unknown.
+    let clocks = tree.clocks_of(broken);
+    assert_eq!(clocks.len(), 2);
+    assert_eq!(
+        tree.rate_of(&clocks[1]),
+        Err(ClockError::Dangling(Phandle::from(0x1234)))
+    );
+
+    let looped = fdt.get_by_path_id("/looped").unwrap();
+    assert!(matches!(
+        tree.rate_of(&tree.clocks_of(looped)[0]),
+        Err(ClockError::Cycle(_))
+    ));
+
+    let loop_a = fdt.get_by_path_id("/loop-a").unwrap();
+    let loop_b = fdt.get_by_path_id("/loop-b").unwrap();
+    let cycles = tree.cycles();
+    assert_eq!(cycles.len(), 1);
+    let providers: Vec<_> = cycles[0].iter().map(|output| output.provider).collect();
+    assert!(providers.contains(&loop_a) && providers.contains(&loop_b));
+
+    let text = tree.to_string();
+    assert!(text.contains("dangling: /broken clocks -> <0x1234>"));
+    assert!(text.contains("cycle: "));
+    assert!(text.contains("/loop-a [fixed-factor-clock]"));
+}
+
+#[test]
+fn test_clock_tree_rate_overflow() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            osc: osc {
+                compatible = \"fixed-clock\";
+                #clock-cells = <0>;
+                clock-frequency = <4000000000>;
+            };
+            x1: x1 {
+                compatible = \"fixed-factor-clock\";
+                #clock-cells = <0>;
+                clocks = <&osc>;
+                clock-mult = <4000000000>;
+                clock-div = <1>;
+            };
+            x2: x2 {
+                compatible = \"fixed-factor-clock\";
+                #clock-cells = <0>;
+                clocks = <&x1>;
+                clock-mult = <4000000000>;
+                clock-div = <1>;
+            };
+        };",
+    )
+    .unwrap();
+    let tree = fdt.clock_tree();
+
+    let output = |path| ClockOutput {
+        provider: fdt.get_by_path_id(path).unwrap(),
+        specifier: vec![],
+    };
+    assert_eq!(tree.rate(&output("/x1")), Ok(16_000_000_000_000_000_000));
+    assert_eq!(
+        tree.rate(&output("/x2")),
+        Err(ClockError::Overflow("/x2".into()))
+    );
+}
+
+#[test]
+fn test_clock_tree_render() {
+    let fdt = Fdt::from_dts(CLOCK_DTS).unwrap();
+    let text = fdt.clock_tree().to_string();
+    let lines: Vec<&str> = text.lines().collect();
+
+    let osc = lines
+        .iter()
+        .position(|l| *l == "/osc [fixed-clock] 24000000 Hz")
+        .unwrap();
+    assert_eq!(lines[osc + 1], "  /pll [fixed-factor-clock] 1200000000 Hz");
+    assert_eq!(lines[osc + 2], "    /cru [provider]");
+    assert!(lines.contains(&"      /uart \"bus\" <0x2> 200000000 Hz"));
+    assert!(lines.contains(&"      /uart \"debug\" <0x4> ? Hz"));
+}
+
+#[test]
+fn test_clock_tree_dangling_assigned_clocks() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            setup {
+                assigned-clocks = <0x4321>;
+                assigned-clock-rates = <100000000>;
+            };
+        };",
+    )
+    .unwrap();
+    let tree = fdt.clock_tree();
+
+    assert_eq!(tree.dangling().len(), 1);
+    assert!(
+        tree.to_string()
+            .contains("dangling: /setup assigned-clocks -> <0x4321>")
+    );
+}
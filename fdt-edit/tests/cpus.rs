@@ -0,0 +1,100 @@
+//! CPU node view tests.
+
+use dtb_file::*;
+use fdt_edit::{EnableMethod, Fdt, NodeType, NodeTypeMut};
+
+#[test]
+fn test_cpu_node_detection() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let cpus: Vec<_> = fdt
+        .all_nodes()
+        .filter_map(|node| match node {
+            NodeType::Cpu(cpu) => Some(cpu),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(cpus.len(), 4);
+
+    let hwids: Vec<_> = cpus.iter().map(|cpu| cpu.hwid().unwrap()).collect();
+    assert_eq!(hwids, [0x0, 0x100, 0x200, 0x300]);
+    for cpu in &cpus {
+        assert_eq!(cpu.enable_method(), Some(EnableMethod::Psci));
+        let opp = fdt.get_by_phandle(cpu.operating_points_v2().unwrap());
+        assert!(
+            opp.unwrap()
+                .as_node()
+                .compatibles()
+                .any(|c| c == "operating-points-v2")
+        );
+    }
+}
+
+#[test]
+fn test_cpu_topology() {
+    let raw_data = fdt_phytium();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let Some(NodeType::Cpu(cpu)) = fdt.get_by_path("/cpus/cpu@101") else {
+        panic!("cpu@101 should be a CPU node");
+    };
+    assert_eq!(cpu.hwid(), Some(0x100));
+    let topology = cpu.topology().unwrap();
+    assert_eq!((topology.cluster, topology.core), (Some(2), 0));
+
+    let map = fdt.cpu_map();
+    assert_eq!(map.len(), 4);
+    assert!(map.iter().all(|entry| entry.socket.is_none()));
+}
+
+#[test]
+fn test_cpu_spin_table_and_caches() {
+    let raw_data = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let Some(NodeType::Cpu(cpu)) = fdt.get_by_path("/cpus/cpu@2") else {
+        panic!("cpu@2 should be a CPU node");
+    };
+    assert_eq!(cpu.hwid(), Some(2));
+    assert_eq!(
+        cpu.enable_method(),
+        Some(EnableMethod::SpinTable {
+            cpu_release_addr: Some(0xe8)
+        })
+    );
+    let caches: Vec<_> = cpu.caches().iter().map(|cache| cache.path()).collect();
+    assert_eq!(caches, ["/cpus/l2-cache0"]);
+
+    let raw_data = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+    let Some(NodeType::Cpu(cpu)) = fdt.get_by_path("/cpus/cpu@700") else {
+        panic!("cpu@700 should be a CPU node");
+    };
+    let caches: Vec<_> = cpu.caches().iter().map(|cache| cache.path()).collect();
+    assert_eq!(caches, ["/cpus/l2-cache-b3", "/cpus/l3-cache"]);
+}
+
+#[test]
+fn test_add_cpu_node() {
+    let mut fdt = Fdt::new();
+    let NodeTypeMut::Generic(mut root) = fdt.root_mut() else {
+        panic!("root should be generic");
+    };
+    let mut cpus = root.add_child_generic("cpus");
+    let mut cpu = cpus.add_child_cpu("cpu@0");
+    cpu.set_enable_method(EnableMethod::SpinTable {
+        cpu_release_addr: Some(0x8000_00d8),
+    });
+
+    let Some(NodeType::Cpu(cpu)) = fdt.get_by_path("/cpus/cpu@0") else {
+        panic!("cpu@0 should be a CPU node");
+    };
+    assert_eq!(cpu.cpu_release_addr(), Some(0x8000_00d8));
+    assert_eq!(
+        cpu.enable_method(),
+        Some(EnableMethod::SpinTable {
+            cpu_release_addr: Some(0x8000_00d8)
+        })
+    );
+}
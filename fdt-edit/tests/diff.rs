@@ -0,0 +1,135 @@
+//! Tree diff and patch tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, NodeMove, PropCell, PropValue, diff};
+
+const OLD: &str = r#"
+/dts-v1/;
+/ {
+    clk: clock { #clock-cells = <1>; phandle = <1>; };
+    osc: osc { #clock-cells = <0>; phandle = <2>; };
+    bus-a {
+        uart@100 { compatible = "ns16550a"; clocks = <&clk 3>; };
+    };
+    bus-b { };
+    gone { status = "okay"; };
+};
+"#;
+
+const NEW: &str = r#"
+/dts-v1/;
+/ {
+    clk: clock { #clock-cells = <1>; phandle = <7>; };
+    osc: osc { #clock-cells = <0>; phandle = <8>; };
+    bus-a { };
+    bus-b {
+        uart@100 { compatible = "ns16550a"; clocks = <&clk 3>, <&osc>; };
+    };
+    extra { clocks = <&osc>; };
+};
+"#;
+
+#[test]
+fn test_diff_identical() {
+    let raw = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    assert!(diff(&fdt, &fdt.clone()).is_empty());
+}
+
+#[test]
+fn test_diff_ignores_renumbered_phandles() {
+    let old = Fdt::from_dts(OLD).unwrap();
+    let renumbered = Fdt::from_dts(
+        &OLD.replace("phandle = <1>", "phandle = <0x40>")
+            .replace("phandle = <2>", "phandle = <0x41>"),
+    )
+    .unwrap();
+    assert!(diff(&old, &renumbered).is_empty());
+}
+
+#[test]
+fn test_diff_changes() {
+    let old = Fdt::from_dts(OLD).unwrap();
+    let new = Fdt::from_dts(NEW).unwrap();
+    let diff = diff(&old, &new);
+
+    assert_eq!(
+        diff.moved,
+        [NodeMove {
+            from: "/bus-a/uart@100".into(),
+            to: "/bus-b/uart@100".into(),
+        }]
+    );
+    assert_eq!(diff.added, ["/extra"]);
+    assert_eq!(diff.removed, ["/gone"]);
+
+    assert_eq!(diff.properties.len(), 2);
+    let clocks = &diff.properties[0];
+    assert_eq!(clocks.path, "/bus-b/uart@100");
+    assert_eq!(
+        clocks.new,
+        Some(PropValue::Cells(vec![
+            PropCell::Ref("/clock".into()),
+            PropCell::Value(3),
+            PropCell::Ref("/osc".into()),
+        ]))
+    );
+    assert_eq!(
+        (
+            diff.properties[1].path.as_str(),
+            diff.properties[1].old.as_ref()
+        ),
+        ("/extra", None)
+    );
+
+    let text = diff.to_string();
+    assert!(text.contains("@@ /bus-a/uart@100 moved to /bus-b/uart@100 @@\n"));
+    assert!(text.contains(
+        "@@ /bus-b/uart@100 @@\n-\tclocks = <&{/clock} 0x3>;\n+\tclocks = <&{/clock} 0x3 &{/osc}>;\n"
+    ));
+    assert!(text.contains("@@ /extra (added) @@\n+\tclocks = <&{/osc}>;\n"));
+    assert!(text.contains("@@ /gone (removed) @@\n"));
+}
+
+#[test]
+fn test_patch_third_tree() {
+    let old = Fdt::from_dts(OLD).unwrap();
+    let new = Fdt::from_dts(NEW).unwrap();
+    let diff = diff(&old, &new);
+
+    // Same shape as the old tree, but with other phandles and an extra node
+    let mut third = Fdt::from_dts(
+        &OLD.replace("phandle = <1>", "phandle = <0x20>")
+            .replace("phandle = <2>", "phandle = <0x21>")
+            .replace("bus-b { };", "bus-b { }; keep { };"),
+    )
+    .unwrap();
+    diff.apply(&mut third).unwrap();
+
+    let rest = fdt_edit::diff(&third, &new);
+    assert!(rest.moved.is_empty() && rest.properties.is_empty());
+    assert_eq!(rest.removed, ["/keep"]);
+
+    let uart = third.get_by_path("/bus-b/uart@100").unwrap();
+    let clocks: Vec<u32> = uart
+        .as_node()
+        .get_property("clocks")
+        .unwrap()
+        .get_u32_iter()
+        .collect();
+    assert_eq!(clocks, [0x20, 3, 0x21]);
+}
+
+#[test]
+fn test_patch_between_boards() {
+    let old = Fdt::from_bytes(&fdt_3568()).unwrap();
+    let new = Fdt::from_bytes(&fdt_orangepi_5plus()).unwrap();
+
+    let diff = diff(&old, &new);
+    assert!(!diff.is_empty());
+
+    let mut patched = old.clone();
+    diff.apply(&mut patched).unwrap();
+    let rest = fdt_edit::diff(&patched, &new);
+    assert!(rest.is_empty(), "{rest}");
+}
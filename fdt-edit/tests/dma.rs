@@ -0,0 +1,302 @@
+//! IOMMU, MSI and DMA mapping tests.
+
+use dtb_file::*;
+use fdt_edit::{DmaRange, Fdt, FdtError, MappedId, NodeType, PciNodeView, Phandle};
+
+fn pci_node<'a>(fdt: &'a Fdt, path: &str) -> PciNodeView<'a> {
+    let NodeType::Pci(pci) = fdt.get_by_path(path).unwrap() else {
+        panic!("{path} should be a PCI node");
+    };
+    pci
+}
+
+#[test]
+fn test_iommus_zero_cells() {
+    let raw_data = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let cif = fdt.get_by_path("/rkcif-dvp").unwrap();
+    let iommus = cif.iommus();
+    assert_eq!(iommus.len(), 1);
+    assert_eq!(iommus[0].iommu, Phandle::from(0x51));
+    assert_eq!(iommus[0].cells, 0);
+    assert!(iommus[0].specifier.is_empty());
+}
+
+#[test]
+fn test_pci_rid_translation() {
+    let raw_data = fdt_phytium();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+    let pci = pci_node(&fdt, "/soc/pcie@40000000");
+
+    assert_eq!(pci.iommu_map().len(), 1);
+    assert_eq!(
+        pci.iommu_stream_id(1, 2, 3).unwrap(),
+        MappedId {
+            target: Phandle::from(0x11),
+            id: 0x113,
+        }
+    );
+
+    // No msi-map: the requester ID goes to the ITS unchanged.
+    assert!(pci.msi_map().is_empty());
+    assert_eq!(pci.msi_parents()[0].controller, Phandle::from(0x10));
+    assert_eq!(
+        pci.msi_device_id(1, 2, 3).unwrap(),
+        MappedId {
+            target: Phandle::from(0x10),
+            id: 0x113,
+        }
+    );
+
+    assert!(pci.is_dma_coherent());
+}
+
+#[test]
+fn test_pci_msi_map() {
+    let raw_data = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+    let pci = pci_node(&fdt, "/pcie@10000000");
+
+    assert_eq!(
+        pci.msi_device_id(0, 3, 0).unwrap(),
+        MappedId {
+            target: Phandle::from(0x8003),
+            id: 0x18,
+        }
+    );
+    assert!(matches!(
+        pci.iommu_stream_id(0, 3, 0),
+        Err(FdtError::PropertyNotFound("iommu-map"))
+    ));
+    assert!(pci.is_dma_coherent());
+}
+
+#[test]
+fn test_dma_ranges_rpi() {
+    let raw_data = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let mmc = fdt.get_by_path("/emmc2bus/mmc@7e340000").unwrap();
+    let ranges = mmc.dma_ranges().unwrap();
+    assert_eq!(
+        ranges,
+        [DmaRange {
+            dma_address: 0xc000_0000,
+            cpu_address: 0,
+            size: 0x4000_0000,
+        }]
+    );
+    assert_eq!(ranges[0].offset(), -0xc000_0000);
+    assert_eq!(ranges[0].dma_to_cpu(0xc000_1000), Some(0x1000));
+    assert_eq!(ranges[0].cpu_to_dma(0x4000_0000), None);
+    assert!(!mmc.is_dma_coherent());
+
+    // The PCIe window sits inside the second window of /scb.
+    let pci = pci_node(&fdt, "/scb/pcie@7d500000");
+    assert_eq!(
+        pci.child_dma_ranges().unwrap(),
+        [DmaRange {
+            dma_address: 0,
+            cpu_address: 0,
+            size: 0xc000_0000,
+        }]
+    );
+}
+
+const DMA_DTS: &str = "/dts-v1/;
+/ {
+    #address-cells = <1>;
+    #size-cells = <1>;
+
+    its: msi-controller@1000 {
+        msi-controller;
+        #msi-cells = <1>;
+    };
+
+    smmu: iommu@2000 {
+        #iommu-cells = <1>;
+        dma-coherent;
+    };
+
+    bus@10000000 {
+        compatible = \"simple-bus\";
+        #address-cells = <1>;
+        #size-cells = <1>;
+        ranges;
+        dma-ranges = <0x0 0x80000000 0x10000000>, <0x40000000 0x0 0x1000>;
+        msi-parent = <&its 0x42>;
+        dma-coherent;
+
+        sub-bus {
+            compatible = \"simple-bus\";
+            #address-cells = <1>;
+            #size-cells = <1>;
+            ranges;
+            dma-ranges = <0x1000 0x100 0x1000>, <0x2000 0x20000000 0x1000>;
+            dma-noncoherent;
+
+            device {
+                iommus = <&smmu 7>, <&smmu 8>;
+                msi-map = <0x0 &its 0x100 0x10>;
+                msi-map-mask = <0xf>;
+            };
+        };
+
+        plain {
+        };
+
+        far-bus {
+            compatible = \"simple-bus\";
+            #address-cells = <1>;
+            #size-cells = <1>;
+            ranges;
+            dma-ranges = <0x0 0x30000000 0x1000>;
+
+            master {
+            };
+        };
+    };
+};";
+
+#[test]
+fn test_dma_ranges_nested() {
+    let fdt = Fdt::from_dts(DMA_DTS).unwrap();
+
+    let device = fdt.get_by_path("/bus@10000000/sub-bus/device").unwrap();
+    // 0x1000 -> 0x100 -> 0x80000100; 0x2000 falls outside the outer windows.
+    assert_eq!(
+        device.dma_ranges().unwrap(),
+        [DmaRange {
+            dma_address: 0x1000,
+            cpu_address: 0x8000_0100,
+            size: 0x1000,
+        }]
+    );
+    assert!(!device.is_dma_coherent());
+
+    let plain = fdt.get_by_path("/bus@10000000/plain").unwrap();
+    assert_eq!(plain.dma_ranges().unwrap().len(), 2);
+    assert!(plain.is_dma_coherent());
+
+    let smmu = fdt.get_by_path("/iommu@2000").unwrap();
+    assert_eq!(smmu.dma_ranges(), None);
+
+    // No window of far-bus lies inside the windows of its parent.
+    let master = fdt.get_by_path("/bus@10000000/far-bus/master").unwrap();
+    assert_eq!(master.dma_ranges(), Some(vec![]));
+}
+
+#[test]
+fn test_dma_ranges_overflow() {
+    let top = DmaRange {
+        dma_address: 0,
+        cpu_address: 0xffff_ffff_ffff_f000,
+        size: 0x2000,
+    };
+    assert_eq!(top.dma_to_cpu(0xfff), Some(u64::MAX));
+    assert_eq!(top.dma_to_cpu(0x1000), None);
+    assert_eq!(top.cpu_to_dma(0xffff_ffff_ffff_f800), Some(0x800));
+
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            #address-cells = <2>;
+            #size-cells = <2>;
+
+            bus {
+                #address-cells = <2>;
+                #size-cells = <2>;
+                ranges;
+                dma-ranges = <0x0 0x0 0xffffffff 0xfffff000 0x0 0x2000>;
+
+                sub-bus {
+                    #address-cells = <2>;
+                    #size-cells = <2>;
+                    ranges;
+                    dma-ranges = <0x0 0x0 0x0 0x800 0x0 0x100>,
+                                 <0x0 0x1000 0x0 0x1800 0x0 0x100>;
+
+                    device {
+                    };
+                };
+            };
+        };",
+    )
+    .unwrap();
+
+    // The second window would end up past the top of the CPU address space.
+    let device = fdt.get_by_path("/bus/sub-bus/device").unwrap();
+    assert_eq!(
+        device.dma_ranges().unwrap(),
+        [DmaRange {
+            dma_address: 0,
+            cpu_address: 0xffff_ffff_ffff_f800,
+            size: 0x100,
+        }]
+    );
+}
+
+#[test]
+fn test_iommus_and_msi_parent() {
+    let fdt = Fdt::from_dts(DMA_DTS).unwrap();
+
+    let device = fdt.get_by_path("/bus@10000000/sub-bus/device").unwrap();
+    let iommus = device.iommus();
+    assert_eq!(iommus.len(), 2);
+    assert_eq!(iommus[0].specifier, vec![7]);
+    assert_eq!(iommus[1].specifier, vec![8]);
+    assert_eq!(iommus[0].iommu, iommus[1].iommu);
+
+    // Inherited from the outer bus, with one #msi-cells specifier cell.
+    let plain = fdt.get_by_path("/bus@10000000/plain").unwrap();
+    let parents = plain.msi_parents();
+    assert_eq!(parents.len(), 1);
+    assert_eq!(parents[0].cells, 1);
+    assert_eq!(parents[0].specifier, vec![0x42]);
+    assert_eq!(device.msi_parents(), parents);
+
+    let NodeType::Generic(device) = device else {
+        panic!("device should be a generic node");
+    };
+    let map = device.msi_map();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map[0].target_base, 0x100);
+    assert_eq!(map[0].length, 0x10);
+}
+
+#[test]
+fn test_pci_id_map_mask() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            its: msi-controller {
+                msi-controller;
+            };
+            smmu: iommu {
+                #iommu-cells = <1>;
+            };
+            pcie {
+                device_type = \"pci\";
+                #address-cells = <3>;
+                #size-cells = <2>;
+                iommu-map = <0x0 &smmu 0x1000 0x100>;
+                iommu-map-mask = <0xff>;
+                msi-map = <0x100 &its 0x0 0x100>,
+                          <0x200 &its 0xffffff00 0x200>;
+            };
+        };",
+    )
+    .unwrap();
+    let pci = pci_node(&fdt, "/pcie");
+
+    // The mask drops the bus number before the lookup.
+    assert_eq!(pci.iommu_stream_id(3, 1, 2).unwrap().id, 0x100a);
+    assert_eq!(pci.msi_device_id(1, 0, 1).unwrap().id, 0x1);
+    assert!(matches!(
+        pci.msi_device_id(0, 1, 0),
+        Err(FdtError::NotFound)
+    ));
+    // Output IDs wrap around like Linux's u32 arithmetic.
+    assert_eq!(pci.msi_device_id(3, 0, 0).unwrap().id, 0);
+}
@@ -0,0 +1,260 @@
+//! Device tree source parser tests.
+
+use dtb_file::*;
+use fdt_edit::*;
+
+fn prop_u32s(fdt: &Fdt, path: &str, name: &str) -> Vec<u32> {
+    fdt.get_by_path(path)
+        .unwrap()
+        .as_node()
+        .get_property(name)
+        .unwrap()
+        .get_u32_iter()
+        .collect()
+}
+
+fn prop_data(fdt: &Fdt, path: &str, name: &str) -> Vec<u8> {
+    fdt.get_by_path(path)
+        .unwrap()
+        .as_node()
+        .get_property(name)
+        .unwrap()
+        .data
+        .clone()
+}
+
+fn parse_err(source: &str) -> DtsError {
+    match Fdt::from_dts(source) {
+        Ok(_) => panic!("source parsed without error"),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn test_from_dts_matches_dtc_output() {
+    let source = include_str!("../../dtb-file/src/dtb/test_reserve.dts");
+    let fdt = Fdt::from_dts(source).unwrap();
+
+    assert_eq!(fdt.memory_reservations.len(), 3);
+    assert_eq!(fdt.memory_reservations[1].address, 0x8000_0000);
+    assert_eq!(fdt.memory_reservations[1].size, 0x10_0000);
+
+    let raw = fdt_reserve();
+    assert_eq!(&fdt.encode()[..], &raw[..]);
+}
+
+#[test]
+fn test_from_dts_phandle_references() {
+    let fdt = Fdt::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            uart@1000 {
+                clocks = <&osc 1>, <&{/clocks/pll} 2>;
+                interrupt-parent = <&intc>;
+            };
+            intc: interrupt-controller {
+                phandle = <1>;
+                interrupt-controller;
+            };
+            clocks {
+                osc: osc { #clock-cells = <1>; };
+                pll { #clock-cells = <1>; };
+            };
+            aliases {
+                serial0 = &{/uart@1000};
+                osc = &osc;
+            };
+        };
+        "#,
+    )
+    .unwrap();
+
+    // `intc` keeps its explicit phandle, the others get the lowest free values.
+    assert_eq!(prop_u32s(&fdt, "/interrupt-controller", "phandle"), [1]);
+    assert_eq!(prop_u32s(&fdt, "/clocks/osc", "phandle"), [2]);
+    assert_eq!(prop_u32s(&fdt, "/clocks/pll", "phandle"), [3]);
+    assert_eq!(prop_u32s(&fdt, "/uart@1000", "clocks"), [2, 1, 3, 2]);
+    assert_eq!(prop_u32s(&fdt, "/uart@1000", "interrupt-parent"), [1]);
+
+    assert_eq!(
+        prop_data(&fdt, "/aliases", "serial0"),
+        b"/uart@1000\0".to_vec()
+    );
+    assert_eq!(
+        prop_data(&fdt, "/aliases", "osc"),
+        b"/clocks/osc\0".to_vec()
+    );
+
+    let osc = fdt.get_by_phandle(Phandle::from(2)).unwrap();
+    assert_eq!(osc.path(), "/clocks/osc");
+}
+
+#[test]
+fn test_from_dts_values() {
+    let fdt = Fdt::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            bytes = [00 12ab 34];
+            bits8 = /bits/ 8 <1 0xff 'a'>;
+            bits16 = /bits/ 16 <0x1234 (-1)>;
+            bits64 = /bits/ 64 <0x100000000>;
+            mixed = "a", <1>, [ff], "b\t\x41\101";
+            expr = <(1 << 4 | 2) (10 / 3 * 2) (1 ? 7 : 8) (~0)>;
+            empty;
+        };
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(prop_data(&fdt, "/", "bytes"), [0x00, 0x12, 0xab, 0x34]);
+    assert_eq!(prop_data(&fdt, "/", "bits8"), [1, 0xff, b'a']);
+    assert_eq!(prop_data(&fdt, "/", "bits16"), [0x12, 0x34, 0xff, 0xff]);
+    assert_eq!(prop_data(&fdt, "/", "bits64"), [0, 0, 0, 1, 0, 0, 0, 0]);
+    assert_eq!(
+        prop_data(&fdt, "/", "mixed"),
+        b"a\0\0\0\0\x01\xffb\tAA\0".to_vec()
+    );
+    assert_eq!(prop_u32s(&fdt, "/", "expr"), [0x12, 6, 7, 0xffff_ffff]);
+    assert!(prop_data(&fdt, "/", "empty").is_empty());
+}
+
+#[test]
+fn test_from_dts_merge_and_delete() {
+    let fdt = Fdt::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            soc {
+                uart0: serial@0 { status = "okay"; old; };
+                serial@1 { };
+                spi@2 { };
+            };
+        };
+        / {
+            soc {
+                serial@1 { status = "disabled"; };
+                /delete-node/ spi@2;
+            };
+        };
+        &uart0 {
+            status = "disabled";
+            /delete-property/ old;
+        };
+        /delete-node/ &{/soc/serial@1};
+        "#,
+    )
+    .unwrap();
+
+    let uart = fdt.get_by_path("/soc/serial@0").unwrap();
+    assert_eq!(
+        uart.as_node().get_property("status").unwrap().as_str(),
+        Some("disabled")
+    );
+    assert!(uart.as_node().get_property("old").is_none());
+    assert!(fdt.get_by_path("/soc/serial@1").is_none());
+    assert!(fdt.get_by_path("/soc/spi@2").is_none());
+}
+
+#[test]
+fn test_from_dts_errors() {
+    let err = parse_err("/ { };");
+    assert_eq!(err.kind, DtsErrorKind::MissingVersion);
+
+    let err = parse_err("/dts-v1/;\n/ {\n    clocks = <&missing>;\n};\n");
+    assert_eq!((err.line, err.column), (3, 15));
+    assert_eq!(
+        err.kind,
+        DtsErrorKind::UnresolvedReference("missing".into())
+    );
+
+    let err = parse_err("/dts-v1/;\n/ {\n  a = /bits/ 8 <256>;\n};");
+    assert_eq!((err.line, err.column), (3, 17));
+    assert_eq!(err.kind, DtsErrorKind::OutOfRange);
+
+    let err = parse_err("/dts-v1/;\n/ {\n  a = <1>\n};");
+    assert_eq!((err.line, err.column), (4, 1));
+    assert_eq!(err.kind, DtsErrorKind::Expected("`;`"));
+
+    let err = parse_err("/dts-v1/;\n/include/ \"foo.dtsi\"");
+    assert_eq!(err.kind, DtsErrorKind::Unsupported("/include/"));
+
+    let err = parse_err("/dts-v1/;\n/ { a = <1>;");
+    assert_eq!(err.kind, DtsErrorKind::UnexpectedEof);
+}
+
+fn assert_round_trip(raw: &[u8], options: &DtsOptions) {
+    let fdt = Fdt::from_bytes(raw).unwrap();
+    let dts = fdt.to_dts_with(options);
+    let reparsed = match Fdt::from_dts(&dts) {
+        Ok(fdt) => fdt,
+        Err(err) => panic!("{}\n{}", err, dts.lines().nth(err.line - 1).unwrap_or("")),
+    };
+    assert_eq!(&reparsed.encode()[..], &fdt.encode()[..]);
+}
+
+#[test]
+fn test_to_dts_round_trip() {
+    let options = [
+        DtsOptions::default(),
+        DtsOptions {
+            labels: LabelStyle::NodeNames,
+            hex: false,
+            ..Default::default()
+        },
+        DtsOptions {
+            labels: LabelStyle::None,
+            phandle_refs: false,
+            ..Default::default()
+        },
+    ];
+    for raw in [
+        &fdt_qemu()[..],
+        &fdt_rpi_4b()[..],
+        &fdt_phytium()[..],
+        &fdt_3568()[..],
+        &fdt_orangepi_5plus()[..],
+        &fdt_reserve()[..],
+    ] {
+        for options in &options {
+            assert_round_trip(raw, options);
+        }
+    }
+}
+
+#[test]
+fn test_to_dts_labels_and_references() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let dts = fdt.to_dts();
+
+    assert!(dts.starts_with("/dts-v1/;\n"));
+    assert!(dts.contains("\tuart0: serial@7e201000 {\n"));
+    assert!(dts.contains("clk_osc: clk-osc {\n"));
+    assert!(dts.contains("interrupt-parent = <&gicv2>;"));
+
+    let dts = fdt.to_dts_with(&DtsOptions {
+        labels: LabelStyle::None,
+        ..Default::default()
+    });
+    assert!(!dts.contains("uart0:"));
+    assert!(dts.contains("interrupt-parent = <&{/soc/interrupt-controller@40041000}>;"));
+}
+
+#[test]
+fn test_to_dts_memreserve_and_cells() {
+    let raw = fdt_reserve();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    let dts = fdt.to_dts();
+    assert!(dts.contains("/memreserve/ 0x40000000 0x4000000;\n"));
+    assert!(dts.contains("clock-frequency = <0x16e3600>;"));
+    assert!(dts.contains("compatible = \"arm,cortex-a53\";"));
+
+    let dts = fdt.to_dts_with(&DtsOptions {
+        hex: false,
+        ..Default::default()
+    });
+    assert!(dts.contains("clock-frequency = <24000000>;"));
+}
@@ -266,3 +266,92 @@ fn test_encode_properties_integrity() {
     let strings: Vec<&str> = prop_list.as_str_iter().collect();
     assert_eq!(strings, vec!["first", "second", "third"]);
 }
+
+/// 测试后缀合并缩小字符串块且不改变树的内容
+#[test]
+fn test_encode_suffix_merge() {
+    let raw_data = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let exact = fdt.encode();
+    let merged = fdt.encode_with(&EncodeOptions::new().dedup(StringDedup::SuffixMerge));
+    assert!(merged.len() < exact.len());
+
+    let exact_header = fdt_raw::Fdt::from_bytes(&exact).unwrap().header().clone();
+    let merged_fdt =
+        fdt_raw::Fdt::from_bytes_checked(&merged, fdt_raw::ValidationLevel::Strict).unwrap();
+    assert!(merged_fdt.header().size_dt_strings < exact_header.size_dt_strings);
+    assert_eq!(
+        merged_fdt.header().size_dt_struct,
+        exact_header.size_dt_struct
+    );
+
+    let reparsed = Fdt::from_bytes(&merged).unwrap();
+    assert_eq!(reparsed.to_dts(), fdt.to_dts());
+}
+
+/// 测试版本 16 的 header
+#[test]
+fn test_encode_version_16() {
+    let raw_data = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let encoded = fdt.encode_with(&EncodeOptions::new().version(FdtVersion::V16));
+    let parsed =
+        fdt_raw::Fdt::from_bytes_checked(&encoded, fdt_raw::ValidationLevel::Strict).unwrap();
+    assert_eq!(parsed.header().version, 16);
+    assert_eq!(parsed.header().last_comp_version, 16);
+    assert_eq!(parsed.header().size_dt_struct, 0);
+    assert_eq!(parsed.header().off_mem_rsvmap, 40);
+
+    let reparsed = Fdt::from_bytes(&encoded).unwrap();
+    assert_eq!(reparsed.to_dts(), fdt.to_dts());
+}
+
+/// 测试字符串块在前的布局
+#[test]
+fn test_encode_strings_first() {
+    let raw_data = fdt_reserve();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let encoded = fdt.encode_with(&EncodeOptions::new().block_order(BlockOrder::StringsFirst));
+    let parsed =
+        fdt_raw::Fdt::from_bytes_checked(&encoded, fdt_raw::ValidationLevel::Strict).unwrap();
+    let header = parsed.header();
+    assert!(header.off_dt_strings < header.off_dt_struct);
+    assert_eq!(
+        (header.off_dt_struct + header.size_dt_struct) as usize,
+        encoded.len()
+    );
+
+    let reparsed = Fdt::from_bytes(&encoded).unwrap();
+    assert_eq!(
+        reparsed.memory_reservations.len(),
+        fdt.memory_reservations.len()
+    );
+    assert_eq!(reparsed.to_dts(), fdt.to_dts());
+}
+
+/// 测试尾部填充与目标大小
+#[test]
+fn test_encode_padding() {
+    let raw_data = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+    let plain = fdt.encode();
+
+    let padded = fdt.encode_with(&EncodeOptions::new().padding(0x100));
+    assert_eq!(padded.len(), plain.len() + 0x100);
+    let header = fdt_raw::Fdt::from_bytes(&padded).unwrap().header().clone();
+    assert_eq!(header.totalsize as usize, padded.len());
+    assert_eq!(&padded[..plain.len()][8..], &plain[8..]);
+    assert!(padded[plain.len()..].iter().all(|&b| b == 0));
+
+    let sized = fdt.encode_with(&EncodeOptions::new().total_size(0x10000));
+    assert_eq!(sized.len(), 0x10000);
+    let reparsed = Fdt::from_bytes(&sized).unwrap();
+    assert_eq!(reparsed.to_dts(), fdt.to_dts());
+
+    // 内容已超过目标大小时不截断
+    let small = fdt.encode_with(&EncodeOptions::new().total_size(64));
+    assert_eq!(small.len(), plain.len());
+}
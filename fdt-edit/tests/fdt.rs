@@ -74,6 +74,7 @@ fn test_node_classify() {
 
     let mut memory_count = 0;
     let mut intc_count = 0;
+    let mut cpu_count = 0;
     let mut generic_count = 0;
 
     for view in fdt.all_nodes() {
@@ -110,6 +111,52 @@ fn test_node_classify() {
                     intc.interrupt_cells()
                 );
             }
+            NodeType::GpioController(gpio) => {
+                println!(
+                    "GPIO node: {} #gpio-cells={}",
+                    gpio.path(),
+                    gpio.gpio_cells()
+                );
+            }
+            NodeType::PowerDomain(pd) => {
+                println!(
+                    "Power domain node: {} #power-domain-cells={}",
+                    pd.path(),
+                    pd.power_domain_cells()
+                );
+            }
+            NodeType::ResetController(reset) => {
+                println!(
+                    "Reset node: {} #reset-cells={}",
+                    reset.path(),
+                    reset.reset_cells()
+                );
+            }
+            NodeType::Regulator(regulator) => {
+                println!(
+                    "Regulator node: {} name={:?}",
+                    regulator.path(),
+                    regulator.regulator_name()
+                );
+            }
+            NodeType::OppTable(table) => {
+                println!(
+                    "OPP table: {} opps={} shared={}",
+                    table.path(),
+                    table.opps().len(),
+                    table.is_shared()
+                );
+            }
+            NodeType::ThermalZone(zone) => {
+                println!("Thermal zone: {} trips={}", zone.path(), zone.trips().len());
+            }
+            NodeType::Chosen(chosen) => {
+                println!("Chosen node: bootargs={:?}", chosen.bootargs());
+            }
+            NodeType::Cpu(cpu) => {
+                cpu_count += 1;
+                println!("CPU node: {} hwid={:?}", cpu.path(), cpu.hwid());
+            }
             NodeType::Generic(g) => {
                 generic_count += 1;
                 let _ = g.path();
@@ -123,6 +170,7 @@ fn test_node_classify() {
     );
     assert!(memory_count > 0, "phytium DTB should have memory nodes");
     assert!(intc_count > 0, "phytium DTB should have intc nodes");
+    assert_eq!(cpu_count, 4, "phytium DTB should have 4 CPU nodes");
     assert!(generic_count > 0, "phytium DTB should have generic nodes");
 }
 
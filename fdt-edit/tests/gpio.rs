@@ -0,0 +1,138 @@
+//! GPIO controller view and GPIO specifier tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, GpioFlags, NodeType, NodeTypeMut, Phandle};
+
+#[test]
+fn test_gpio_controller_view() {
+    let raw_data = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let node = fdt.get_by_path("/soc/gpio@7e200000").unwrap();
+    // GPIO banks that also provide interrupts stay interrupt controllers
+    let NodeType::InterruptController(intc) = node else {
+        panic!("gpio@7e200000 should be classified as an interrupt controller");
+    };
+    let gpio = intc.gpio_controller().unwrap();
+
+    assert_eq!(gpio.gpio_cells(), 2);
+    assert!(gpio.is_interrupt_controller());
+    assert_eq!(gpio.line_name(0).as_deref(), Some("ID_SDA"));
+    assert_eq!(gpio.line_name(1).as_deref(), Some("ID_SCL"));
+    assert_eq!(gpio.line_name(1000), None);
+
+    let ranges = gpio.gpio_ranges();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(ranges[0].pinctrl, Phandle::from(0x07));
+    assert_eq!(ranges[0].gpio_offset, 0);
+    assert_eq!(ranges[0].pin_offset, 0);
+    assert_eq!(ranges[0].count, 58);
+}
+
+#[test]
+fn test_named_gpios() {
+    let raw_data = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let spi = fdt.get_by_path("/soc/spi@7e204000").unwrap();
+    let cs = spi.gpios("cs");
+    assert_eq!(cs.len(), 2);
+    assert_eq!(cs[0].controller, Phandle::from(0x07));
+    assert_eq!(cs[0].line, 8);
+    assert!(cs[0].flags.is_active_low());
+    assert_eq!(cs[0].specifier, vec![8, GpioFlags::ACTIVE_LOW]);
+    assert_eq!(cs[1].line, 7);
+    assert!(cs[1].flags.is_active_low());
+
+    assert!(spi.gpios("reset").is_empty());
+}
+
+#[test]
+fn test_legacy_gpio_suffix() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let eth = fdt.get_by_path("/ethernet@fe010000").unwrap();
+    let reset = eth.gpios("snps,reset");
+    assert_eq!(reset.len(), 1);
+    assert_eq!(reset[0].controller, Phandle::from(0x91));
+    assert_eq!(reset[0].line, 0x19);
+    assert!(reset[0].flags.is_active_low());
+
+    let controller = fdt.get_by_phandle(reset[0].controller).unwrap();
+    assert_eq!(controller.path(), "/pinctrl/gpio2@fe750000");
+    let NodeType::InterruptController(intc) = controller else {
+        panic!("gpio2@fe750000 should be classified as an interrupt controller");
+    };
+    assert_eq!(intc.gpio_controller().unwrap().gpio_cells(), 2);
+}
+
+#[test]
+fn test_gpio_flags_and_holes() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            gpio: gpio-controller {
+                gpio-controller;
+                #gpio-cells = <2>;
+                ngpios = <16>;
+                gpio-line-names = \"LED\", \"\", \"BUTTON\";
+            };
+            narrow: narrow-gpio {
+                gpio-controller;
+                #gpio-cells = <1>;
+            };
+            device {
+                gpios = <&gpio 3 6>, <0>, <&narrow 5>;
+                enable-gpios = <&gpio 1 0x12>;
+            };
+        };",
+    )
+    .unwrap();
+
+    let node = fdt.get_by_path("/gpio-controller").unwrap();
+    let NodeType::GpioController(gpio) = node else {
+        panic!("gpio-controller should be classified as a GPIO controller");
+    };
+    assert_eq!(gpio.ngpios(), Some(16));
+    assert_eq!(gpio.line_name(0).as_deref(), Some("LED"));
+    assert_eq!(gpio.line_name(1), None);
+    assert_eq!(gpio.line_name(2).as_deref(), Some("BUTTON"));
+    assert!(!gpio.is_interrupt_controller());
+
+    let device = fdt.get_by_path("/device").unwrap();
+    let gpios = device.gpios("");
+    assert_eq!(gpios.len(), 2);
+    assert_eq!(gpios[0].line, 3);
+    assert!(gpios[0].flags.is_open_drain());
+    assert!(!gpios[0].flags.is_open_source());
+    assert!(!gpios[0].flags.is_active_low());
+    assert_eq!(gpios[1].line, 5);
+    assert_eq!(gpios[1].flags, GpioFlags(0));
+    assert_eq!(gpios[1].specifier, vec![5]);
+
+    let enable = device.gpios("enable");
+    assert_eq!(enable.len(), 1);
+    assert!(enable[0].flags.is_open_source());
+    assert!(enable[0].flags.pull_up());
+    assert!(!enable[0].flags.pull_down());
+}
+
+#[test]
+fn test_add_gpio_controller() {
+    let mut fdt = Fdt::new();
+    let NodeTypeMut::Generic(mut root) = fdt.root_mut() else {
+        panic!("root should be generic");
+    };
+    let mut gpio = root.add_child_gpio_controller("gpio@1000");
+    gpio.set_ngpios(32);
+    gpio.set_line_names(&["A", "B"]);
+
+    let node = fdt.get_by_path("/gpio@1000").unwrap();
+    let NodeType::GpioController(gpio) = node else {
+        panic!("new node should be classified as a GPIO controller");
+    };
+    assert_eq!(gpio.gpio_cells(), 2);
+    assert_eq!(gpio.ngpios(), Some(32));
+    assert_eq!(gpio.line_names(), vec!["A", "B"]);
+}
@@ -1,5 +1,5 @@
 use dtb_file::*;
-use fdt_edit::{Fdt, NodeType, Phandle};
+use fdt_edit::{Fdt, FdtError, NodeType, Phandle};
 
 fn load_orangepi5plus() -> Fdt {
     let raw_data = fdt_orangepi_5plus();
@@ -56,3 +56,159 @@ fn test_interrupts_inherit_parent() {
     assert_eq!(interrupts[0].specifier, vec![0x00, 0x14b, 0x04]);
     assert_eq!(interrupts[0].name, None);
 }
+
+const NEXUS_DTS: &str = "/dts-v1/;
+/ {
+    #address-cells = <1>;
+    #size-cells = <1>;
+    interrupt-parent = <&gic>;
+
+    gic: interrupt-controller@1000 {
+        reg = <0x1000 0x1000>;
+        interrupt-controller;
+        #interrupt-cells = <3>;
+        #address-cells = <0>;
+    };
+
+    pmu: pmu-intc {
+        interrupt-controller;
+        #interrupt-cells = <1>;
+    };
+
+    wakeup: wakeup-nexus {
+        #interrupt-cells = <1>;
+        #address-cells = <0>;
+        interrupt-map-mask = <0xff>;
+        interrupt-map = <1 &gic 0 40 4>, <2 &bridge 0x200 7>;
+    };
+
+    bridge: bus@2000 {
+        #address-cells = <1>;
+        #size-cells = <1>;
+        #interrupt-cells = <1>;
+        interrupt-map-mask = <0xf00 0x7>;
+        interrupt-map = <0x100 1 &gic 0 50 4>, <0x200 7 &pmu 3>;
+
+        device@100 {
+            reg = <0x100 0x10>;
+            interrupt-parent = <&bridge>;
+            interrupts = <1>;
+        };
+
+        device@300 {
+            reg = <0x300 0x10>;
+            interrupt-parent = <&bridge>;
+            interrupts = <1>;
+        };
+    };
+
+    device {
+        interrupts-extended = <&gic 0 5 4>, <&wakeup 2>, <&pmu 9>;
+        interrupt-names = \"irq\", \"wake\", \"pmu\";
+    };
+
+    orphan {
+        interrupts-extended = <&wakeup 3>;
+    };
+};";
+
+#[test]
+fn test_resolve_interrupts_single_hop() {
+    let fdt = load_orangepi5plus();
+    let gpu = fdt.get_by_path("/gpu@fb000000").unwrap();
+
+    let resolved = gpu.resolve_interrupts().unwrap();
+    assert_eq!(resolved.len(), 3);
+    assert_eq!(resolved[0].name.as_deref(), Some("GPU"));
+    assert_eq!(resolved[0].hops.len(), 1);
+    assert_eq!(
+        resolved[0].controller().unwrap().controller,
+        "/interrupt-controller@fe600000"
+    );
+    assert_eq!(
+        resolved[0].controller().unwrap().specifier,
+        vec![0x00, 0x5e, 0x04]
+    );
+}
+
+#[test]
+fn test_interrupts_extended() {
+    let fdt = Fdt::from_dts(NEXUS_DTS).unwrap();
+    let device = fdt.get_by_path("/device").unwrap();
+
+    let interrupts = device.interrupts();
+    assert_eq!(interrupts.len(), 3);
+    assert_eq!(interrupts[0].cells, 3);
+    assert_eq!(interrupts[0].specifier, vec![0, 5, 4]);
+    assert_eq!(interrupts[1].cells, 1);
+    assert_eq!(interrupts[1].specifier, vec![2]);
+    assert_eq!(interrupts[2].name.as_deref(), Some("pmu"));
+    assert_ne!(
+        interrupts[0].interrupt_parent,
+        interrupts[1].interrupt_parent
+    );
+}
+
+#[test]
+fn test_resolve_interrupts_through_nexus_chain() {
+    let fdt = Fdt::from_dts(NEXUS_DTS).unwrap();
+    let device = fdt.get_by_path("/device").unwrap();
+
+    let resolved = device.resolve_interrupts().unwrap();
+    assert_eq!(resolved.len(), 3);
+
+    assert_eq!(resolved[0].name.as_deref(), Some("irq"));
+    assert_eq!(resolved[0].hops.len(), 1);
+    assert_eq!(
+        resolved[0].controller().unwrap().controller,
+        "/interrupt-controller@1000"
+    );
+
+    // wakeup-nexus -> bus@2000 -> pmu-intc
+    assert_eq!(resolved[1].name.as_deref(), Some("wake"));
+    let hops: Vec<_> = resolved[1]
+        .hops
+        .iter()
+        .map(|hop| (hop.controller.as_str(), hop.specifier.clone()))
+        .collect();
+    assert_eq!(
+        hops,
+        [
+            ("/wakeup-nexus", vec![2]),
+            ("/bus@2000", vec![7]),
+            ("/pmu-intc", vec![3]),
+        ]
+    );
+
+    assert_eq!(resolved[2].hops.len(), 1);
+    assert_eq!(resolved[2].controller().unwrap().controller, "/pmu-intc");
+}
+
+#[test]
+fn test_resolve_interrupts_uses_unit_address() {
+    let fdt = Fdt::from_dts(NEXUS_DTS).unwrap();
+    let device = fdt.get_by_path("/bus@2000/device@100").unwrap();
+
+    let resolved = device.resolve_interrupts().unwrap();
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[0].hops[0].controller, "/bus@2000");
+    assert_eq!(
+        resolved[0].controller().unwrap().controller,
+        "/interrupt-controller@1000"
+    );
+    assert_eq!(resolved[0].controller().unwrap().specifier, vec![0, 50, 4]);
+
+    // Same pin, but no map entry for unit address 0x300.
+    let device = fdt.get_by_path("/bus@2000/device@300").unwrap();
+    let err = device.resolve_interrupts().unwrap_err();
+    assert!(matches!(err, FdtError::NotFound));
+}
+
+#[test]
+fn test_resolve_interrupts_unmapped() {
+    let fdt = Fdt::from_dts(NEXUS_DTS).unwrap();
+    let orphan = fdt.get_by_path("/orphan").unwrap();
+
+    let err = orphan.resolve_interrupts().unwrap_err();
+    assert!(matches!(err, FdtError::NotFound));
+}
@@ -0,0 +1,151 @@
+//! Device tree lint tests.
+
+use dtb_file::*;
+use fdt_edit::{
+    Fdt,
+    lint::{Check, Diagnostic, Linter},
+};
+
+const BROKEN: &str = r#"
+/dts-v1/;
+/ {
+    #address-cells = <1>;
+    #size-cells = <1>;
+
+    osc: osc { #clock-cells = <0>; phandle = <1>; };
+    pll: pll { phandle = <1>; };
+    intc: interrupt-controller { interrupt-controller; phandle = <2>; };
+
+    uart@1000 {
+        reg = <0x1000 0x100 0x2000>;
+        clocks = <&osc 0>;
+        interrupt-parent = <0x99>;
+    };
+    spi@3000 {
+        reg = <0x4000 0x100>;
+        interrupts-extended = <0x77 1>;
+    };
+    bus@10000000 {
+        #address-cells = <1>;
+        #size-cells = <1>;
+        ranges = <0x0 0x10000000 0x2000>, <0x1000 0x20000000 0x1000>;
+    };
+    timer {
+        reg = <0x5000 0x100>;
+    };
+};
+"#;
+
+fn checks(diagnostics: &[Diagnostic], path: &str) -> Vec<Check> {
+    diagnostics
+        .iter()
+        .filter(|d| d.path == path)
+        .map(|d| d.check)
+        .collect()
+}
+
+#[test]
+fn test_lint_reports_each_check() {
+    let fdt = Fdt::from_dts(BROKEN).unwrap();
+    let diagnostics = fdt.lint();
+
+    assert_eq!(
+        checks(&diagnostics, "/uart@1000"),
+        [Check::RegFormat, Check::DanglingPhandle, Check::ClockCells]
+    );
+    assert_eq!(
+        checks(&diagnostics, "/spi@3000"),
+        [Check::UnitAddress, Check::DanglingPhandle]
+    );
+    assert_eq!(checks(&diagnostics, "/pll"), [Check::DuplicatePhandle]);
+    assert_eq!(
+        checks(&diagnostics, "/interrupt-controller"),
+        [Check::InterruptCells]
+    );
+    assert_eq!(
+        checks(&diagnostics, "/bus@10000000"),
+        [Check::RangesOverlap]
+    );
+    assert_eq!(checks(&diagnostics, "/timer"), [Check::UnitAddress]);
+
+    let spi = diagnostics
+        .iter()
+        .find(|d| d.path == "/spi@3000" && d.check == Check::UnitAddress)
+        .unwrap();
+    assert_eq!(
+        spi.to_string(),
+        "/spi@3000: unit address `3000` does not match first reg address (expected `4000`) [unit_address_vs_reg]"
+    );
+}
+
+#[test]
+fn test_lint_enable_disable() {
+    let fdt = Fdt::from_dts(BROKEN).unwrap();
+
+    let linter = Linter::new()
+        .disable(Check::UnitAddress)
+        .disable(Check::DanglingPhandle);
+    assert!(!linter.is_enabled(Check::UnitAddress));
+    assert!(linter.is_enabled(Check::RegFormat));
+    let diagnostics = linter.run(&fdt);
+    assert!(!diagnostics.is_empty());
+    assert!(
+        diagnostics
+            .iter()
+            .all(|d| d.check != Check::UnitAddress && d.check != Check::DanglingPhandle)
+    );
+
+    let diagnostics = Linter::none().enable(Check::DuplicatePhandle).run(&fdt);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].path, "/pll");
+
+    for check in Check::ALL {
+        assert_eq!(Check::from_name(check.name()), Some(check));
+    }
+    assert_eq!(Check::from_name("no_such_check"), None);
+}
+
+#[test]
+fn test_lint_clean_tree() {
+    let fdt = Fdt::from_dts(
+        r#"
+        /dts-v1/;
+        / {
+            #address-cells = <1>;
+            #size-cells = <1>;
+            osc: osc { #clock-cells = <1>; };
+            intc: interrupt-controller {
+                interrupt-controller;
+                #interrupt-cells = <2>;
+            };
+            uart@1000 {
+                reg = <0x1000 0x100>;
+                clocks = <&osc 3>;
+                interrupts-extended = <&intc 1 2>;
+            };
+        };
+        "#,
+    )
+    .unwrap();
+    assert_eq!(fdt.lint(), []);
+}
+
+#[test]
+fn test_lint_vendor_blobs() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let diagnostics = fdt.lint();
+
+    // `__symbols__` has a label named `clocks`; it must not be checked as a property.
+    assert!(diagnostics.iter().all(|d| d.path != "/__symbols__"));
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| d.path == "/scb" && d.check == Check::RangesOverlap)
+    );
+
+    let raw = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let diagnostics = Linter::none().enable(Check::UnitAddress).run(&fdt);
+    assert!(diagnostics.iter().any(|d| d.path == "/pcie@fe260000"));
+}
@@ -0,0 +1,174 @@
+//! Operating performance point table tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, NodeType, NodeTypeMut, OppMicrovolt};
+
+fn cpu<'a>(fdt: &'a Fdt, path: &str) -> fdt_edit::CpuNodeView<'a> {
+    let NodeType::Cpu(cpu) = fdt.get_by_path(path).unwrap() else {
+        panic!("{path} should be a CPU node");
+    };
+    cpu
+}
+
+#[test]
+fn test_opp_v2_rk3568() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let cpu0 = cpu(&fdt, "/cpus/cpu@0");
+    let table = cpu0.opp_table().unwrap();
+    assert_eq!(table.path(), "/cpu0-opp-table");
+    assert!(table.is_shared());
+    assert!(matches!(
+        fdt.get_by_path("/cpu0-opp-table").unwrap(),
+        NodeType::OppTable(_)
+    ));
+
+    let opps = cpu0.opps();
+    assert_eq!(opps.len(), 11);
+    assert_eq!(opps[0].name.as_deref(), Some("opp-408000000"));
+    assert_eq!(opps[0].rate(), Some(408_000_000));
+    assert_eq!(
+        opps[0].voltages(1).unwrap(),
+        [OppMicrovolt {
+            target: 850_000,
+            min: 850_000,
+            max: 1_150_000,
+        }]
+    );
+    assert_eq!(opps[0].supported_hw, [0xfb, 0xffff]);
+    assert_eq!(opps[0].clock_latency_ns, Some(40_000));
+    assert!(!opps[0].suspend);
+    assert!(opps[2].suspend);
+    assert!(!opps[2].turbo);
+
+    // Leakage bins select a lower voltage on better silicon.
+    let opp = &opps[4];
+    assert_eq!(opp.rate(), Some(1_416_000_000));
+    assert_eq!(opp.voltages_for("L0", 1).unwrap()[0].target, 1_025_000);
+    assert_eq!(opp.voltages_for("L3", 1).unwrap()[0].target, 925_000);
+    assert_eq!(opp.voltages_for("L9", 1), opp.voltages(1));
+
+    // opp-supported-hw groups are <soc-variant speed-bin>.
+    assert_eq!(table.supported_opps(&[0x4, 0x1]).len(), 2);
+    assert_eq!(table.supported_opps(&[0x1, 0x1]).len(), 8);
+    assert_eq!(table.supported_opps(&[]).len(), 11);
+
+    // The other cores share cpu0's table.
+    assert_eq!(cpu(&fdt, "/cpus/cpu@100").opps(), opps);
+}
+
+#[test]
+fn test_opp_v2_dual_supply() {
+    let raw_data = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let NodeType::OppTable(table) = fdt.get_by_path("/cluster0-opp-table").unwrap() else {
+        panic!("cluster0-opp-table should be an OPP table");
+    };
+    let opp = &table.opps()[0];
+    let voltages = opp.voltages(2).unwrap();
+    assert_eq!(voltages.len(), 2);
+    assert_eq!(voltages[0], voltages[1]);
+    assert_eq!(voltages[0].target, 675_000);
+    assert_eq!(voltages[0].max, 950_000);
+    // Six cells are two triplets or six single targets, never one supply.
+    assert_eq!(opp.voltages(1), None);
+    assert_eq!(opp.voltages(6).unwrap()[2].target, 950_000);
+}
+
+#[test]
+fn test_opp_from_dts() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            cpus {
+                #address-cells = <1>;
+                #size-cells = <0>;
+                cpu@0 {
+                    device_type = \"cpu\";
+                    reg = <0>;
+                    operating-points-v2 = <&table>;
+                };
+                cpu@1 {
+                    device_type = \"cpu\";
+                    reg = <1>;
+                    operating-points = <600000 900000>, <1200000 1100000>;
+                };
+            };
+            table: opp-table {
+                compatible = \"operating-points-v2\";
+                opp-low {
+                    opp-hz = /bits/ 64 <500000000 250000000>;
+                    opp-microvolt = <800000>, <900000>;
+                };
+                opp-boost {
+                    opp-hz = /bits/ 64 <5000000000>;
+                    opp-microvolt = <1200000 1150000 1250000>;
+                    opp-microvolt-fast = <1100000 1050000 1150000>;
+                    turbo-mode;
+                };
+                opp-three {
+                    opp-hz = <100000000>;
+                    opp-microvolt = <900000>, <950000>, <1000000>;
+                };
+                opp-empty {
+                    opp-hz;
+                };
+                not-an-opp {
+                };
+            };
+        };",
+    )
+    .unwrap();
+
+    let opps = cpu(&fdt, "/cpus/cpu@0").opps();
+    assert_eq!(opps.len(), 3);
+    assert!(!cpu(&fdt, "/cpus/cpu@0").opp_table().unwrap().is_shared());
+    assert_eq!(opps[0].hz, [500_000_000, 250_000_000]);
+    assert_eq!(opps[0].voltages(2).unwrap()[1].min, 900_000);
+    // Above 4 GHz: opp-hz needs all 64 bits.
+    assert_eq!(opps[1].rate(), Some(5_000_000_000));
+    assert!(opps[1].turbo);
+    assert_eq!(opps[1].voltages(1).unwrap()[0].min, 1_150_000);
+    assert_eq!(
+        opps[1].voltages_for("fast", 1).unwrap()[0].target,
+        1_100_000
+    );
+    // A 32-bit opp-hz and three single-target supplies.
+    assert_eq!(opps[2].hz, [100_000_000]);
+    let three = opps[2].voltages(3).unwrap();
+    assert_eq!(three.len(), 3);
+    assert_eq!(three[2].target, 1_000_000);
+    assert_eq!(three[2].min, 1_000_000);
+    assert_eq!(opps[2].voltages(1).unwrap()[0].max, 1_000_000);
+
+    let legacy = cpu(&fdt, "/cpus/cpu@1");
+    assert!(legacy.opp_table().is_none());
+    let opps = legacy.opps();
+    assert_eq!(opps.len(), 2);
+    assert_eq!(opps[1].rate(), Some(1_200_000_000));
+    assert_eq!(opps[1].voltages(1).unwrap()[0].target, 1_100_000);
+    assert_eq!(opps[1].name, None);
+}
+
+#[test]
+fn test_add_opp_table() {
+    let mut fdt = Fdt::new();
+    let NodeTypeMut::Generic(mut root) = fdt.root_mut() else {
+        panic!("root should be generic");
+    };
+    let mut table = root.add_child_opp_table("opp-table");
+    table.set_shared(true);
+    table.add_opp(1_000_000_000, 900_000);
+
+    let NodeType::OppTable(table) = fdt.get_by_path("/opp-table").unwrap() else {
+        panic!("new node should be an OPP table");
+    };
+    assert!(table.is_shared());
+    let opps = table.opps();
+    assert_eq!(opps.len(), 1);
+    assert_eq!(opps[0].name.as_deref(), Some("opp-1000000000"));
+    assert_eq!(opps[0].rate(), Some(1_000_000_000));
+    assert_eq!(opps[0].voltages(1).unwrap()[0].target, 900_000);
+}
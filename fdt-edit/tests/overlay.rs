@@ -0,0 +1,174 @@
+//! Device tree overlay tests.
+
+use dtb_file::*;
+use fdt_edit::*;
+
+fn str_prop(name: &str, value: &str) -> Property {
+    let mut prop = Property::new(name, Vec::new());
+    prop.set_string(value);
+    prop
+}
+
+fn u32_prop(name: &str, values: &[u32]) -> Property {
+    let mut prop = Property::new(name, Vec::new());
+    prop.set_u32_ls(values);
+    prop
+}
+
+/// Builds the equivalent of compiling the following with `dtc -@`:
+///
+/// ```text
+/// /plugin/;
+/// &uart0 {
+///     status = "okay";
+///     bt: bluetooth {
+///         phandle = <1>;
+///         clocks = <&bt 0>, <&clk_osc 1>;
+///     };
+/// };
+/// &{/chosen} {
+///     overlay-applied;
+/// };
+/// ```
+fn build_overlay() -> Fdt {
+    let mut overlay = Fdt::new();
+    let root = overlay.root_id();
+
+    let mut fragment = Node::new("fragment@0");
+    fragment.set_property(u32_prop("target", &[0xffff_ffff]));
+    let fragment0 = overlay.add_node(root, fragment);
+    let mut ov = Node::new("__overlay__");
+    ov.set_property(str_prop("status", "okay"));
+    let ov0 = overlay.add_node(fragment0, ov);
+    let mut bt = Node::new("bluetooth");
+    bt.set_property(u32_prop("phandle", &[1]));
+    bt.set_property(u32_prop("clocks", &[1, 0, 0xffff_ffff, 1]));
+    overlay.add_node(ov0, bt);
+
+    let mut fragment = Node::new("fragment@1");
+    fragment.set_property(str_prop("target-path", "/chosen"));
+    let fragment1 = overlay.add_node(root, fragment);
+    let mut ov = Node::new("__overlay__");
+    ov.set_property(Property::new("overlay-applied", Vec::new()));
+    overlay.add_node(fragment1, ov);
+
+    let mut symbols = Node::new("__symbols__");
+    symbols.set_property(str_prop("bt", "/fragment@0/__overlay__/bluetooth"));
+    overlay.add_node(root, symbols);
+
+    let mut fixups = Node::new("__fixups__");
+    fixups.set_property(Property::new("uart0", b"/fragment@0:target:0\0".to_vec()));
+    fixups.set_property(Property::new(
+        "clk_osc",
+        b"/fragment@0/__overlay__/bluetooth:clocks:8\0".to_vec(),
+    ));
+    overlay.add_node(root, fixups);
+
+    let local_fixups = overlay.add_node(root, Node::new("__local_fixups__"));
+    let fragment = overlay.add_node(local_fixups, Node::new("fragment@0"));
+    let ov = overlay.add_node(fragment, Node::new("__overlay__"));
+    let mut bt = Node::new("bluetooth");
+    bt.set_property(u32_prop("clocks", &[0]));
+    overlay.add_node(ov, bt);
+
+    overlay
+}
+
+#[test]
+fn test_apply_overlay_rpi() {
+    let raw = fdt_rpi_4b();
+    let mut fdt = Fdt::from_bytes(&raw).unwrap();
+    let base_max = fdt.max_phandle();
+    let clk_osc = fdt
+        .get_by_path("/clocks/clk-osc")
+        .unwrap()
+        .as_node()
+        .phandle()
+        .unwrap();
+
+    fdt.apply_overlay(&build_overlay()).unwrap();
+
+    let uart = fdt.get_by_path("/soc/serial@7e201000").unwrap();
+    assert_eq!(
+        uart.as_node().get_property("status").unwrap().as_str(),
+        Some("okay")
+    );
+
+    let bt = fdt.get_by_path("/soc/serial@7e201000/bluetooth").unwrap();
+    let bt_phandle = bt.as_node().phandle().unwrap();
+    assert_eq!(bt_phandle.raw(), base_max + 1);
+    assert_eq!(fdt.get_by_phandle(bt_phandle).unwrap().id(), bt.id());
+
+    let clocks: Vec<u32> = bt
+        .as_node()
+        .get_property("clocks")
+        .unwrap()
+        .get_u32_iter()
+        .collect();
+    assert_eq!(clocks, vec![base_max + 1, 0, clk_osc.raw(), 1]);
+
+    let chosen = fdt.get_by_path("/chosen").unwrap();
+    assert!(chosen.as_node().get_property("overlay-applied").is_some());
+
+    let symbols = fdt.get_by_path("/__symbols__").unwrap();
+    assert_eq!(
+        symbols.as_node().get_property("bt").unwrap().as_str(),
+        Some("/soc/serial@7e201000/bluetooth")
+    );
+}
+
+#[test]
+fn test_apply_overlay_missing_label() {
+    let mut fdt = Fdt::new();
+    let err = fdt.apply_overlay(&build_overlay()).unwrap_err();
+    assert_eq!(err, OverlayError::LabelNotFound("uart0".into()));
+}
+
+#[test]
+fn test_apply_overlay_missing_target() {
+    let mut fdt = Fdt::new();
+    let root = fdt.root_id();
+
+    let mut overlay = Fdt::new();
+    let overlay_root = overlay.root_id();
+    let mut fragment = Node::new("fragment@0");
+    fragment.set_property(str_prop("target-path", "/soc"));
+    let fragment = overlay.add_node(overlay_root, fragment);
+    overlay.add_node(fragment, Node::new("__overlay__"));
+
+    let err = fdt.apply_overlay(&overlay).unwrap_err();
+    assert_eq!(
+        err,
+        OverlayError::TargetNotFound {
+            fragment: "/fragment@0".into(),
+            target: "/soc".into(),
+        }
+    );
+
+    fdt.add_node(root, Node::new("soc"));
+    fdt.apply_overlay(&overlay).unwrap();
+}
+
+#[test]
+fn test_apply_overlay_fixup_offset_overflow() {
+    let mut fdt = Fdt::from_bytes(&fdt_rpi_4b()).unwrap();
+    let mut overlay = build_overlay();
+    let fixups = overlay.get_by_path_id("/__fixups__").unwrap();
+    overlay
+        .node_mut(fixups)
+        .unwrap()
+        .set_property(Property::new(
+            "uart0",
+            b"/fragment@0:target:18446744073709551615\0".to_vec(),
+        ));
+
+    let err = fdt.apply_overlay(&overlay).unwrap_err();
+    assert_eq!(
+        err,
+        OverlayError::FixupOutOfBounds {
+            path: "/fragment@0".into(),
+            property: "target".into(),
+            offset: usize::MAX,
+        }
+    );
+}
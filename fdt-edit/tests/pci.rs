@@ -3,7 +3,7 @@
 use std::sync::Once;
 
 use dtb_file::*;
-use fdt_edit::{Fdt, NodeType, PciRange, PciSpace};
+use fdt_edit::{Fdt, FdtError, NodeType, PciNodeView, PciRange, PciSpace, Phandle};
 
 fn init_logging() {
     static INIT: Once = Once::new();
@@ -164,7 +164,12 @@ fn test_pci_irq_map() {
 
     let irq = pci.child_interrupts(0, 0, 0, 4).unwrap();
 
-    assert!(!irq.irqs.is_empty());
+    assert_eq!(irq.interrupt_parent, Phandle::from(0x01));
+    assert_eq!(irq.irqs, vec![0, 7, 4]);
+
+    // The mask ignores the device number, so every slot shares the map.
+    let irq = pci.child_interrupts(0, 5, 0, 1).unwrap();
+    assert_eq!(irq.irqs, vec![0, 4, 4]);
 }
 
 #[test]
@@ -191,3 +196,137 @@ fn test_pci_irq_map2() {
         assert_eq!(*got, *want);
     }
 }
+
+fn host_bridge(fdt: &Fdt) -> PciNodeView<'_> {
+    let node_ref = fdt
+        .find_compatible(&["pci-host-ecam-generic"])
+        .into_iter()
+        .next()
+        .unwrap();
+
+    let NodeType::Pci(pci) = node_ref else {
+        panic!("Not a PCI node");
+    };
+    pci
+}
+
+#[test]
+fn test_pci_irq_unmapped() {
+    for raw in [fdt_qemu(), fdt_phytium()] {
+        let fdt = Fdt::from_bytes(&raw).unwrap();
+        let pci = host_bridge(&fdt);
+
+        // Pin 0 means the function uses no legacy interrupt.
+        assert!(matches!(
+            pci.child_interrupts(0, 1, 0, 0),
+            Err(FdtError::NotFound)
+        ));
+        assert!(matches!(
+            pci.child_interrupts(0, 1, 0, 5),
+            Err(FdtError::InvalidInput)
+        ));
+        // No bridge node describes how bus 1 is reached.
+        assert!(matches!(
+            pci.child_interrupts(1, 0, 0, 1),
+            Err(FdtError::NotFound)
+        ));
+    }
+}
+
+#[test]
+fn test_pci_irq_qemu_slots() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let pci = host_bridge(&fdt);
+
+    // Each slot rotates INTA..INTD across GIC SPIs 3..6.
+    let irq = pci.child_interrupts(0, 1, 0, 1).unwrap();
+    assert_eq!(irq.interrupt_parent, Phandle::from(0x8002));
+    assert_eq!(irq.irqs, vec![0, 4, 4]);
+    let irq = pci.child_interrupts(0, 3, 0, 4).unwrap();
+    assert_eq!(irq.irqs, vec![0, 5, 4]);
+}
+
+const BRIDGE_DTS: &str = "/dts-v1/;
+/ {
+    #address-cells = <2>;
+    #size-cells = <2>;
+
+    gic: interrupt-controller {
+        interrupt-controller;
+        #interrupt-cells = <3>;
+        #address-cells = <0>;
+    };
+
+    pcie@10000000 {
+        compatible = \"pci-host-ecam-generic\";
+        device_type = \"pci\";
+        #address-cells = <3>;
+        #size-cells = <2>;
+        #interrupt-cells = <1>;
+        bus-range = <0 0xff>;
+        interrupt-map-mask = <0x1800 0 0 7>;
+        interrupt-map = <0x0000 0 0 1 &gic 0 3 4>, <0x0000 0 0 2 &gic 0 4 4>,
+                        <0x0000 0 0 3 &gic 0 5 4>, <0x0000 0 0 4 &gic 0 6 4>,
+                        <0x0800 0 0 1 &gic 0 4 4>, <0x0800 0 0 2 &gic 0 5 4>,
+                        <0x0800 0 0 3 &gic 0 6 4>, <0x0800 0 0 4 &gic 0 3 4>;
+
+        pci@1,0 {
+            device_type = \"pci\";
+            reg = <0x0800 0 0 0 0>;
+            #address-cells = <3>;
+            #size-cells = <2>;
+            #interrupt-cells = <1>;
+            bus-range = <1 2>;
+
+            pci@0,0 {
+                device_type = \"pci\";
+                reg = <0x10000 0 0 0 0>;
+                #address-cells = <3>;
+                #size-cells = <2>;
+                #interrupt-cells = <1>;
+                bus-range = <2 2>;
+            };
+        };
+
+        pci@2,0 {
+            device_type = \"pci\";
+            reg = <0x1000 0 0 0 0>;
+            #address-cells = <3>;
+            #size-cells = <2>;
+            #interrupt-cells = <1>;
+            bus-range = <3 3>;
+            interrupt-map-mask = <0 0 0 7>;
+            interrupt-map = <0 0 0 1 &gic 0 9 4>;
+        };
+    };
+};";
+
+#[test]
+fn test_pci_irq_bridge_swizzle() {
+    let fdt = Fdt::from_dts(BRIDGE_DTS).unwrap();
+    let pci = host_bridge(&fdt);
+
+    // Bus 1 sits behind the bridge in slot 1: device 2, INTA becomes INTC
+    // at the bridge, which the host maps to SPI 6.
+    let irq = pci.child_interrupts(1, 2, 0, 1).unwrap();
+    assert_eq!(irq.irqs, vec![0, 6, 4]);
+
+    // Bus 2 adds a second bridge at device 0, which keeps the pin.
+    let irq = pci.child_interrupts(2, 1, 0, 1).unwrap();
+    assert_eq!(irq.irqs, vec![0, 5, 4]);
+
+    // A bridge with its own interrupt-map translates directly.
+    let irq = pci.child_interrupts(3, 7, 0, 1).unwrap();
+    assert_eq!(irq.irqs, vec![0, 9, 4]);
+    assert!(matches!(
+        pci.child_interrupts(3, 7, 0, 2),
+        Err(FdtError::NotFound)
+    ));
+
+    // No bridge leads to bus 4.
+    assert!(matches!(
+        pci.child_interrupts(4, 0, 0, 1),
+        Err(FdtError::NotFound)
+    ));
+}
@@ -0,0 +1,153 @@
+//! Pin control state and configuration tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, Phandle, PinBias, RockchipPin};
+
+#[test]
+fn test_pinctrl_rk3568_uart() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let uart = fdt.get_by_path("/serial@fe660000").unwrap();
+    let states = uart.pinctrl_states();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].index, 0);
+    assert_eq!(states[0].name.as_deref(), Some("default"));
+    assert!(states[0].unresolved.is_empty());
+
+    let config = &states[0].configs[0];
+    assert_eq!(config.path(), "/pinctrl/uart2/uart2m0-xfer");
+    let pins = config.rockchip_pins();
+    assert_eq!(
+        pins,
+        [
+            RockchipPin {
+                bank: 0,
+                pin: 0x18,
+                mux: 1,
+                config: Phandle::from(0x13b),
+            },
+            RockchipPin {
+                bank: 0,
+                pin: 0x19,
+                mux: 1,
+                config: Phandle::from(0x13b),
+            },
+        ]
+    );
+
+    let pcfg = config.rockchip_config(&pins[0]).unwrap();
+    assert_eq!(pcfg.path(), "/pinctrl/pcfg-pull-up");
+    assert_eq!(pcfg.bias(), Some(PinBias::PullUp(None)));
+    assert_eq!(pcfg.drive_strength(), None);
+}
+
+#[test]
+fn test_pinctrl_named_states() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let pmic = fdt.get_by_path("/i2c@fdd40000/pmic@20").unwrap();
+    let names: Vec<_> = pmic
+        .pinctrl_states()
+        .into_iter()
+        .map(|state| state.name.unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        ["default", "pmic-sleep", "pmic-power-off", "pmic-reset"]
+    );
+
+    let sleep = pmic.pinctrl_state("pmic-sleep").unwrap();
+    assert_eq!(sleep.index, 1);
+    assert_eq!(sleep.configs.len(), 2);
+    assert!(pmic.pinctrl_state("idle").is_none());
+}
+
+#[test]
+fn test_pinctrl_orangepi_5plus() {
+    let raw_data = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let uart = fdt.get_by_path("/serial@feb50000").unwrap();
+    let state = uart.pinctrl_state("default").unwrap();
+    let config = &state.configs[0];
+    assert_eq!(config.path(), "/pinctrl/uart2/uart2m1-xfer");
+
+    let pins = config.rockchip_pins();
+    assert_eq!(pins.len(), 2);
+    assert_eq!((pins[0].bank, pins[0].pin, pins[0].mux), (4, 0x19, 0xa));
+    assert_eq!((pins[1].bank, pins[1].pin, pins[1].mux), (4, 0x18, 0xa));
+    let pcfg = config.rockchip_config(&pins[0]).unwrap();
+    assert_eq!(pcfg.bias(), Some(PinBias::PullUp(None)));
+}
+
+#[test]
+fn test_generic_pinconf() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            pinctrl {
+                i2c0_default: i2c0-default {
+                    groups = \"i2c0_grp\";
+                    function = \"i2c0\";
+                    bias-pull-up = <4700>;
+                    drive-strength = <8>;
+                    input-enable;
+                };
+                i2c0_gpio: i2c0-gpio {
+                    pins = \"PA0\", \"PA1\";
+                    function = \"gpio\";
+                    bias-disable;
+                    output-low;
+                    pinmux = <0x100 0x101>;
+                };
+                i2c0_sleep: i2c0-sleep {
+                    pins = \"PA0\";
+                    bias-pull-down;
+                    input-schmitt-enable;
+                    slew-rate = <1>;
+                };
+            };
+            i2c {
+                pinctrl-names = \"default\", \"gpio\", \"sleep\", \"unused\";
+                pinctrl-0 = <&i2c0_default>;
+                pinctrl-1 = <&i2c0_gpio 0x4321>;
+                pinctrl-2 = <&i2c0_sleep>;
+                pinctrl-4 = <&i2c0_sleep>;
+            };
+        };",
+    )
+    .unwrap();
+
+    let i2c = fdt.get_by_path("/i2c").unwrap();
+    // pinctrl-3 is missing, so pinctrl-4 is not reached.
+    let states = i2c.pinctrl_states();
+    assert_eq!(states.len(), 3);
+
+    let default = &states[0].configs[0];
+    assert_eq!(default.groups(), ["i2c0_grp"]);
+    assert!(default.pins().is_empty());
+    assert_eq!(default.function().as_deref(), Some("i2c0"));
+    assert_eq!(default.bias(), Some(PinBias::PullUp(Some(4700))));
+    assert_eq!(default.drive_strength(), Some(8));
+    assert!(default.input_enable());
+    assert!(!default.input_schmitt_enable());
+    assert_eq!(default.output_level(), None);
+
+    let gpio = &states[1];
+    assert_eq!(gpio.configs.len(), 1);
+    assert_eq!(gpio.unresolved, [Phandle::from(0x4321)]);
+    let gpio = &gpio.configs[0];
+    assert_eq!(gpio.pins(), ["PA0", "PA1"]);
+    assert_eq!(gpio.bias(), Some(PinBias::Disable));
+    assert_eq!(gpio.output_level(), Some(false));
+    assert_eq!(gpio.raw_cells("pinmux"), [0x100, 0x101]);
+    assert!(gpio.rockchip_pins().is_empty());
+
+    let sleep = &states[2].configs[0];
+    assert_eq!(sleep.function(), None);
+    assert_eq!(sleep.bias(), Some(PinBias::PullDown(None)));
+    assert!(sleep.input_schmitt_enable());
+    assert_eq!(sleep.slew_rate(), Some(1));
+}
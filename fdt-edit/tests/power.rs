@@ -0,0 +1,186 @@
+//! Power domain, reset and regulator consumer tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, NodeType, NodeTypeMut, Phandle};
+
+#[test]
+fn test_power_domains_and_resets_rk3568() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let usb = fdt.get_by_path("/usbdrd/dwc3@fcc00000").unwrap();
+    let domains = usb.power_domains();
+    assert_eq!(domains.len(), 1);
+    assert_eq!(domains[0].provider, Phandle::from(0x25));
+    assert_eq!(domains[0].cells, 1);
+    assert_eq!(domains[0].specifier, vec![0xf]);
+    assert_eq!(domains[0].name, None);
+
+    let resets = usb.resets();
+    assert_eq!(resets.len(), 1);
+    assert_eq!(resets[0].name.as_deref(), Some("usb3-otg"));
+    assert_eq!(resets[0].controller, Phandle::from(0x23));
+    assert_eq!(resets[0].specifier, vec![0x94]);
+
+    let npu = fdt.get_by_path("/npu@fde40000").unwrap();
+    let resets = npu.resets();
+    assert_eq!(resets.len(), 2);
+    assert_eq!(resets[1].specifier, vec![0x2c]);
+
+    let provider = fdt.get_by_phandle(domains[0].provider).unwrap();
+    let NodeType::PowerDomain(provider) = provider else {
+        panic!("power-controller should be classified as a power domain provider");
+    };
+    assert_eq!(
+        provider.path(),
+        "/power-management@fdd90000/power-controller"
+    );
+    assert_eq!(provider.power_domain_cells(), 1);
+
+    // The CRU provides resets too, but stays a clock provider.
+    assert!(matches!(
+        fdt.get_by_phandle(resets[0].controller).unwrap(),
+        NodeType::Clock(_)
+    ));
+}
+
+#[test]
+fn test_supplies_rk3568() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let cpu = fdt.get_by_path("/cpus/cpu@0").unwrap();
+    let supplies = cpu.supplies().resolved;
+    assert_eq!(supplies.len(), 1);
+    assert_eq!(supplies[0].name, "cpu");
+    assert_eq!(supplies[0].regulator, Phandle::from(0x5));
+    assert_eq!(supplies[0].regulator_name.as_deref(), Some("vdd_cpu"));
+    assert_eq!(supplies[0].min_microvolt, Some(712_500));
+    assert_eq!(supplies[0].max_microvolt, Some(1_390_000));
+
+    let gpu = fdt.get_by_path("/gpu@fde60000").unwrap();
+    let supplies = gpu.supplies().resolved;
+    assert_eq!(supplies.len(), 1);
+    assert_eq!(supplies[0].name, "mali");
+    assert_eq!(supplies[0].min_microvolt, Some(500_000));
+    assert_eq!(supplies[0].max_microvolt, Some(1_350_000));
+
+    let regulator = fdt
+        .get_by_path("/i2c@fdd40000/pmic@20/regulators/DCDC_REG2")
+        .unwrap();
+    let NodeType::Regulator(regulator) = regulator else {
+        panic!("DCDC_REG2 should be classified as a regulator");
+    };
+    assert_eq!(regulator.regulator_name(), supplies[0].regulator_name);
+    assert_eq!(regulator.always_on(), supplies[0].always_on);
+    assert_eq!(regulator.boot_on(), supplies[0].boot_on);
+}
+
+#[test]
+fn test_refs_from_dts() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            pd: power-controller {
+                #power-domain-cells = <1>;
+            };
+            pd0: pd-single {
+                #power-domain-cells = <0>;
+            };
+            rst: reset-controller {
+                #reset-cells = <2>;
+            };
+            vpwm: pwm-regulator {
+                compatible = \"pwm-regulator\";
+                regulator-min-microvolt = <800000>;
+            };
+            vcc: vcc-3v3 {
+                compatible = \"regulator-fixed\";
+                regulator-min-microvolt = <3300000>;
+                regulator-max-microvolt = <3300000>;
+                regulator-always-on;
+            };
+            device {
+                power-domains = <&pd 3>, <&pd0>;
+                power-domain-names = \"core\", \"mem\";
+                resets = <&rst 1 2>, <&rst 3>;
+                reset-names = \"bus\", \"truncated\";
+                vdd-supply = <&vcc>;
+                vio-supply = <&vpwm>;
+                vbad-supply = <0x4321>;
+            };
+        };",
+    )
+    .unwrap();
+
+    let device = fdt.get_by_path("/device").unwrap();
+
+    let domains = device.power_domains();
+    assert_eq!(domains.len(), 2);
+    assert_eq!(domains[0].name.as_deref(), Some("core"));
+    assert_eq!(domains[0].specifier, vec![3]);
+    assert_eq!(domains[1].name.as_deref(), Some("mem"));
+    assert_eq!(domains[1].cells, 0);
+    assert!(domains[1].specifier.is_empty());
+
+    // The second entry is missing a cell.
+    let resets = device.resets();
+    assert_eq!(resets.len(), 1);
+    assert_eq!(resets[0].name.as_deref(), Some("bus"));
+    assert_eq!(resets[0].specifier, vec![1, 2]);
+
+    let supplies = device.supplies();
+    assert_eq!(supplies.resolved.len(), 2);
+    assert_eq!(supplies.resolved[0].name, "vdd");
+    assert_eq!(supplies.resolved[0].regulator_name, None);
+    assert_eq!(supplies.resolved[0].min_microvolt, Some(3_300_000));
+    assert!(supplies.resolved[0].always_on);
+    assert!(!supplies.resolved[0].boot_on);
+    // pwm-regulator has neither regulator-name nor a regulator-* compatible.
+    assert_eq!(supplies.resolved[1].name, "vio");
+    assert_eq!(supplies.resolved[1].min_microvolt, Some(800_000));
+    assert_eq!(
+        supplies.unresolved,
+        [("vbad".to_string(), Phandle::from(0x4321))]
+    );
+
+    assert!(matches!(
+        fdt.get_by_path("/reset-controller").unwrap(),
+        NodeType::ResetController(_)
+    ));
+    assert!(matches!(
+        fdt.get_by_path("/vcc-3v3").unwrap(),
+        NodeType::Regulator(_)
+    ));
+}
+
+#[test]
+fn test_add_providers() {
+    let mut fdt = Fdt::new();
+    let NodeTypeMut::Generic(mut root) = fdt.root_mut() else {
+        panic!("root should be generic");
+    };
+    root.add_child_power_domain("power-controller");
+    root.add_child_reset_controller("reset-controller");
+    let mut regulator = root.add_child_regulator("vcc-1v8");
+    regulator.set_microvolt_range(1_800_000, 1_800_000);
+    regulator.set_boot_on(true);
+    regulator.set_always_on(true);
+    regulator.set_always_on(false);
+
+    let NodeType::PowerDomain(pd) = fdt.get_by_path("/power-controller").unwrap() else {
+        panic!("new node should be a power domain provider");
+    };
+    assert_eq!(pd.power_domain_cells(), 1);
+    let NodeType::ResetController(rst) = fdt.get_by_path("/reset-controller").unwrap() else {
+        panic!("new node should be a reset controller");
+    };
+    assert_eq!(rst.reset_cells(), 1);
+    let NodeType::Regulator(regulator) = fdt.get_by_path("/vcc-1v8").unwrap() else {
+        panic!("new node should be a regulator");
+    };
+    assert_eq!(regulator.regulator_name().as_deref(), Some("vcc-1v8"));
+    assert_eq!(regulator.max_microvolt(), Some(1_800_000));
+    assert!(regulator.boot_on());
+    assert!(!regulator.always_on());
+}
@@ -83,3 +83,100 @@ fn test_set_regs_roundtrip() {
     );
     assert_eq!(roundtrip_reg.size, original_reg.size);
 }
+
+#[test]
+fn test_regs_translation_errors() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            #address-cells = <2>;
+            #size-cells = <2>;
+            soc {
+                compatible = \"simple-bus\";
+                #address-cells = <1>;
+                #size-cells = <1>;
+                ranges = <0x0 0x0 0xf0000000 0x100000>;
+                uart@ff000 {
+                    reg = <0xff000 0x1000>, <0xfff00 0x200>, <0x200000 0x100>;
+                };
+                i2c@1000 {
+                    reg = <0x1000 0x100>;
+                    #address-cells = <1>;
+                    #size-cells = <0>;
+                    sensor@48 {
+                        reg = <0x48>;
+                    };
+                };
+                pcie@2000 {
+                    device_type = \"pci\";
+                    reg = <0x2000 0x1000>;
+                    #address-cells = <3>;
+                    #size-cells = <2>;
+                    ranges = <0x01000000 0x0 0x0 0x80000 0x0 0x10000>,
+                             <0x02000000 0x0 0x40000000 0x40000 0x0 0x40000>;
+                    ethernet@0,0 {
+                        reg = <0x02000000 0x0 0x40001000 0x0 0x1000>,
+                              <0x01000000 0x0 0x100 0x0 0x100>,
+                              <0x00000000 0x0 0x0 0x0 0x0>;
+                    };
+                };
+            };
+        };",
+    )
+    .unwrap();
+
+    let uart = fdt.get_by_path("/soc/uart@ff000").unwrap();
+    let regs = uart.regs();
+    assert_eq!(regs[0].address, 0xf00ff000);
+    assert_eq!(regs[0].error, None);
+    assert_eq!(
+        regs[1].error,
+        Some(TranslateError::CrossesWindow {
+            address: 0xfff00,
+            size: 0x200
+        })
+    );
+    assert_eq!(regs[1].address, regs[1].child_bus_address);
+    assert_eq!(
+        regs[2].error,
+        Some(TranslateError::Unmapped { address: 0x200000 })
+    );
+
+    let sensor = fdt.get_by_path("/soc/i2c@1000/sensor@48").unwrap();
+    let regs = sensor.regs();
+    assert_eq!(regs[0].address, 0x48);
+    assert_eq!(
+        regs[0].error,
+        Some(TranslateError::NoRanges { address: 0x48 })
+    );
+
+    // Memory, I/O and configuration space, picked by the phys.hi space bits
+    let eth = fdt.get_by_path("/soc/pcie@2000/ethernet@0,0").unwrap();
+    let regs = eth.regs();
+    assert_eq!(regs[0].address, 0xf0041000);
+    assert_eq!(regs[0].child_bus_address, 0x40001000);
+    assert_eq!(regs[1].address, 0xf0080100);
+    assert_eq!(regs[2].error, Some(TranslateError::Unmapped { address: 0 }));
+    assert_eq!(
+        eth.try_translate((0x0200_0000u128 << 64) | 0x4003_f000, 0x2000),
+        Err(TranslateError::CrossesWindow {
+            address: (0x0200_0000u128 << 64) | 0x4003_f000,
+            size: 0x2000
+        })
+    );
+}
+
+#[test]
+fn test_regs_zero_address_cells() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/; / { #address-cells = <0>; #size-cells = <0>; empty { reg = <>; }; \
+         bus { #address-cells = <0>; #size-cells = <1>; dev { reg = <0x10 0x20>; }; }; };",
+    )
+    .unwrap();
+
+    assert!(fdt.get_by_path("/empty").unwrap().regs().is_empty());
+
+    let regs = fdt.get_by_path("/bus/dev").unwrap().regs();
+    let sizes: Vec<_> = regs.iter().map(|reg| reg.size).collect();
+    assert_eq!(sizes, [Some(0x10), Some(0x20)]);
+}
@@ -0,0 +1,142 @@
+//! Dynamic reserved memory placement tests.
+
+use fdt_edit::{Fdt, ReservedMemoryError, ReservedMemoryPlacement};
+
+const SOURCE: &str = r#"
+/dts-v1/;
+/memreserve/ 0x4ff00000 0x100000;
+
+/ {
+    #address-cells = <2>;
+    #size-cells = <2>;
+
+    memory@40000000 {
+        device_type = "memory";
+        reg = <0x0 0x40000000 0x0 0x10000000>;
+    };
+
+    reserved-memory {
+        #address-cells = <2>;
+        #size-cells = <2>;
+        ranges;
+
+        firmware@4fe00000 {
+            reg = <0x0 0x4fe00000 0x0 0x100000>;
+            no-map;
+        };
+
+        linux,cma {
+            compatible = "shared-dma-pool";
+            reusable;
+            size = <0x0 0x1000000>;
+            alignment = <0x0 0x400000>;
+        };
+
+        dma-pool {
+            compatible = "restricted-dma-pool";
+            size = <0x0 0x100000>;
+            alloc-ranges = <0x0 0x40000000 0x0 0x2000000>;
+        };
+
+        disabled-pool {
+            size = <0x0 0x100000>;
+            status = "disabled";
+        };
+    };
+};
+"#;
+
+#[test]
+fn test_plan_reserved_memory() {
+    let fdt = Fdt::from_dts(SOURCE).unwrap();
+    let plan = fdt.plan_reserved_memory().unwrap();
+
+    assert_eq!(
+        plan,
+        [
+            // Highest aligned slot below the static region and /memreserve/
+            ReservedMemoryPlacement {
+                path: "/reserved-memory/linux,cma".into(),
+                address: 0x4ec0_0000,
+                size: 0x100_0000,
+            },
+            // Top of its alloc-ranges window
+            ReservedMemoryPlacement {
+                path: "/reserved-memory/dma-pool".into(),
+                address: 0x41f0_0000,
+                size: 0x10_0000,
+            },
+        ]
+    );
+
+    // Planning leaves the tree alone
+    let cma = fdt.get_by_path("/reserved-memory/linux,cma").unwrap();
+    assert!(cma.as_node().get_property("reg").is_none());
+}
+
+#[test]
+fn test_allocate_reserved_memory_writes_reg() {
+    let mut fdt = Fdt::from_dts(SOURCE).unwrap();
+    let plan = fdt.allocate_reserved_memory().unwrap();
+    assert_eq!(plan.len(), 2);
+
+    let data = fdt.encode();
+    let raw = fdt_raw::Fdt::from_bytes(&data).unwrap();
+    let mut checked = 0;
+    for region in raw.reserved_memory_regions() {
+        let Some(placement) = plan
+            .iter()
+            .find(|p| p.path.ends_with(&format!("/{}", region.name())))
+        else {
+            continue;
+        };
+        assert!(!region.is_dynamic());
+        let reg = region.regions().next().unwrap();
+        assert_eq!((reg.address, reg.size), (placement.address, placement.size));
+        checked += 1;
+    }
+    assert_eq!(checked, 2);
+
+    // Everything is static now, so a second pass has nothing to do
+    assert!(fdt.plan_reserved_memory().unwrap().is_empty());
+}
+
+#[test]
+fn test_reserved_memory_no_space() {
+    let source = SOURCE.replace("size = <0x0 0x1000000>;", "size = <0x0 0x20000000>;");
+    let fdt = Fdt::from_dts(&source).unwrap();
+    assert_eq!(
+        fdt.plan_reserved_memory(),
+        Err(ReservedMemoryError::NoSpace {
+            path: "/reserved-memory/linux,cma".into(),
+            size: 0x2000_0000,
+            alignment: 0x40_0000,
+        })
+    );
+
+    let fdt = Fdt::from_dts("/dts-v1/; / { reserved-memory { }; };").unwrap();
+    assert_eq!(
+        fdt.plan_reserved_memory(),
+        Err(ReservedMemoryError::NoMemory)
+    );
+}
+
+#[test]
+fn test_reserved_memory_zero_cells() {
+    let source = SOURCE
+        .replace(
+            "#address-cells = <2>;\n        #size-cells = <2>;\n        ranges;",
+            "#address-cells = <0>;\n        #size-cells = <0>;\n        ranges;",
+        )
+        .replace("reg = <0x0 0x4fe00000 0x0 0x100000>;", "reg = <>;")
+        .replace(
+            "alloc-ranges = <0x0 0x40000000 0x0 0x2000000>;",
+            "alloc-ranges = <>;",
+        );
+    assert_ne!(source, SOURCE);
+    let fdt = Fdt::from_dts(&source).unwrap();
+
+    // Zero-width reg and alloc-ranges describe nothing, and sizes cannot
+    // be read, so there is nothing to place
+    assert!(fdt.plan_reserved_memory().unwrap().is_empty());
+}
@@ -0,0 +1,167 @@
+//! Thermal zone view tests.
+
+use dtb_file::*;
+use fdt_edit::{Fdt, NodeType, NodeTypeMut, Phandle, TripType};
+
+#[test]
+fn test_thermal_zone_rk3568() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let zones = fdt.thermal_zones();
+    assert_eq!(zones.len(), 2);
+    let soc = zones[0];
+    assert_eq!(soc.path(), "/thermal-zones/soc-thermal");
+    assert_eq!(soc.polling_delay(), Some(1000));
+    assert_eq!(soc.polling_delay_passive(), Some(20));
+    assert_eq!(soc.sustainable_power(), Some(905));
+
+    let sensors = soc.thermal_sensors();
+    assert_eq!(sensors.len(), 1);
+    assert_eq!(sensors[0].sensor, Phandle::from(0x1f));
+    assert_eq!(sensors[0].specifier, vec![0]);
+    assert_eq!(zones[1].thermal_sensors()[0].specifier, vec![1]);
+
+    let trips = soc.trips();
+    assert_eq!(trips.len(), 3);
+    assert_eq!(trips[0].name, "trip-point-0");
+    assert_eq!(trips[0].temperature, 75_000);
+    assert_eq!(trips[0].hysteresis, 2_000);
+    assert_eq!(trips[0].kind, TripType::Passive);
+    assert_eq!(trips[2].kind, TripType::Critical);
+    assert_eq!(trips[2].temperature, 115_000);
+
+    let maps = soc.cooling_maps();
+    assert_eq!(maps.len(), 2);
+    assert_eq!(maps[0].contribution, Some(1024));
+    let trip = soc.trip(maps[0].trip.unwrap()).unwrap();
+    assert_eq!(trip.name, "trip-point-1");
+    assert_eq!(maps[0].devices.len(), 1);
+    assert_eq!(maps[0].devices[0].device, Phandle::from(0xc));
+    assert_eq!(maps[0].devices[0].min_state(), None);
+    assert_eq!(maps[0].devices[0].max_state(), None);
+
+    assert!(matches!(
+        fdt.get_by_path("/thermal-zones/gpu-thermal").unwrap(),
+        NodeType::ThermalZone(_)
+    ));
+}
+
+#[test]
+fn test_cpu_thermal_zones() {
+    let raw_data = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let NodeType::Cpu(cpu0) = fdt.get_by_path("/cpus/cpu@0").unwrap() else {
+        panic!("cpu@0 should be a CPU node");
+    };
+    let zones = cpu0.thermal_zones();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0].path(), "/thermal-zones/soc-thermal");
+
+    let NodeType::Cpu(cpu1) = fdt.get_by_path("/cpus/cpu@100").unwrap() else {
+        panic!("cpu@100 should be a CPU node");
+    };
+    assert!(cpu1.thermal_zones().is_empty());
+}
+
+#[test]
+fn test_thermal_zone_phytium() {
+    let raw_data = fdt_phytium();
+    let fdt = Fdt::from_bytes(&raw_data).unwrap();
+
+    let zones = fdt.thermal_zones();
+    let sensor0 = zones
+        .iter()
+        .find(|zone| zone.path() == "/thermal-zones/sensor0")
+        .unwrap();
+    assert_eq!(sensor0.polling_delay_passive(), Some(100));
+
+    // One map cooling two CPUs.
+    let maps = sensor0.cooling_maps();
+    assert_eq!(maps.len(), 1);
+    assert_eq!(maps[0].devices.len(), 2);
+    assert_eq!(maps[0].contribution, None);
+    assert_eq!(
+        sensor0.trip(maps[0].trip.unwrap()).unwrap().temperature,
+        90_000
+    );
+}
+
+#[test]
+fn test_thermal_zone_from_dts() {
+    let fdt = Fdt::from_dts(
+        "/dts-v1/;
+        / {
+            tsens: sensor {
+                #thermal-sensor-cells = <0>;
+            };
+            fan: fan {
+                #cooling-cells = <2>;
+            };
+            thermal-zones {
+                board {
+                    polling-delay = <0>;
+                    thermal-sensors = <&tsens>;
+                    trips {
+                        cold {
+                            temperature = <0xffffd8f0>;
+                            type = \"active\";
+                        };
+                        fan_on: warm {
+                            temperature = <45000>;
+                            hysteresis = <1000>;
+                            type = \"active\";
+                        };
+                        odd {
+                            temperature = <50000>;
+                            type = \"custom\";
+                        };
+                    };
+                    cooling-maps {
+                        map {
+                            trip = <&fan_on>;
+                            cooling-device = <&fan 1 3>, <&fan>;
+                        };
+                    };
+                };
+            };
+        };",
+    )
+    .unwrap();
+
+    let zone = fdt.thermal_zones()[0];
+    assert_eq!(zone.polling_delay(), Some(0));
+    assert_eq!(zone.polling_delay_passive(), None);
+    assert!(zone.thermal_sensors()[0].specifier.is_empty());
+
+    let trips = zone.trips();
+    assert_eq!(trips[0].temperature, -10_000);
+    assert_eq!(trips[0].hysteresis, 0);
+    assert_eq!(trips[2].kind, TripType::Other("custom".into()));
+
+    // The second entry is missing its two state cells.
+    let map = &zone.cooling_maps()[0];
+    assert_eq!(map.devices.len(), 1);
+    assert_eq!(map.devices[0].min_state(), Some(1));
+    assert_eq!(map.devices[0].max_state(), Some(3));
+    assert_eq!(zone.trip(map.trip.unwrap()).unwrap().name, "warm");
+}
+
+#[test]
+fn test_add_thermal_zone() {
+    let mut fdt = Fdt::new();
+    let NodeTypeMut::Generic(mut root) = fdt.root_mut() else {
+        panic!("root should be generic");
+    };
+    let mut zones = root.add_child_generic("thermal-zones");
+    let mut zone = zones.add_child_thermal_zone("cpu-thermal");
+    zone.set_polling_delays(1000, 100);
+    zone.set_thermal_sensor(Phandle::from(0x10), &[2]);
+
+    let zones = fdt.thermal_zones();
+    assert_eq!(zones.len(), 1);
+    assert_eq!(zones[0].polling_delay(), Some(1000));
+    assert_eq!(zones[0].polling_delay_passive(), Some(100));
+    assert!(zones[0].trips().is_empty());
+}
@@ -0,0 +1,24 @@
+//! Regenerates the C header when the `cbindgen` feature is enabled.
+//!
+//! The header is written to `OUT_DIR`, never into the source tree; the
+//! copy in `include/libfdt.h` is checked in and updated by hand from it.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "cbindgen")]
+    generate_header();
+}
+
+#[cfg(feature = "cbindgen")]
+fn generate_header() {
+    use std::{env, path::PathBuf};
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    cbindgen::generate(&crate_dir)
+        .expect("failed to generate the C header")
+        .write_to_file(out_dir.join("libfdt.h"));
+}
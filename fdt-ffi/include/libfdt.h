@@ -0,0 +1,183 @@
+#ifndef LIBFDT_H
+#define LIBFDT_H
+
+/* Generated by cbindgen from fdt-ffi. Do not edit. */
+
+#include <stddef.h>
+
+/**
+ * The requested node or property does not exist.
+ */
+#define FDT_ERR_NOTFOUND 1
+
+/**
+ * A node or property with the requested name already exists.
+ */
+#define FDT_ERR_EXISTS 2
+
+/**
+ * The blob has no room left for the requested change.
+ */
+#define FDT_ERR_NOSPACE 3
+
+/**
+ * The offset is not the start of a node.
+ */
+#define FDT_ERR_BADOFFSET 4
+
+/**
+ * The path is malformed or names an unknown alias.
+ */
+#define FDT_ERR_BADPATH 5
+
+/**
+ * The phandle is invalid.
+ */
+#define FDT_ERR_BADPHANDLE 6
+
+/**
+ * The blob is in an unexpected state.
+ */
+#define FDT_ERR_BADSTATE 7
+
+/**
+ * The blob or structure block ends too early.
+ */
+#define FDT_ERR_TRUNCATED 8
+
+/**
+ * The blob does not start with the FDT magic number.
+ */
+#define FDT_ERR_BADMAGIC 9
+
+/**
+ * The blob's version is not supported.
+ */
+#define FDT_ERR_BADVERSION 10
+
+/**
+ * The structure block is malformed.
+ */
+#define FDT_ERR_BADSTRUCTURE 11
+
+/**
+ * The blocks are laid out in a way the operation cannot handle.
+ */
+#define FDT_ERR_BADLAYOUT 12
+
+/**
+ * An internal error.
+ */
+#define FDT_ERR_INTERNAL 13
+
+/**
+ * A `#address-cells` or `#size-cells` value is invalid.
+ */
+#define FDT_ERR_BADNCELLS 14
+
+/**
+ * A property value is invalid.
+ */
+#define FDT_ERR_BADVALUE 15
+
+/**
+ * The overlay is malformed.
+ */
+#define FDT_ERR_BADOVERLAY 16
+
+/**
+ * No phandles are left to allocate.
+ */
+#define FDT_ERR_NOPHANDLES 17
+
+/**
+ * Invalid flags were passed.
+ */
+#define FDT_ERR_BADFLAGS 18
+
+/**
+ * The blob is not correctly aligned.
+ */
+#define FDT_ERR_ALIGNMENT 19
+
+/**
+ * Returns the offset of the node at `path`.
+ *
+ * `path` is absolute, or starts with an alias name. A path component
+ * without a unit address also matches a node that has one.
+ *
+ * # Safety
+ *
+ * See the [crate documentation](crate#safety).
+ */
+int fdt_path_offset(const void *fdt, const char *path);
+
+/**
+ * Returns a pointer to the value of property `name` of the node at
+ * `nodeoffset`, storing its length in `*lenp`.
+ *
+ * On failure, returns null and stores the negative error code in
+ * `*lenp`. `lenp` may be null.
+ *
+ * # Safety
+ *
+ * See the [crate documentation](crate#safety).
+ */
+const void *fdt_getprop(const void *fdt, int nodeoffset, const char *name, int *lenp);
+
+/**
+ * Returns the offset of the node following `offset` in depth-first
+ * order, or of the root node when `offset` is negative.
+ *
+ * If `depth` is not null, it is adjusted by the change in depth. Returns
+ * `-FDT_ERR_NOTFOUND` after the last node, or, when `depth` is not null,
+ * as soon as the walk leaves the subtree `*depth` started in.
+ *
+ * # Safety
+ *
+ * See the [crate documentation](crate#safety).
+ */
+int fdt_next_node(const void *fdt, int offset, int *depth);
+
+/**
+ * Sets property `name` of the node at `nodeoffset` to the `len` bytes at
+ * `val`, adding the property if needed.
+ *
+ * Returns 0, or `-FDT_ERR_NOSPACE` if the result does not fit in the
+ * blob's `totalsize`.
+ *
+ * # Safety
+ *
+ * See the [crate documentation](crate#safety). `val` must point to `len`
+ * readable bytes unless `len` is 0.
+ */
+int fdt_setprop(void *fdt, int nodeoffset, const char *name, const void *val, int len);
+
+/**
+ * Adds an empty node called `name` under the node at `parentoffset` and
+ * returns its offset.
+ *
+ * Unlike libfdt, which inserts the node before the parent's existing
+ * children, the node becomes the parent's last child. Returns
+ * `-FDT_ERR_EXISTS` if the parent already has a child matching `name`.
+ *
+ * # Safety
+ *
+ * See the [crate documentation](crate#safety).
+ */
+int fdt_add_subnode(void *fdt, int parentoffset, const char *name);
+
+/**
+ * Removes the free space inside the blob and shrinks `totalsize` to the
+ * data it holds.
+ *
+ * The blob is re-encoded in the standard block order, which also drops
+ * `FDT_NOP` tokens.
+ *
+ * # Safety
+ *
+ * See the [crate documentation](crate#safety).
+ */
+int fdt_pack(void *fdt);
+
+#endif  /* LIBFDT_H */
@@ -0,0 +1,269 @@
+//! Blob access shared by the exported functions.
+//!
+//! Errors are carried as positive `FDT_ERR_*` codes and negated only at
+//! the ABI boundary.
+
+use core::ffi::{CStr, c_char, c_int, c_void};
+
+use fdt_edit::{EncodeOptions, NodeId, StringDedup};
+use fdt_raw::{Fdt, FdtError, Header, Node, Token, ValidationLevel, data::Bytes};
+
+use crate::*;
+
+/// Result whose error is a positive `FDT_ERR_*` code.
+pub(crate) type FfiResult<T> = Result<T, c_int>;
+
+/// Size of a structure block tag.
+const TAG_SIZE: usize = 4;
+
+/// Maps a parse error to the libfdt code closest in meaning.
+pub(crate) fn error_code(err: FdtError) -> c_int {
+    match err {
+        FdtError::NotFound
+        | FdtError::NoAlias(_)
+        | FdtError::NodeNotFound(_)
+        | FdtError::PropertyNotFound(_) => FDT_ERR_NOTFOUND,
+        FdtError::Exists => FDT_ERR_EXISTS,
+        FdtError::NoSpace { .. } => FDT_ERR_NOSPACE,
+        FdtError::InvalidMagic(_) => FDT_ERR_BADMAGIC,
+        FdtError::UnsupportedVersion { .. } => FDT_ERR_BADVERSION,
+        FdtError::BufferTooSmall { .. }
+        | FdtError::BlockOutOfBounds { .. }
+        | FdtError::MissingRsvmapTerminator => FDT_ERR_TRUNCATED,
+        FdtError::MisalignedBlock { .. } => FDT_ERR_ALIGNMENT,
+        FdtError::BlocksOverlap { .. } | FdtError::BadLayout => FDT_ERR_BADLAYOUT,
+        FdtError::MissingEndToken
+        | FdtError::InvalidInput
+        | FdtError::FromBytesUntilNull
+        | FdtError::Utf8Error(_)
+        | FdtError::TooDeep { .. } => FDT_ERR_BADSTRUCTURE,
+        FdtError::InvalidPtr | FdtError::NoMemory | FdtError::PathTooLong { .. } => {
+            FDT_ERR_INTERNAL
+        }
+    }
+}
+
+/// Converts a result to libfdt's convention of negative error codes.
+pub(crate) fn status(result: FfiResult<c_int>) -> c_int {
+    result.unwrap_or_else(|code| -code)
+}
+
+/// Returns the `totalsize` bytes of the blob at `fdt`.
+///
+/// # Safety
+///
+/// `fdt` must point to a blob that stays valid, and is not written through
+/// another pointer, for `'a`.
+pub(crate) unsafe fn blob<'a>(fdt: *const c_void) -> FfiResult<&'a [u8]> {
+    if fdt.is_null() {
+        return Err(FDT_ERR_BADMAGIC);
+    }
+    let header = unsafe { Header::from_ptr(fdt as *mut u8) }.map_err(error_code)?;
+    let data = unsafe { core::slice::from_raw_parts(fdt as *const u8, header.totalsize as usize) };
+    Fdt::from_bytes_checked(data, ValidationLevel::Header).map_err(error_code)?;
+    Ok(data)
+}
+
+/// Reads a C string argument, failing with `code` if it is null or not
+/// UTF-8.
+///
+/// # Safety
+///
+/// A non-null `s` must point to a NUL-terminated string.
+pub(crate) unsafe fn str_arg<'a>(s: *const c_char, code: c_int) -> FfiResult<&'a str> {
+    if s.is_null() {
+        return Err(code);
+    }
+    unsafe { CStr::from_ptr(s) }.to_str().map_err(|_| code)
+}
+
+/// Compares a node name with a path component the way libfdt does: a
+/// component without a unit address also matches names that have one.
+pub(crate) fn name_matches(name: &str, component: &str) -> bool {
+    name == component
+        || (!component.contains('@')
+            && name
+                .strip_prefix(component)
+                .is_some_and(|rest| rest.starts_with('@')))
+}
+
+/// Checks that an `FDT_BEGIN_NODE` tag is at `offset` and returns the
+/// offset of the tag following the node's name.
+pub(crate) fn node_body(fdt: &Fdt<'_>, offset: c_int) -> FfiResult<usize> {
+    let offset = usize::try_from(offset).map_err(|_| FDT_ERR_BADOFFSET)?;
+    if !offset.is_multiple_of(TAG_SIZE) {
+        return Err(FDT_ERR_BADOFFSET);
+    }
+    match next_tag(fdt, offset) {
+        Ok((Token::BeginNode, after)) => Ok(after),
+        _ => Err(FDT_ERR_BADOFFSET),
+    }
+}
+
+/// Finds the node whose `FDT_BEGIN_NODE` is at `offset`.
+///
+/// The offset is checked in place first; only a valid one costs a walk
+/// of the tree, which is needed to learn the node's path and cells.
+pub(crate) fn node_at<'a>(fdt: &Fdt<'a>, offset: c_int) -> FfiResult<Node<'a>> {
+    node_body(fdt, offset)?;
+    fdt.all_nodes()
+        .find(|node| node.offset() == offset as usize)
+        .ok_or(FDT_ERR_BADOFFSET)
+}
+
+/// Returns the value of property `name` of the node at `offset`.
+///
+/// Scans the node's own tags from its offset, like libfdt, without
+/// walking the nodes before it.
+pub(crate) fn property_at<'a>(fdt: &Fdt<'a>, offset: c_int, name: &str) -> FfiResult<&'a [u8]> {
+    let data = fdt.as_slice();
+    let header = fdt.header();
+    let base = header.off_dt_struct as usize;
+    let strings_start = header.off_dt_strings as usize;
+    let strings_end = strings_start.saturating_add(header.size_dt_strings as usize);
+    let strings = data
+        .get(strings_start..strings_end)
+        .ok_or(FDT_ERR_TRUNCATED)?;
+
+    let mut next = node_body(fdt, offset)?;
+    loop {
+        let current = next;
+        let (token, after) = next_tag(fdt, current)?;
+        next = after;
+        match token {
+            Token::Prop => {
+                let mut reader = Bytes::new(data).reader_at(base + current + TAG_SIZE);
+                let len = reader.read_u32().ok_or(FDT_ERR_TRUNCATED)? as usize;
+                let nameoff = reader.read_u32().ok_or(FDT_ERR_TRUNCATED)? as usize;
+                let value = reader.read_bytes(len).ok_or(FDT_ERR_TRUNCATED)?;
+                if nameoff >= strings.len() {
+                    return Err(FDT_ERR_BADSTRUCTURE);
+                }
+                let prop_name = Bytes::new(strings)
+                    .reader_at(nameoff)
+                    .read_cstr()
+                    .map_err(|_| FDT_ERR_TRUNCATED)?;
+                if prop_name == name {
+                    return Ok(value.as_slice());
+                }
+            }
+            Token::Nop => {}
+            Token::BeginNode | Token::EndNode | Token::End | Token::Data(_) => {
+                return Err(FDT_ERR_NOTFOUND);
+            }
+        }
+    }
+}
+
+/// Resolves an absolute path, or one starting with an alias, to a node
+/// offset.
+pub(crate) fn path_offset(fdt: &Fdt<'_>, path: &str) -> FfiResult<c_int> {
+    let (base, rest) = match path.strip_prefix('/') {
+        Some(rest) => ("/", rest),
+        None => {
+            let (alias, rest) = path.split_once('/').unwrap_or((path, ""));
+            let target = fdt.aliases().get(alias).ok_or(FDT_ERR_BADPATH)?;
+            (target, rest)
+        }
+    };
+
+    let mut nodes = fdt.all_nodes();
+    let mut current = nodes.next().ok_or(FDT_ERR_BADSTRUCTURE)?;
+    for component in base.split('/').chain(rest.split('/')) {
+        if component.is_empty() {
+            continue;
+        }
+        let level = current.level();
+        current = loop {
+            let node = nodes
+                .next()
+                .filter(|node| node.level() > level)
+                .ok_or(FDT_ERR_NOTFOUND)?;
+            if node.level() == level + 1 && name_matches(node.name(), component) {
+                break node;
+            }
+        };
+    }
+    Ok(current.offset() as c_int)
+}
+
+/// Reads the tag at structure block offset `offset` and returns it with
+/// the offset of the following tag, like libfdt's `fdt_next_tag`.
+pub(crate) fn next_tag(fdt: &Fdt<'_>, offset: usize) -> FfiResult<(Token, usize)> {
+    let data = fdt.as_slice();
+    let base = fdt.header().off_dt_struct as usize;
+    let start = base + offset;
+    if start + TAG_SIZE > data.len() {
+        return Err(FDT_ERR_TRUNCATED);
+    }
+
+    let mut reader = Bytes::new(data).reader_at(start);
+    let token = reader.read_token().map_err(error_code)?;
+    match token {
+        Token::BeginNode => {
+            reader.read_cstr().map_err(|_| FDT_ERR_TRUNCATED)?;
+            reader.align4();
+        }
+        Token::Prop => {
+            let len = reader.read_u32().ok_or(FDT_ERR_TRUNCATED)?;
+            reader.read_u32().ok_or(FDT_ERR_TRUNCATED)?;
+            reader.read_bytes(len as usize).ok_or(FDT_ERR_TRUNCATED)?;
+            reader.align4();
+        }
+        Token::EndNode | Token::Nop | Token::End => {}
+        Token::Data(_) => return Err(FDT_ERR_BADSTRUCTURE),
+    }
+    Ok((token, reader.position() - base))
+}
+
+/// Re-encodes the blob at `fdt` after `edit` changed the node at
+/// `nodeoffset`, keeping `totalsize`.
+///
+/// # Safety
+///
+/// `fdt` must point to a writable blob that is not accessed through
+/// another pointer during the call.
+pub(crate) unsafe fn edit<T>(
+    fdt: *mut c_void,
+    nodeoffset: c_int,
+    edit: impl FnOnce(&mut fdt_edit::Fdt, NodeId) -> FfiResult<T>,
+) -> FfiResult<T> {
+    let data = unsafe { blob(fdt)? };
+    let totalsize = data.len();
+    let view = Fdt::from_bytes(data).map_err(error_code)?;
+    let path = node_at(&view, nodeoffset)?.path();
+
+    let mut tree = fdt_edit::Fdt::from_bytes(data).map_err(error_code)?;
+    let id = tree.get_by_path_id(&path).ok_or(FDT_ERR_INTERNAL)?;
+    let result = edit(&mut tree, id)?;
+
+    unsafe { write_back(fdt, totalsize, &tree, totalsize)? };
+    Ok(result)
+}
+
+/// Encodes `tree` into the `capacity` bytes at `fdt`, zero-filling the
+/// rest, and sets `totalsize` to at least `min_totalsize`.
+///
+/// Strings are suffix-merged so that a blob produced by dtc, which shares
+/// suffixes too, does not grow when it is re-encoded.
+///
+/// # Safety
+///
+/// `fdt` must point to at least `capacity` writable bytes.
+pub(crate) unsafe fn write_back(
+    fdt: *mut c_void,
+    capacity: usize,
+    tree: &fdt_edit::Fdt,
+    min_totalsize: usize,
+) -> FfiResult<()> {
+    let encoded = tree.encode_with(&EncodeOptions::new().dedup(StringDedup::SuffixMerge));
+    if encoded.len() > capacity {
+        return Err(FDT_ERR_NOSPACE);
+    }
+    let out = unsafe { core::slice::from_raw_parts_mut(fdt as *mut u8, capacity) };
+    out[..encoded.len()].copy_from_slice(&encoded);
+    out[encoded.len()..].fill(0);
+    let totalsize = min_totalsize.max(encoded.len()) as u32;
+    out[4..8].copy_from_slice(&totalsize.to_be_bytes());
+    Ok(())
+}
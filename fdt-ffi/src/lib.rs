@@ -0,0 +1,284 @@
+//! libfdt-compatible C ABI.
+//!
+//! Exports a subset of libfdt's functions with the same signatures, node
+//! offsets and `-FDT_ERR_*` error codes, so that C code written against
+//! libfdt keeps working when linked against this crate instead. The
+//! matching header is checked in as `include/libfdt.h`; building with the
+//! `cbindgen` feature regenerates it into `OUT_DIR`, and a test checks
+//! that the two agree.
+//!
+//! Lookups run directly on the blob through fdt-raw. Edits decode the
+//! blob into an [`fdt_edit::Fdt`], apply the change and encode it back
+//! into the same buffer; as with libfdt, the blob must have room for the
+//! change within its `totalsize`, and offsets obtained before an edit
+//! are no longer valid after it.
+//!
+//! # Safety
+//!
+//! Every function takes a raw pointer to a blob, which must be valid for
+//! reads of its header and `totalsize` bytes (and writes, for the editing
+//! functions). String arguments must be NUL-terminated.
+//!
+//! # Features
+//!
+//! `std` is on by default. Without it the crate is `no_std` and only
+//! needs `alloc`; enable `c-allocator` and `panic-handler` when the
+//! firmware linking the static library provides neither.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use core::ffi::{c_char, c_int, c_void};
+
+use fdt_edit::{Node, Property};
+use fdt_raw::{Fdt, Token};
+
+mod blob;
+mod runtime;
+
+use blob::{FfiResult, status};
+
+/// The requested node or property does not exist.
+pub const FDT_ERR_NOTFOUND: c_int = 1;
+/// A node or property with the requested name already exists.
+pub const FDT_ERR_EXISTS: c_int = 2;
+/// The blob has no room left for the requested change.
+pub const FDT_ERR_NOSPACE: c_int = 3;
+/// The offset is not the start of a node.
+pub const FDT_ERR_BADOFFSET: c_int = 4;
+/// The path is malformed or names an unknown alias.
+pub const FDT_ERR_BADPATH: c_int = 5;
+/// The phandle is invalid.
+pub const FDT_ERR_BADPHANDLE: c_int = 6;
+/// The blob is in an unexpected state.
+pub const FDT_ERR_BADSTATE: c_int = 7;
+/// The blob or structure block ends too early.
+pub const FDT_ERR_TRUNCATED: c_int = 8;
+/// The blob does not start with the FDT magic number.
+pub const FDT_ERR_BADMAGIC: c_int = 9;
+/// The blob's version is not supported.
+pub const FDT_ERR_BADVERSION: c_int = 10;
+/// The structure block is malformed.
+pub const FDT_ERR_BADSTRUCTURE: c_int = 11;
+/// The blocks are laid out in a way the operation cannot handle.
+pub const FDT_ERR_BADLAYOUT: c_int = 12;
+/// An internal error.
+pub const FDT_ERR_INTERNAL: c_int = 13;
+/// A `#address-cells` or `#size-cells` value is invalid.
+pub const FDT_ERR_BADNCELLS: c_int = 14;
+/// A property value is invalid.
+pub const FDT_ERR_BADVALUE: c_int = 15;
+/// The overlay is malformed.
+pub const FDT_ERR_BADOVERLAY: c_int = 16;
+/// No phandles are left to allocate.
+pub const FDT_ERR_NOPHANDLES: c_int = 17;
+/// Invalid flags were passed.
+pub const FDT_ERR_BADFLAGS: c_int = 18;
+/// The blob is not correctly aligned.
+pub const FDT_ERR_ALIGNMENT: c_int = 19;
+
+/// Returns the offset of the node at `path`.
+///
+/// `path` is absolute, or starts with an alias name. A path component
+/// without a unit address also matches a node that has one.
+///
+/// # Safety
+///
+/// See the [crate documentation](crate#safety).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fdt_path_offset(fdt: *const c_void, path: *const c_char) -> c_int {
+    status((|| {
+        let fdt = Fdt::from_bytes(unsafe { blob::blob(fdt)? }).map_err(blob::error_code)?;
+        let path = unsafe { blob::str_arg(path, FDT_ERR_BADPATH)? };
+        blob::path_offset(&fdt, path)
+    })())
+}
+
+/// Returns a pointer to the value of property `name` of the node at
+/// `nodeoffset`, storing its length in `*lenp`.
+///
+/// On failure, returns null and stores the negative error code in
+/// `*lenp`. `lenp` may be null.
+///
+/// # Safety
+///
+/// See the [crate documentation](crate#safety).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fdt_getprop(
+    fdt: *const c_void,
+    nodeoffset: c_int,
+    name: *const c_char,
+    lenp: *mut c_int,
+) -> *const c_void {
+    let result = (|| -> FfiResult<&[u8]> {
+        let fdt = Fdt::from_bytes(unsafe { blob::blob(fdt)? }).map_err(blob::error_code)?;
+        let name = unsafe { blob::str_arg(name, FDT_ERR_NOTFOUND)? };
+        blob::property_at(&fdt, nodeoffset, name)
+    })();
+
+    let (value, len) = match result {
+        Ok(value) => (value.as_ptr().cast(), value.len() as c_int),
+        Err(code) => (core::ptr::null(), -code),
+    };
+    if let Some(lenp) = unsafe { lenp.as_mut() } {
+        *lenp = len;
+    }
+    value
+}
+
+/// Returns the offset of the node following `offset` in depth-first
+/// order, or of the root node when `offset` is negative.
+///
+/// If `depth` is not null, it is adjusted by the change in depth. Returns
+/// `-FDT_ERR_NOTFOUND` after the last node, or, when `depth` is not null,
+/// as soon as the walk leaves the subtree `*depth` started in.
+///
+/// # Safety
+///
+/// See the [crate documentation](crate#safety).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fdt_next_node(
+    fdt: *const c_void,
+    offset: c_int,
+    depth: *mut c_int,
+) -> c_int {
+    let mut depth = unsafe { depth.as_mut() };
+    status((|| {
+        let fdt = Fdt::from_bytes(unsafe { blob::blob(fdt)? }).map_err(blob::error_code)?;
+
+        let mut next = 0;
+        if offset >= 0 {
+            let offset = offset as usize;
+            match blob::next_tag(&fdt, offset) {
+                Ok((Token::BeginNode, after)) if offset.is_multiple_of(4) => next = after,
+                _ => return Err(FDT_ERR_BADOFFSET),
+            }
+        }
+
+        loop {
+            let current = next;
+            let (token, after) = blob::next_tag(&fdt, current)?;
+            next = after;
+            match token {
+                Token::BeginNode => {
+                    if let Some(depth) = depth.as_deref_mut() {
+                        *depth += 1;
+                    }
+                    return Ok(current as c_int);
+                }
+                Token::EndNode => {
+                    if let Some(depth) = depth.as_deref_mut() {
+                        *depth -= 1;
+                        if *depth < 0 {
+                            return Ok(next as c_int);
+                        }
+                    }
+                }
+                Token::End => return Err(FDT_ERR_NOTFOUND),
+                Token::Prop | Token::Nop | Token::Data(_) => {}
+            }
+        }
+    })())
+}
+
+/// Sets property `name` of the node at `nodeoffset` to the `len` bytes at
+/// `val`, adding the property if needed.
+///
+/// Returns 0, or `-FDT_ERR_NOSPACE` if the result does not fit in the
+/// blob's `totalsize`.
+///
+/// # Safety
+///
+/// See the [crate documentation](crate#safety). `val` must point to `len`
+/// readable bytes unless `len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fdt_setprop(
+    fdt: *mut c_void,
+    nodeoffset: c_int,
+    name: *const c_char,
+    val: *const c_void,
+    len: c_int,
+) -> c_int {
+    status((|| {
+        let name = unsafe { blob::str_arg(name, FDT_ERR_BADVALUE)? };
+        let len = usize::try_from(len).map_err(|_| FDT_ERR_BADVALUE)?;
+        let value = if len == 0 {
+            &[][..]
+        } else if val.is_null() {
+            return Err(FDT_ERR_BADVALUE);
+        } else {
+            unsafe { core::slice::from_raw_parts(val as *const u8, len) }
+        };
+
+        unsafe {
+            blob::edit(fdt, nodeoffset, |tree, id| {
+                let node = tree.node_mut(id).ok_or(FDT_ERR_INTERNAL)?;
+                node.set_property(Property::new(name, value.to_vec()));
+                Ok(0)
+            })
+        }
+    })())
+}
+
+/// Adds an empty node called `name` under the node at `parentoffset` and
+/// returns its offset.
+///
+/// Unlike libfdt, which inserts the node before the parent's existing
+/// children, the node becomes the parent's last child. Returns
+/// `-FDT_ERR_EXISTS` if the parent already has a child matching `name`.
+///
+/// # Safety
+///
+/// See the [crate documentation](crate#safety).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fdt_add_subnode(
+    fdt: *mut c_void,
+    parentoffset: c_int,
+    name: *const c_char,
+) -> c_int {
+    status((|| {
+        let name = unsafe { blob::str_arg(name, FDT_ERR_BADPATH)? };
+        if name.is_empty() || name.contains('/') {
+            return Err(FDT_ERR_BADPATH);
+        }
+
+        let path = unsafe {
+            blob::edit(fdt, parentoffset, |tree, id| {
+                let parent = tree.node(id).ok_or(FDT_ERR_INTERNAL)?;
+                let exists = parent.children().iter().any(|&child| {
+                    tree.node(child)
+                        .is_some_and(|child| blob::name_matches(&child.name, name))
+                });
+                if exists {
+                    return Err(FDT_ERR_EXISTS);
+                }
+                let child = tree.add_node(id, Node::new(name));
+                Ok(tree.path_of(child))
+            })?
+        };
+
+        let fdt = Fdt::from_bytes(unsafe { blob::blob(fdt)? }).map_err(blob::error_code)?;
+        blob::path_offset(&fdt, &path)
+    })())
+}
+
+/// Removes the free space inside the blob and shrinks `totalsize` to the
+/// data it holds.
+///
+/// The blob is re-encoded in the standard block order, which also drops
+/// `FDT_NOP` tokens.
+///
+/// # Safety
+///
+/// See the [crate documentation](crate#safety).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fdt_pack(fdt: *mut c_void) -> c_int {
+    status((|| {
+        let data = unsafe { blob::blob(fdt)? };
+        let totalsize = data.len();
+        let tree = fdt_edit::Fdt::from_bytes(data).map_err(blob::error_code)?;
+        unsafe { blob::write_back(fdt, totalsize, &tree, 0)? };
+        Ok(0)
+    })())
+}
@@ -0,0 +1,58 @@
+//! Runtime support for builds without std.
+//!
+//! A `no_std` static library still needs a global allocator and a panic
+//! handler. Firmware that already has both leaves these features off;
+//! otherwise `c-allocator` forwards to the C library's allocator and
+//! `panic-handler` stops the calling CPU.
+
+#[cfg(feature = "c-allocator")]
+mod allocator {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::ffi::c_void;
+
+    unsafe extern "C" {
+        fn malloc(size: usize) -> *mut c_void;
+        fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void;
+        fn free(ptr: *mut c_void);
+    }
+
+    /// Alignment `malloc` guarantees on every supported target.
+    const MALLOC_ALIGN: usize = 2 * core::mem::size_of::<usize>();
+
+    /// Global allocator backed by `malloc`.
+    ///
+    /// Requests aligned beyond what `malloc` guarantees fail.
+    struct CAllocator;
+
+    unsafe impl GlobalAlloc for CAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            if layout.align() > MALLOC_ALIGN {
+                return core::ptr::null_mut();
+            }
+            unsafe { malloc(layout.size()) }.cast()
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
+            unsafe { free(ptr.cast()) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            if layout.align() > MALLOC_ALIGN {
+                return core::ptr::null_mut();
+            }
+            unsafe { realloc(ptr.cast(), new_size) }.cast()
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: CAllocator = CAllocator;
+}
+
+/// Spins forever; there is nothing to unwind to across the C ABI.
+#[cfg(all(feature = "panic-handler", not(feature = "std")))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo<'_>) -> ! {
+    loop {
+        core::hint::spin_loop();
+    }
+}
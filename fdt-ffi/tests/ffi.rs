@@ -0,0 +1,195 @@
+//! C ABI tests, cross-checked against fdt-raw.
+
+use core::ffi::{c_int, c_void};
+use std::ffi::CString;
+
+use dtb_file::*;
+use fdt_ffi::*;
+use fdt_raw::Fdt;
+
+fn path_offset(data: &[u8], path: &str) -> c_int {
+    let path = CString::new(path).unwrap();
+    unsafe { fdt_path_offset(data.as_ptr().cast(), path.as_ptr()) }
+}
+
+fn getprop<'a>(data: &'a [u8], offset: c_int, name: &str) -> Result<&'a [u8], c_int> {
+    let name = CString::new(name).unwrap();
+    let mut len = 0;
+    let value = unsafe { fdt_getprop(data.as_ptr().cast(), offset, name.as_ptr(), &mut len) };
+    if value.is_null() {
+        Err(len)
+    } else {
+        Ok(unsafe { core::slice::from_raw_parts(value.cast(), len as usize) })
+    }
+}
+
+fn setprop(data: &mut [u8], offset: c_int, name: &str, value: &[u8]) -> c_int {
+    let name = CString::new(name).unwrap();
+    unsafe {
+        fdt_setprop(
+            data.as_mut_ptr().cast(),
+            offset,
+            name.as_ptr(),
+            value.as_ptr().cast::<c_void>(),
+            value.len() as c_int,
+        )
+    }
+}
+
+fn add_subnode(data: &mut [u8], offset: c_int, name: &str) -> c_int {
+    let name = CString::new(name).unwrap();
+    unsafe { fdt_add_subnode(data.as_mut_ptr().cast(), offset, name.as_ptr()) }
+}
+
+#[test]
+fn test_path_offset_matches_fdt_raw() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    for node in fdt.all_nodes() {
+        assert_eq!(path_offset(&raw, &node.path()), node.offset() as c_int);
+    }
+    assert_eq!(path_offset(&raw, "/"), 0);
+    assert_eq!(
+        path_offset(&raw, "/memory"),
+        path_offset(&raw, "/memory@40000000")
+    );
+    assert_eq!(path_offset(&raw, "/missing"), -FDT_ERR_NOTFOUND);
+    assert_eq!(path_offset(&raw, "nosuchalias"), -FDT_ERR_BADPATH);
+
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let serial = fdt.find_by_alias("serial0").unwrap();
+    assert_eq!(path_offset(&raw, "serial0"), serial.offset() as c_int);
+}
+
+#[test]
+fn test_next_node_walks_all_nodes() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let expected: Vec<_> = fdt
+        .all_nodes()
+        .map(|node| (node.offset() as c_int, node.level() as c_int))
+        .collect();
+
+    let mut walked = Vec::new();
+    let mut depth = 0;
+    let mut offset = unsafe { fdt_next_node(raw.as_ptr().cast(), -1, &mut depth) };
+    while offset >= 0 {
+        walked.push((offset, depth - 1));
+        offset = unsafe { fdt_next_node(raw.as_ptr().cast(), offset, &mut depth) };
+    }
+    assert_eq!(offset, -FDT_ERR_NOTFOUND);
+    assert_eq!(walked, expected);
+
+    let bad = unsafe { fdt_next_node(raw.as_ptr().cast(), 2, core::ptr::null_mut()) };
+    assert_eq!(bad, -FDT_ERR_BADOFFSET);
+}
+
+#[test]
+fn test_getprop() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let node = fdt.find_by_path("/chosen").unwrap();
+    let offset = path_offset(&raw, "/chosen");
+
+    let expected = node.find_property("rng-seed").unwrap();
+    let value = getprop(&raw, offset, "rng-seed").unwrap();
+    assert_eq!(value, expected.data().as_slice());
+    assert_eq!(value.len(), 32);
+
+    assert_eq!(getprop(&raw, offset, "missing"), Err(-FDT_ERR_NOTFOUND));
+    assert_eq!(getprop(&raw, 3, "rng-seed"), Err(-FDT_ERR_BADOFFSET));
+    // The tag after "chosen" and its padding is a property, not a node
+    assert_eq!(
+        getprop(&raw, offset + 12, "rng-seed"),
+        Err(-FDT_ERR_BADOFFSET)
+    );
+    // Properties of descendants are not found on the root
+    assert_eq!(getprop(&raw, 0, "rng-seed"), Err(-FDT_ERR_NOTFOUND));
+}
+
+#[test]
+fn test_setprop_and_add_subnode() {
+    let raw = fdt_qemu();
+    let mut data = raw.to_vec();
+    let totalsize = Fdt::from_bytes(&data).unwrap().header().totalsize;
+
+    let chosen = path_offset(&data, "/chosen");
+    assert_eq!(
+        setprop(&mut data, chosen, "bootargs", b"console=ttyAMA0\0"),
+        0
+    );
+    let chosen = path_offset(&data, "/chosen");
+    assert_eq!(
+        getprop(&data, chosen, "bootargs").unwrap(),
+        b"console=ttyAMA0\0"
+    );
+
+    let root = path_offset(&data, "/");
+    let offset = add_subnode(&mut data, root, "test@1000");
+    assert!(offset > 0);
+    assert_eq!(path_offset(&data, "/test@1000"), offset);
+    assert_eq!(add_subnode(&mut data, root, "test"), -FDT_ERR_EXISTS);
+    assert_eq!(add_subnode(&mut data, root, "a/b"), -FDT_ERR_BADPATH);
+
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    assert_eq!(fdt.header().totalsize, totalsize);
+    assert!(fdt.find_by_path("/test@1000").is_some());
+    assert!(fdt.find_by_path("/psci").is_some());
+}
+
+#[test]
+fn test_setprop_nospace() {
+    let raw = fdt_rpi_4b();
+    let totalsize = Fdt::from_bytes(&raw).unwrap().header().totalsize as usize;
+    let mut data = raw[..totalsize].to_vec();
+
+    let chosen = path_offset(&data, "/chosen");
+    let before = data.clone();
+    assert_eq!(
+        setprop(&mut data, chosen, "new-prop", &[0; 64]),
+        -FDT_ERR_NOSPACE
+    );
+    assert_eq!(data, before);
+}
+
+#[test]
+fn test_pack() {
+    let raw = fdt_qemu();
+    let mut data = raw.to_vec();
+    let before = Fdt::from_bytes(&data).unwrap().header().totalsize;
+    let count = Fdt::from_bytes(&data).unwrap().all_nodes().count();
+
+    assert_eq!(unsafe { fdt_pack(data.as_mut_ptr().cast()) }, 0);
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    assert!(fdt.header().totalsize < before);
+    assert_eq!(fdt.all_nodes().count(), count);
+}
+
+#[test]
+fn test_header_declares_functions() {
+    let header = include_str!("../include/libfdt.h");
+    for name in [
+        "fdt_path_offset",
+        "fdt_getprop",
+        "fdt_next_node",
+        "fdt_setprop",
+        "fdt_add_subnode",
+        "fdt_pack",
+        "FDT_ERR_NOTFOUND",
+    ] {
+        assert!(header.contains(name), "{name} missing from libfdt.h");
+    }
+}
+
+#[cfg(feature = "cbindgen")]
+#[test]
+fn test_header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/libfdt.h"));
+    assert!(
+        generated == include_str!("../include/libfdt.h"),
+        "include/libfdt.h is stale, copy it from {}",
+        env!("OUT_DIR")
+    );
+}
@@ -74,6 +74,17 @@ impl<'a> Bytes<'a> {
         }
     }
 
+    /// Creates a new `Bytes` from a subrange of the current data.
+    ///
+    /// Returns `None` if the range is reversed or `range.end` exceeds the
+    /// current length.
+    pub(crate) fn try_slice(&self, range: Range<usize>) -> Option<Self> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+        Some(self.slice(range))
+    }
+
     /// Returns the underlying byte slice as reference.
     pub fn as_slice(&self) -> &'a [u8] {
         &self.all[self.range.clone()]
@@ -161,18 +172,17 @@ impl<'a> Reader<'a> {
     ///
     /// Returns `None` if insufficient bytes remain.
     pub fn read_bytes(&mut self, size: usize) -> Option<Bytes<'a>> {
-        if self.iter + size > self.bytes.len() {
-            return None;
-        }
         let start = self.iter;
-        self.iter += size;
-        Some(self.bytes.slice(start..start + size))
+        let end = start.checked_add(size)?;
+        let bytes = self.bytes.try_slice(start..end)?;
+        self.iter = end;
+        Some(bytes)
     }
 
     /// Reads a big-endian u32 value.
     pub fn read_u32(&mut self) -> Option<u32> {
         let bytes = self.read_bytes(U32_SIZE)?;
-        Some(u32::from_be_bytes(bytes.as_slice().try_into().unwrap()))
+        Some(u32::from_be_bytes(*bytes.as_slice().first_chunk()?))
     }
 
     /// Reads a big-endian u64 value (composed of two u32 values).
@@ -196,10 +206,33 @@ impl<'a> Reader<'a> {
 
     /// Reads a token from the FDT structure block.
     pub fn read_token(&mut self) -> Result<Token, FdtError> {
-        let bytes = self.read_bytes(U32_SIZE).ok_or(FdtError::BufferTooSmall {
+        let value = self.read_u32().ok_or(FdtError::BufferTooSmall {
             pos: self.position(),
         })?;
-        Ok(u32::from_be_bytes(bytes.as_slice().try_into().unwrap()).into())
+        Ok(value.into())
+    }
+
+    /// Reads a null-terminated UTF-8 string, advancing past the terminator.
+    pub fn read_cstr(&mut self) -> Result<&'a str, FdtError> {
+        let bytes = self.remain();
+        let cstr = CStr::from_bytes_until_nul(bytes.as_slice())?;
+        let s = cstr.to_str()?;
+        // Skip string content + null terminator
+        let _ = self.read_bytes(s.len() + 1);
+        Ok(s)
+    }
+
+    /// Aligns the read position to a 4-byte boundary.
+    ///
+    /// FDT structures are 4-byte aligned, so after reading variable-length
+    /// data (like node names), the padding up to the next boundary is skipped.
+    pub fn align4(&mut self) {
+        let pos = self.position();
+        let aligned = (pos + U32_SIZE - 1) & !(U32_SIZE - 1);
+        let skip = aligned - pos;
+        if skip > 0 {
+            let _ = self.read_bytes(skip);
+        }
     }
 
     /// Moves the read position back by the specified size.
@@ -232,8 +265,7 @@ impl Iterator for U32Iter<'_> {
     type Item = u32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let bytes = self.reader.read_bytes(U32_SIZE)?;
-        Some(u32::from_be_bytes(bytes.as_slice().try_into().unwrap()))
+        self.reader.read_u32()
     }
 }
 
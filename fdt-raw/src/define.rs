@@ -180,6 +180,108 @@ pub enum FdtError {
     /// The specified property was not found
     #[error("property `{0}` not found")]
     PropertyNotFound(&'static str),
+    /// The blob's version is outside the range this crate understands
+    #[error("unsupported version {version} (last compatible version {last_comp_version})")]
+    UnsupportedVersion {
+        /// The `version` header field
+        version: u32,
+        /// The `last_comp_version` header field
+        last_comp_version: u32,
+    },
+    /// A block does not start at the alignment the specification requires
+    #[error("{block} block at offset {offset:#x} is misaligned")]
+    MisalignedBlock {
+        /// Name of the block
+        block: &'static str,
+        /// Offset of the block from the start of the blob
+        offset: usize,
+    },
+    /// A block extends past `totalsize`
+    #[error("{block} block at offset {offset:#x} with size {size:#x} exceeds totalsize")]
+    BlockOutOfBounds {
+        /// Name of the block
+        block: &'static str,
+        /// Offset of the block from the start of the blob
+        offset: usize,
+        /// Size of the block in bytes
+        size: usize,
+    },
+    /// Two blocks (or a block and the header) share bytes
+    #[error("{first} block overlaps {second} block")]
+    BlocksOverlap {
+        /// Name of the first block
+        first: &'static str,
+        /// Name of the second block
+        second: &'static str,
+    },
+    /// The memory reservation block has no zero terminator within `totalsize`
+    #[error("memory reservation block is not terminated")]
+    MissingRsvmapTerminator,
+    /// The last token of the structure block is not `FDT_END`
+    #[error("structure block does not end with FDT_END")]
+    MissingEndToken,
+    /// Nodes are nested deeper than the traversal stack can hold
+    #[error("node nesting exceeds the maximum depth of {max_depth}")]
+    TooDeep {
+        /// Capacity of the traversal stack
+        max_depth: usize,
+    },
+    /// A node path does not fit in the provided buffer
+    #[error("node path does not fit in {capacity} bytes")]
+    PathTooLong {
+        /// Capacity of the path buffer in bytes
+        capacity: usize,
+    },
+    /// An in-place edit needs more room than the blob or buffer has
+    #[error("not enough space: {needed} bytes needed, {available} available")]
+    NoSpace {
+        /// Bytes the edit requires
+        needed: usize,
+        /// Bytes that are available
+        available: usize,
+    },
+    /// A node with the requested name already exists
+    #[error("node already exists")]
+    Exists,
+    /// The blocks are not laid out as memory reservation, structure and
+    /// strings in that order, so they cannot be grown in place
+    #[error("blocks are not in the order required for in-place growth")]
+    BadLayout,
+}
+
+/// Errors from fallible address translation.
+///
+/// Addresses are carried as `u128` so 3-cell PCI addresses keep their
+/// `phys.hi` cell in bits 64..96.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslateError {
+    /// The node or one of its ancestors does not exist
+    #[error("node not found")]
+    NodeNotFound,
+    /// A bus on the path has no `ranges` property, so its children are not
+    /// memory mapped
+    #[error("address {address:#x} is on a bus without `ranges`")]
+    NoRanges {
+        /// Address in the child address space of that bus
+        address: u128,
+    },
+    /// No `ranges` window of a bus contains the address
+    #[error("address {address:#x} is outside every `ranges` window")]
+    Unmapped {
+        /// Address in the child address space of that bus
+        address: u128,
+    },
+    /// The address starts inside a window but the region runs past its end
+    #[error("region {address:#x}+{size:#x} crosses a `ranges` window boundary")]
+    CrossesWindow {
+        /// Address in the child address space of that bus
+        address: u128,
+        /// Size of the region
+        size: u64,
+    },
+    /// The translated address does not fit the parent address space
+    #[error("translated address overflows")]
+    Overflow,
 }
 
 impl From<FromBytesUntilNulError> for FdtError {
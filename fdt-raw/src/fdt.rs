@@ -5,11 +5,12 @@
 //! paths, translating addresses, and accessing special nodes like
 //! /chosen and /memory.
 
-use core::fmt;
+use core::{fmt, ops::Deref};
 
 use crate::{
-    Chosen, FdtError, Memory, MemoryReservation, Node, Property, VecRange, data, data::Bytes,
-    fmt_utils, header::Header, iter::FdtIter,
+    Aliases, Chosen, CpusView, FdtError, Memory, MemoryReservation, Node, Phandle, Property,
+    ReservedMemoryRegion, Symbols, TranslateError, ValidationLevel, VecRange, data, data::Bytes,
+    fmt_utils, header::Header, iter::FdtIter, validate,
 };
 
 /// Iterator over memory reservation entries.
@@ -27,19 +28,14 @@ impl<'a> Iterator for MemoryReservationIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         // Ensure we have enough data to read a complete entry
-        if self.offset + data::MEM_RSV_ENTRY_SIZE > self.data.len() {
-            return None;
-        }
-
-        // Read address (8 bytes, big-endian)
-        let address_bytes = &self.data[self.offset..self.offset + 8];
-        let address = u64::from_be_bytes(address_bytes.try_into().unwrap());
-        self.offset += 8;
+        let end = self.offset.checked_add(data::MEM_RSV_ENTRY_SIZE)?;
+        let entry = self.data.get(self.offset..end)?;
+        self.offset = end;
 
-        // Read size (8 bytes, big-endian)
-        let size_bytes = &self.data[self.offset..self.offset + 8];
-        let size = u64::from_be_bytes(size_bytes.try_into().unwrap());
-        self.offset += 8;
+        // Read address and size (8 bytes each, big-endian)
+        let (address_bytes, size_bytes) = entry.split_first_chunk::<8>()?;
+        let address = u64::from_be_bytes(*address_bytes);
+        let size = u64::from_be_bytes(*size_bytes.first_chunk::<8>()?);
 
         // Check for terminator (both address and size are zero)
         if address == 0 && size == 0 {
@@ -89,6 +85,32 @@ impl<'a> Fdt<'a> {
         })
     }
 
+    /// Creates an `Fdt` from an already parsed header.
+    pub(crate) fn from_parts(header: Header, data: &'a [u8]) -> Self {
+        Fdt {
+            header,
+            data: Bytes::new(data),
+        }
+    }
+
+    /// Create a new `Fdt` from a byte slice, validating its layout first.
+    ///
+    /// Use this instead of [`Fdt::from_bytes`] for blobs that come from an
+    /// untrusted source. See [`ValidationLevel`] for what each level checks.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`Fdt::from_bytes`], returns
+    /// `FdtError::UnsupportedVersion`, `FdtError::MisalignedBlock`,
+    /// `FdtError::BlockOutOfBounds`, `FdtError::BlocksOverlap`,
+    /// `FdtError::MissingRsvmapTerminator` or `FdtError::MissingEndToken`
+    /// describing the first problem found.
+    pub fn from_bytes_checked(data: &'a [u8], level: ValidationLevel) -> Result<Fdt<'a>, FdtError> {
+        let fdt = Self::from_bytes(data)?;
+        validate::validate(&fdt.header, data, level)?;
+        Ok(fdt)
+    }
+
     /// Create a new `Fdt` from a raw pointer.
     ///
     /// Parses an FDT from the memory location pointed to by `ptr`.
@@ -124,10 +146,30 @@ impl<'a> Fdt<'a> {
     }
 
     /// Returns an iterator over all nodes in the device tree.
+    ///
+    /// Supports trees up to [`DEFAULT_DEPTH`](crate::DEFAULT_DEPTH) levels
+    /// deep unless the `alloc` feature is enabled; see
+    /// [`Fdt::all_nodes_with`] for deeper trees.
     pub fn all_nodes(&self) -> FdtIter<'a> {
         FdtIter::new(self.clone())
     }
 
+    /// Returns an iterator over all nodes that tracks up to `DEPTH` levels.
+    ///
+    /// Iteration stops with [`FdtError::TooDeep`] (see [`FdtIter::error`])
+    /// at the first node nested deeper than that.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// for node in fdt.all_nodes_with::<64>() {
+    ///     println!("{}", node.path());
+    /// }
+    /// ```
+    pub fn all_nodes_with<const DEPTH: usize>(&self) -> FdtIter<'a, DEPTH> {
+        FdtIter::new(self.clone())
+    }
+
     /// Find a node by its absolute path or alias.
     ///
     /// The path can be an absolute path starting with '/', or an alias
@@ -232,8 +274,13 @@ impl<'a> Fdt<'a> {
     /// Looks up the alias in the /aliases node and returns the corresponding
     /// path string.
     fn resolve_alias(&self, alias: &str) -> Option<&'a str> {
-        let aliases_node = self.find_by_path("/aliases")?;
-        aliases_node.find_property_str(alias)
+        self.aliases().get(alias)
+    }
+
+    /// Returns the direct child of the root node called `name`.
+    fn root_child(&self, name: &str) -> Option<Node<'a>> {
+        self.find_children_by_path("/")
+            .find(|node| node.name() == name)
     }
 
     /// Normalize a path to an absolute path.
@@ -291,44 +338,19 @@ impl<'a> Fdt<'a> {
     /// value is preserved for that address.
     pub fn translate_addresses(&self, path: &'a str, addresses: &mut [u64]) {
         let path = match self.normalize_path(path) {
-            Some(p) => p,
+            Some(p) => p.trim_end_matches('/'),
             None => return,
         };
 
-        let path_parts = Self::split_path(path);
-        if path_parts.is_empty() {
-            return;
-        }
-
-        self.translate_addresses_with_parts(&path_parts, addresses);
-    }
-
-    /// Splits an absolute path into its component parts.
-    ///
-    /// Takes a path like "/soc/serial@0" and returns ["soc", "serial@0"].
-    fn split_path(path: &str) -> heapless::Vec<&str, 16> {
-        path.trim_matches('/')
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect()
-    }
-
-    /// Performs batch address translation using pre-split path components.
-    ///
-    /// Walks up the tree from the deepest node, applying `ranges` at each level
-    /// to all addresses in the slice. Each parent node is looked up only once.
-    fn translate_addresses_with_parts(&self, path_parts: &[&str], addresses: &mut [u64]) {
-        // Walk up from the deepest node, applying ranges at each level
-        // We start from the second-to-last level (the target node itself is skipped)
-        for depth in (0..path_parts.len()).rev() {
-            let parent_parts = &path_parts[..depth];
-
-            if parent_parts.is_empty() {
-                // Reached root node, no more translation needed
-                break;
-            }
-
-            if let Some(parent_node) = self.find_node_by_parts(parent_parts) {
+        // Walk up from the deepest node, applying ranges at each level.
+        // Every '/' after the first one ends the path of an ancestor; the
+        // target node itself and the root are skipped.
+        let ancestors = path
+            .rmatch_indices('/')
+            .filter(|&(i, _)| i > 0 && !path[..i].ends_with('/'))
+            .map(|(i, _)| &path[..i]);
+        for parent_path in ancestors {
+            if let Some(parent_node) = self.find_by_path(parent_path) {
                 let ranges = match parent_node.ranges() {
                     Some(r) => r,
                     None => break, // No ranges property, stop translation
@@ -342,17 +364,47 @@ impl<'a> Fdt<'a> {
         }
     }
 
-    /// Finds a node by its path component parts.
-    fn find_node_by_parts(&self, parts: &[&str]) -> Option<Node<'a>> {
-        let mut path = heapless::String::<256>::new();
-        path.push('/').ok();
-        for (i, part) in parts.iter().enumerate() {
-            if i > 0 {
-                path.push('/').ok();
-            }
-            path.push_str(part).ok();
+    /// Translates the region `[address, address + size)` of the node at
+    /// `path` to a CPU physical address, or reports why it cannot.
+    ///
+    /// Unlike [`Fdt::translate_address`], a bus without `ranges` yields
+    /// [`TranslateError::NoRanges`] instead of the input address, an empty
+    /// `ranges` is an identity mapping, and the whole region must fit one
+    /// window at every level. For 3-cell (PCI) addresses, bits 64..96 of
+    /// `address` hold `phys.hi`, whose space code selects the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Node path (absolute path starting with '/' or alias name)
+    /// * `address` - Device address from the node's `reg` property
+    /// * `size` - Size of the region, 0 to check only the start address
+    pub fn try_translate(
+        &self,
+        path: &'a str,
+        address: u128,
+        size: u64,
+    ) -> Result<u64, TranslateError> {
+        let path = self
+            .normalize_path(path)
+            .ok_or(TranslateError::NodeNotFound)?
+            .trim_end_matches('/');
+        if !path.is_empty() && self.find_by_path(path).is_none() {
+            return Err(TranslateError::NodeNotFound);
+        }
+
+        let ancestors = path
+            .rmatch_indices('/')
+            .filter(|&(i, _)| i > 0 && !path[..i].ends_with('/'))
+            .map(|(i, _)| &path[..i]);
+        let mut address = address;
+        for parent_path in ancestors {
+            let parent = self
+                .find_by_path(parent_path)
+                .ok_or(TranslateError::NodeNotFound)?;
+            address = parent.translate_child(address, size)?;
         }
-        self.find_by_path(path.as_str())
+
+        u64::try_from(address).map_err(|_| TranslateError::Overflow)
     }
 
     /// Translates a single address using the given ranges.
@@ -362,9 +414,13 @@ impl<'a> Fdt<'a> {
     fn apply_ranges_one(ranges: &VecRange<'_>, address: u64) -> u64 {
         for range in ranges.iter() {
             // Check if the address falls within this range
-            if address >= range.child_address && address < range.child_address + range.length {
-                let offset = address - range.child_address;
-                return range.parent_address + offset;
+            let Some(offset) = address.checked_sub(range.child_address) else {
+                continue;
+            };
+            if offset < range.length
+                && let Some(translated) = range.parent_address.checked_add(offset)
+            {
+                return translated;
             }
         }
 
@@ -390,6 +446,54 @@ impl<'a> Fdt<'a> {
         None
     }
 
+    /// Returns an iterator over the `(alias, path)` pairs of /aliases.
+    ///
+    /// The iterator is empty if the tree has no /aliases node.
+    pub fn aliases(&self) -> Aliases<'a> {
+        Aliases::new(self.root_child("aliases").as_deref())
+    }
+
+    /// Finds the node an /aliases entry points at.
+    pub fn find_by_alias(&self, alias: &str) -> Option<Node<'a>> {
+        self.find_by_path(self.resolve_alias(alias)?)
+    }
+
+    /// Returns the /__symbols__ node if it exists.
+    pub fn symbols(&self) -> Option<Symbols<'a>> {
+        self.root_child("__symbols__")
+            .map(|node| Symbols::new(node.deref().clone()))
+    }
+
+    /// Finds the node carrying a source label, through /__symbols__.
+    ///
+    /// Only blobs compiled with `dtc -@` keep their labels.
+    pub fn find_by_label(&self, label: &str) -> Option<Node<'a>> {
+        self.find_by_path(self.symbols()?.get(label)?)
+    }
+
+    /// Finds the node behind the `serialN` alias.
+    pub fn serial_by_index(&self, index: u32) -> Option<Node<'a>> {
+        self.find_by_alias_index("serial", index)
+    }
+
+    /// Finds the node behind the `<stem>N` alias, e.g. `ethernet1` or
+    /// `i2c3`.
+    pub fn find_by_alias_index(&self, stem: &str, index: u32) -> Option<Node<'a>> {
+        self.find_by_path(self.aliases().get_indexed(stem, index)?)
+    }
+
+    /// Returns the /cpus node if it exists.
+    pub fn cpus(&self) -> Option<CpusView<'a>> {
+        let node = self.find_by_path("/cpus")?;
+        (node.level() == 1).then(|| CpusView::new(node.deref().clone()))
+    }
+
+    /// Finds the node whose `phandle` property equals `phandle`.
+    pub fn find_by_phandle(&self, phandle: Phandle) -> Option<Node<'a>> {
+        self.all_nodes()
+            .find(|node| node.phandle() == Some(phandle))
+    }
+
     /// Returns an iterator over all memory nodes.
     pub fn memory(&self) -> impl Iterator<Item = Memory<'a>> + 'a {
         self.all_nodes().filter_map(|node| {
@@ -402,6 +506,10 @@ impl<'a> Fdt<'a> {
     }
 
     /// Returns an iterator over nodes in the /reserved-memory region.
+    ///
+    /// Descendants of the regions are included; use
+    /// [`Fdt::reserved_memory_regions`] for typed access to the regions
+    /// themselves.
     pub fn reserved_memory(&self) -> impl Iterator<Item = Node<'a>> + 'a {
         ReservedMemoryIter {
             node_iter: self.all_nodes(),
@@ -409,6 +517,13 @@ impl<'a> Fdt<'a> {
             reserved_level: 0,
         }
     }
+
+    /// Returns an iterator over the regions described by the direct
+    /// children of /reserved-memory.
+    pub fn reserved_memory_regions(&self) -> impl Iterator<Item = ReservedMemoryRegion<'a>> + 'a {
+        self.find_children_by_path("/reserved-memory")
+            .map(|node| ReservedMemoryRegion::new(node.deref().clone()))
+    }
 }
 
 /// Iterator over nodes in the /reserved-memory region.
@@ -0,0 +1,570 @@
+//! In-place editing of a device tree blob.
+//!
+//! [`FdtMut`] patches a blob inside the buffer that holds it, in the way
+//! libfdt's read-write functions do, for early boot code that cannot
+//! afford to build an editable tree and encode it again.
+//!
+//! Edits that keep or reduce a property's size work on any valid blob:
+//! the freed words become `FDT_NOP` tokens, which parsers skip. Edits
+//! that need more room shift the blocks behind the edit into the free
+//! space after the strings block, which is the padding inside
+//! `totalsize` plus the part of the buffer past `totalsize`.
+
+use crate::{
+    Fdt, FdtError, Header, Token, ValidationLevel,
+    data::{Bytes, Reader, U32_SIZE},
+    validate,
+};
+
+/// Offset of the `totalsize` header field.
+const HEADER_TOTALSIZE: usize = 4;
+/// Offset of the `off_dt_strings` header field.
+const HEADER_OFF_DT_STRINGS: usize = 12;
+/// Offset of the `size_dt_strings` header field.
+const HEADER_SIZE_DT_STRINGS: usize = 32;
+/// Offset of the `size_dt_struct` header field.
+const HEADER_SIZE_DT_STRUCT: usize = 36;
+
+/// Size of the `FDT_PROP` token plus its `len` and `nameoff` words.
+const PROP_HEADER_SIZE: usize = 3 * U32_SIZE;
+
+/// A device tree blob that is edited in place.
+///
+/// Nodes are addressed by absolute path or by alias, as in
+/// [`Fdt::find_by_path`]. Each path component must match a node name
+/// exactly, or the part before `@` when the component has no unit
+/// address.
+///
+/// # Example
+///
+/// ```no_run
+/// use fdt_raw::FdtMut;
+///
+/// # fn patch(buffer: &mut [u8]) -> Result<(), fdt_raw::FdtError> {
+/// let mut fdt = FdtMut::from_bytes(buffer)?;
+/// fdt.setprop_str("/chosen", "bootargs", "console=ttyS0,115200")?;
+/// fdt.nop_node("/soc/watchdog")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FdtMut<'a> {
+    data: &'a mut [u8],
+}
+
+impl<'a> FdtMut<'a> {
+    /// Wraps a buffer holding a blob.
+    ///
+    /// The buffer may be larger than the blob's `totalsize`; the extra
+    /// bytes are used when an edit needs to grow the blob.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Fdt::from_bytes_checked`] with
+    /// [`ValidationLevel::Strict`].
+    pub fn from_bytes(data: &'a mut [u8]) -> Result<Self, FdtError> {
+        let header = Header::from_bytes(data)?;
+        if data.len() < header.totalsize as usize {
+            return Err(FdtError::BufferTooSmall {
+                pos: header.totalsize as usize,
+            });
+        }
+        validate::validate(&header, data, ValidationLevel::Strict)?;
+        Ok(Self { data })
+    }
+
+    /// Returns a read-only view of the current blob.
+    pub fn as_fdt(&self) -> Fdt<'_> {
+        Fdt::from_parts(self.header(), self.data)
+    }
+
+    /// Returns the current header.
+    pub fn header(&self) -> Header {
+        Header {
+            magic: self.field(0),
+            totalsize: self.field(1),
+            off_dt_struct: self.field(2),
+            off_dt_strings: self.field(3),
+            off_mem_rsvmap: self.field(4),
+            version: self.field(5),
+            last_comp_version: self.field(6),
+            boot_cpuid_phys: self.field(7),
+            size_dt_strings: self.field(8),
+            size_dt_struct: self.field(9),
+        }
+    }
+
+    /// Returns the number of bytes edits may add to the blob.
+    ///
+    /// Zero when the blocks are not laid out for growth.
+    pub fn free_space(&self) -> usize {
+        match self.used_end() {
+            Ok(end) => self.data.len() - end,
+            Err(_) => 0,
+        }
+    }
+
+    /// Overwrites the value of an existing property without moving any
+    /// other data.
+    ///
+    /// `value` may be shorter than the current value; the words it no
+    /// longer needs are replaced with `FDT_NOP`.
+    ///
+    /// # Errors
+    ///
+    /// `FdtError::NotFound` if the node or property does not exist, and
+    /// `FdtError::NoSpace` if `value` is longer than the current value.
+    pub fn setprop_inplace(
+        &mut self,
+        path: &str,
+        name: &str,
+        value: &[u8],
+    ) -> Result<(), FdtError> {
+        let node = self.node_offset(path)?;
+        let (prop, len) = self.find_prop(node, name)?.ok_or(FdtError::NotFound)?;
+        if value.len() > len {
+            return Err(FdtError::NoSpace {
+                needed: value.len(),
+                available: len,
+            });
+        }
+        self.write_prop(prop, len, value.len(), |data| data.copy_from_slice(value));
+        Ok(())
+    }
+
+    /// Sets a property, adding it after the node's other properties if it
+    /// does not exist.
+    ///
+    /// Values that fit in the current property's padded size are written
+    /// in place; otherwise the blob grows into the free space.
+    ///
+    /// # Errors
+    ///
+    /// `FdtError::NotFound` if the node does not exist, `FdtError::NoSpace`
+    /// if the free space is too small and `FdtError::BadLayout` if the
+    /// blob must grow but its blocks are not in the standard order.
+    pub fn setprop(&mut self, path: &str, name: &str, value: &[u8]) -> Result<(), FdtError> {
+        self.setprop_with(path, name, value.len(), |data| data.copy_from_slice(value))
+    }
+
+    /// Sets a property to a big-endian `u32`.
+    pub fn setprop_u32(&mut self, path: &str, name: &str, value: u32) -> Result<(), FdtError> {
+        self.setprop(path, name, &value.to_be_bytes())
+    }
+
+    /// Sets a property to a big-endian `u64`.
+    pub fn setprop_u64(&mut self, path: &str, name: &str, value: u64) -> Result<(), FdtError> {
+        self.setprop(path, name, &value.to_be_bytes())
+    }
+
+    /// Sets a property to a NUL-terminated string.
+    pub fn setprop_str(&mut self, path: &str, name: &str, value: &str) -> Result<(), FdtError> {
+        self.setprop_with(path, name, value.len() + 1, |data| {
+            let (text, nul) = data.split_at_mut(value.len());
+            text.copy_from_slice(value.as_bytes());
+            nul[0] = 0;
+        })
+    }
+
+    /// Replaces a property with `FDT_NOP` tokens, removing it without
+    /// moving other data.
+    ///
+    /// # Errors
+    ///
+    /// `FdtError::NotFound` if the node or property does not exist.
+    pub fn nop_property(&mut self, path: &str, name: &str) -> Result<(), FdtError> {
+        let node = self.node_offset(path)?;
+        let (prop, len) = self.find_prop(node, name)?.ok_or(FdtError::NotFound)?;
+        self.fill_nop(prop, prop + PROP_HEADER_SIZE + align4(len));
+        Ok(())
+    }
+
+    /// Replaces a node and its subtree with `FDT_NOP` tokens, removing it
+    /// without moving other data.
+    ///
+    /// # Errors
+    ///
+    /// `FdtError::NotFound` if the node does not exist and
+    /// `FdtError::InvalidInput` for the root node.
+    pub fn nop_node(&mut self, path: &str) -> Result<(), FdtError> {
+        let node = self.node_offset(path)?;
+        if node == self.root_offset()? {
+            return Err(FdtError::InvalidInput);
+        }
+        let end = self.node_end(node)?;
+        self.fill_nop(node, end);
+        Ok(())
+    }
+
+    /// Adds an empty node called `name` as the last child of `parent`.
+    ///
+    /// # Errors
+    ///
+    /// `FdtError::NotFound` if `parent` does not exist,
+    /// `FdtError::Exists` if it already has a child called `name`,
+    /// `FdtError::InvalidInput` if `name` is empty or contains `/`, and
+    /// the growth errors of [`FdtMut::setprop`].
+    pub fn add_subnode(&mut self, parent: &str, name: &str) -> Result<(), FdtError> {
+        if name.is_empty() || name.contains('/') {
+            return Err(FdtError::InvalidInput);
+        }
+        let parent = self.node_offset(parent)?;
+        if self.child_offset(parent, name, true)?.is_some() {
+            return Err(FdtError::Exists);
+        }
+
+        // Insert before the parent's FDT_END_NODE
+        let at = self.node_end(parent)? - U32_SIZE;
+        let name_size = align4(name.len() + 1);
+        self.splice(at, 0, 2 * U32_SIZE + name_size)?;
+        self.write_u32(at, Token::BeginNode.into());
+        let name_at = at + U32_SIZE;
+        self.data[name_at..name_at + name.len()].copy_from_slice(name.as_bytes());
+        self.data[name_at + name.len()..name_at + name_size].fill(0);
+        self.write_u32(name_at + name_size, Token::EndNode.into());
+        Ok(())
+    }
+
+    // -----------------------------------------------------------------------
+    // Structure block walking
+    // -----------------------------------------------------------------------
+
+    /// Returns a reader positioned at `offset` of the blob.
+    fn reader_at(&self, offset: usize) -> Result<Reader<'_>, FdtError> {
+        let totalsize = self.header().totalsize as usize;
+        Bytes::new(&self.data[..totalsize])
+            .try_slice(offset..totalsize)
+            .map(|bytes| bytes.reader())
+            .ok_or(FdtError::BufferTooSmall { pos: offset })
+    }
+
+    /// Reads the token at `offset` and returns it with the offset of the
+    /// token after it.
+    fn next_tag(&self, offset: usize) -> Result<(Token, usize), FdtError> {
+        let mut reader = self.reader_at(offset)?;
+        let token = reader.read_token()?;
+        match token {
+            Token::BeginNode => {
+                reader.read_cstr()?;
+                reader.align4();
+            }
+            Token::Prop => {
+                let len = reader.read_u32().ok_or(FdtError::BufferTooSmall {
+                    pos: reader.position(),
+                })?;
+                reader.read_u32().ok_or(FdtError::BufferTooSmall {
+                    pos: reader.position(),
+                })?;
+                reader
+                    .read_bytes(align4(len as usize))
+                    .ok_or(FdtError::BufferTooSmall {
+                        pos: reader.position(),
+                    })?;
+            }
+            Token::Data(_) => return Err(FdtError::InvalidInput),
+            Token::EndNode | Token::Nop | Token::End => {}
+        }
+        Ok((token, reader.position()))
+    }
+
+    /// Returns the offset of the root node's `FDT_BEGIN_NODE`.
+    fn root_offset(&self) -> Result<usize, FdtError> {
+        let mut offset = self.header().off_dt_struct as usize;
+        loop {
+            match self.next_tag(offset)? {
+                (Token::BeginNode, _) => return Ok(offset),
+                (Token::Nop, next) => offset = next,
+                _ => return Err(FdtError::InvalidInput),
+            }
+        }
+    }
+
+    /// Returns the name of the node starting at `offset`.
+    fn node_name(&self, offset: usize) -> Result<&str, FdtError> {
+        self.reader_at(offset + U32_SIZE)?.read_cstr()
+    }
+
+    /// Resolves a path or alias to the offset of its node.
+    fn node_offset(&self, path: &str) -> Result<usize, FdtError> {
+        let fdt = self.as_fdt();
+        let path = if path.starts_with('/') {
+            path
+        } else {
+            fdt.aliases().get(path).ok_or(FdtError::NotFound)?
+        };
+
+        let mut node = self.root_offset()?;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            node = self
+                .child_offset(node, name, false)?
+                .ok_or(FdtError::NotFound)?;
+        }
+        Ok(node)
+    }
+
+    /// Finds the direct child of the node at `parent` called `name`.
+    ///
+    /// Without `exact`, a `name` lacking a unit address also matches
+    /// children with one.
+    fn child_offset(
+        &self,
+        parent: usize,
+        name: &str,
+        exact: bool,
+    ) -> Result<Option<usize>, FdtError> {
+        let (_, mut offset) = self.next_tag(parent)?;
+        let mut depth = 0usize;
+        loop {
+            let (token, next) = self.next_tag(offset)?;
+            match token {
+                Token::BeginNode => {
+                    if depth == 0 {
+                        let child = self.node_name(offset)?;
+                        let base = child.split_once('@').map_or(child, |(base, _)| base);
+                        if child == name || (!exact && !name.contains('@') && base == name) {
+                            return Ok(Some(offset));
+                        }
+                    }
+                    depth += 1;
+                }
+                Token::EndNode => match depth.checked_sub(1) {
+                    Some(d) => depth = d,
+                    None => return Ok(None),
+                },
+                Token::End => return Err(FdtError::InvalidInput),
+                _ => {}
+            }
+            offset = next;
+        }
+    }
+
+    /// Returns the offset just past the `FDT_END_NODE` closing the node at
+    /// `node`.
+    fn node_end(&self, node: usize) -> Result<usize, FdtError> {
+        let (_, mut offset) = self.next_tag(node)?;
+        let mut depth = 0usize;
+        loop {
+            let (token, next) = self.next_tag(offset)?;
+            match token {
+                Token::BeginNode => depth += 1,
+                Token::EndNode => match depth.checked_sub(1) {
+                    Some(d) => depth = d,
+                    None => return Ok(next),
+                },
+                Token::End => return Err(FdtError::InvalidInput),
+                _ => {}
+            }
+            offset = next;
+        }
+    }
+
+    /// Returns the offset of the first token after the properties of the
+    /// node at `node`.
+    fn props_end(&self, node: usize) -> Result<usize, FdtError> {
+        let (_, mut offset) = self.next_tag(node)?;
+        loop {
+            match self.next_tag(offset)? {
+                (Token::Prop | Token::Nop, next) => offset = next,
+                _ => return Ok(offset),
+            }
+        }
+    }
+
+    /// Finds a property of the node at `node`, returning the offset of its
+    /// `FDT_PROP` token and the length of its value.
+    fn find_prop(&self, node: usize, name: &str) -> Result<Option<(usize, usize)>, FdtError> {
+        let header = self.header();
+        let strings = header.off_dt_strings as usize;
+        let strings_end = strings + header.size_dt_strings as usize;
+
+        let (_, mut offset) = self.next_tag(node)?;
+        loop {
+            let (token, next) = self.next_tag(offset)?;
+            match token {
+                Token::Prop => {
+                    let mut reader = self.reader_at(offset + U32_SIZE)?;
+                    let len = reader.read_u32().unwrap_or_default() as usize;
+                    let nameoff = reader.read_u32().unwrap_or_default() as usize;
+                    let prop_name = self
+                        .data
+                        .get(strings + nameoff..strings_end)
+                        .and_then(|s| s.split(|&b| b == 0).next());
+                    if prop_name == Some(name.as_bytes()) {
+                        return Ok(Some((offset, len)));
+                    }
+                }
+                Token::Nop => {}
+                _ => return Ok(None),
+            }
+            offset = next;
+        }
+    }
+
+    /// Finds `name` in the strings block, including as the tail of a
+    /// longer name.
+    fn find_string(&self, name: &str) -> Option<u32> {
+        let header = self.header();
+        let start = header.off_dt_strings as usize;
+        let strings = &self.data[start..start + header.size_dt_strings as usize];
+        let name = name.as_bytes();
+        strings
+            .windows(name.len() + 1)
+            .position(|w| w[name.len()] == 0 && &w[..name.len()] == name)
+            .map(|offset| offset as u32)
+    }
+
+    // -----------------------------------------------------------------------
+    // Writing
+    // -----------------------------------------------------------------------
+
+    /// Reads the header field at word `index`.
+    ///
+    /// The buffer holds at least a validated header, so this cannot fail.
+    fn field(&self, index: usize) -> u32 {
+        let offset = index * U32_SIZE;
+        let mut word = [0; U32_SIZE];
+        word.copy_from_slice(&self.data[offset..offset + U32_SIZE]);
+        u32::from_be_bytes(word)
+    }
+
+    fn write_u32(&mut self, offset: usize, value: u32) {
+        self.data[offset..offset + U32_SIZE].copy_from_slice(&value.to_be_bytes());
+    }
+
+    /// Replaces `[start, end)` with `FDT_NOP` tokens.
+    fn fill_nop(&mut self, start: usize, end: usize) {
+        for offset in (start..end).step_by(U32_SIZE) {
+            self.write_u32(offset, Token::Nop.into());
+        }
+    }
+
+    /// Sets a property to a `len` byte value produced by `fill`.
+    fn setprop_with(
+        &mut self,
+        path: &str,
+        name: &str,
+        len: usize,
+        fill: impl FnOnce(&mut [u8]),
+    ) -> Result<(), FdtError> {
+        let node = self.node_offset(path)?;
+        match self.find_prop(node, name)? {
+            Some((prop, old_len)) => {
+                if align4(len) > align4(old_len) {
+                    self.splice(prop + PROP_HEADER_SIZE, align4(old_len), align4(len))?;
+                    self.write_prop(prop, len, len, fill);
+                } else {
+                    self.write_prop(prop, old_len, len, fill);
+                }
+            }
+            None => {
+                let nameoff = self.find_string(name);
+                let string_size = if nameoff.is_some() { 0 } else { name.len() + 1 };
+                let prop_size = PROP_HEADER_SIZE + align4(len);
+                self.reserve(string_size + prop_size)?;
+
+                let nameoff = match nameoff {
+                    Some(nameoff) => nameoff,
+                    None => self.add_string(name)?,
+                };
+                let prop = self.props_end(node)?;
+                self.splice(prop, 0, prop_size)?;
+                self.write_u32(prop, Token::Prop.into());
+                self.write_u32(prop + 2 * U32_SIZE, nameoff);
+                self.write_prop(prop, len, len, fill);
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a `len` byte value produced by `fill` into the property at
+    /// `prop`, whose value currently spans `align4(old_len)` bytes, and
+    /// updates its length.
+    fn write_prop(
+        &mut self,
+        prop: usize,
+        old_len: usize,
+        len: usize,
+        fill: impl FnOnce(&mut [u8]),
+    ) {
+        let data = prop + PROP_HEADER_SIZE;
+        self.write_u32(prop + U32_SIZE, len as u32);
+        fill(&mut self.data[data..data + len]);
+        let padded = data + align4(len);
+        self.data[data + len..padded].fill(0);
+        self.fill_nop(padded, data + align4(old_len));
+    }
+
+    /// Appends `name` to the strings block and returns its offset.
+    fn add_string(&mut self, name: &str) -> Result<u32, FdtError> {
+        let header = self.header();
+        let offset = header.size_dt_strings as usize;
+        let at = header.off_dt_strings as usize + offset;
+        self.splice(at, 0, name.len() + 1)?;
+        self.data[at..at + name.len()].copy_from_slice(name.as_bytes());
+        self.data[at + name.len()] = 0;
+        self.write_u32(HEADER_SIZE_DT_STRINGS, (offset + name.len() + 1) as u32);
+        Ok(offset as u32)
+    }
+
+    /// Returns the end of the strings block, which must be the last block.
+    fn used_end(&self) -> Result<usize, FdtError> {
+        let header = self.header();
+        let rsvmap = header.off_mem_rsvmap as usize;
+        let structure = header.off_dt_struct as usize;
+        let strings = header.off_dt_strings as usize;
+        let struct_end = if header.version >= 17 {
+            structure + header.size_dt_struct as usize
+        } else {
+            structure
+        };
+        if rsvmap > structure || struct_end > strings {
+            return Err(FdtError::BadLayout);
+        }
+        Ok(strings + header.size_dt_strings as usize)
+    }
+
+    /// Fails with `FdtError::NoSpace` unless `size` more bytes fit.
+    fn reserve(&self, size: usize) -> Result<(), FdtError> {
+        let needed = self.used_end()? + size;
+        if needed > self.data.len() {
+            return Err(FdtError::NoSpace {
+                needed,
+                available: self.data.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Resizes the `old_len` bytes at `at` to `new_len` bytes, moving
+    /// everything behind them, and updates the header.
+    ///
+    /// `totalsize` grows when the data passes it but never shrinks.
+    fn splice(&mut self, at: usize, old_len: usize, new_len: usize) -> Result<(), FdtError> {
+        let end = self.used_end()?;
+        if new_len > old_len {
+            self.reserve(new_len - old_len)?;
+        }
+        let new_end = end - old_len + new_len;
+        self.data.copy_within(at + old_len..end, at + new_len);
+        if new_end < end {
+            self.data[new_end..end].fill(0);
+        }
+
+        let header = self.header();
+        let grow = |value: u32| (value as usize + new_len - old_len) as u32;
+        if at < header.off_dt_strings as usize {
+            self.write_u32(HEADER_OFF_DT_STRINGS, grow(header.off_dt_strings));
+            if header.version >= 17 {
+                self.write_u32(HEADER_SIZE_DT_STRUCT, grow(header.size_dt_struct));
+            }
+        }
+        if new_end > header.totalsize as usize {
+            self.write_u32(HEADER_TOTALSIZE, new_end as u32);
+        }
+        Ok(())
+    }
+}
+
+/// Rounds `len` up to a multiple of 4.
+fn align4(len: usize) -> usize {
+    (len + U32_SIZE - 1) & !(U32_SIZE - 1)
+}
@@ -2,22 +2,34 @@
 //!
 //! This module provides `FdtIter`, which walks through the structure block
 //! of an FDT and yields each node in a depth-first traversal order.
+//!
+//! The iterator keeps one context entry per open ancestor. Without the
+//! `alloc` feature these live in a fixed-capacity stack whose size is the
+//! `DEPTH` const parameter; with `alloc` the stack grows on demand.
 
 use log::error;
 
 use crate::{
     Fdt, FdtError, Node, NodeContext, Token,
     data::{Bytes, Reader},
-    node::{OneNodeIter, OneNodeState},
+    node::{AncestorNames, OneNodeIter, OneNodeState},
 };
 
+/// Default nesting depth supported by [`Fdt::all_nodes`].
+pub const DEFAULT_DEPTH: usize = 16;
+
 /// Iterator over all nodes in a Flattened Device Tree.
 ///
 /// This iterator performs a depth-first traversal of the device tree,
 /// yielding each node as it's encountered. It maintains a context stack
 /// to track the `#address-cells` and `#size-cells` values inherited from
 /// parent nodes.
-pub struct FdtIter<'a> {
+///
+/// `DEPTH` is the number of tree levels the iterator can track, root
+/// included. When a node would be nested deeper, iteration stops and
+/// [`FdtIter::error`] returns [`FdtError::TooDeep`]. With the `alloc`
+/// feature the stack is growable and `DEPTH` is ignored.
+pub struct FdtIter<'a, const DEPTH: usize = DEFAULT_DEPTH> {
     fdt: Fdt<'a>,
     reader: Reader<'a>,
     strings: Bytes<'a>,
@@ -25,33 +37,49 @@ pub struct FdtIter<'a> {
     node_iter: Option<OneNodeIter<'a>>,
     /// Whether iteration has terminated (due to error or end)
     finished: bool,
+    /// The error that terminated iteration, if any
+    error: Option<FdtError>,
     /// Current depth level in the tree
     level: usize,
     /// Context stack, with the top being the current context
-    context_stack: heapless::Vec<NodeContext, 16>,
-    /// Path stack tracking the current path components from root
-    path_stack: heapless::Vec<&'a str, 16>,
+    context_stack: Stack<NodeContext, DEPTH>,
+    /// Names of the open ancestors, root first
+    name_stack: Stack<&'a str, DEPTH>,
 }
 
-impl<'a> FdtIter<'a> {
+impl<'a, const DEPTH: usize> FdtIter<'a, DEPTH> {
     /// Creates a new FDT iterator from an FDT instance.
     ///
     /// Initializes the reader at the start of the structure block and the
     /// strings slice at the strings block. Also initializes the context
     /// stack with default values.
+    ///
+    /// A structure block offset outside the blob yields an empty iterator.
+    /// A strings block reaching past the blob is clamped to the blob's end,
+    /// so names pointing outside it fail to resolve instead of panicking.
     pub fn new(fdt: Fdt<'a>) -> Self {
         let header = fdt.header();
         let struct_offset = header.off_dt_struct as usize;
         let strings_offset = header.off_dt_strings as usize;
         let strings_size = header.size_dt_strings as usize;
+        let len = fdt.data.len();
 
-        let reader = fdt.data.reader_at(struct_offset);
-        let strings = fdt
-            .data
-            .slice(strings_offset..strings_offset + strings_size);
+        let struct_block = fdt.data.try_slice(struct_offset..len);
+        let finished = struct_block.is_none();
+        if finished {
+            error!(
+                "FDT parse error: structure block offset {:#x} is outside the blob",
+                struct_offset
+            );
+        }
+        let reader = struct_block.unwrap_or_else(|| Bytes::new(&[])).reader();
+
+        let strings_start = strings_offset.min(len);
+        let strings_end = strings_offset.saturating_add(strings_size).min(len);
+        let strings = fdt.data.slice(strings_start..strings_end);
 
         // Initialize context stack with default context
-        let mut context_stack = heapless::Vec::new();
+        let mut context_stack = Stack::new();
         let _ = context_stack.push(NodeContext::default());
 
         Self {
@@ -60,23 +88,22 @@ impl<'a> FdtIter<'a> {
             strings,
             node_iter: None,
             level: 0,
-            finished: false,
+            finished,
+            error: None,
             context_stack,
-            path_stack: heapless::Vec::new(),
+            name_stack: Stack::new(),
         }
     }
 
     /// Returns the current context (top of the stack).
     ///
-    /// # Safety
-    ///
-    /// The stack is never empty because a default context is pushed on
-    /// initialization in `FdtIter::new`.
+    /// The stack is initialized with a default context and a child context
+    /// is only pushed together with a level increase, so the default is
+    /// never popped. It is still returned as a fallback rather than
+    /// panicking.
     #[inline]
-    fn current_context(&self) -> &NodeContext {
-        // SAFETY: The stack is initialized with a default context and is never
-        // completely emptied during iteration.
-        self.context_stack.last().unwrap()
+    fn current_context(&self) -> NodeContext {
+        self.context_stack.last().cloned().unwrap_or_default()
     }
 
     /// Handles an error by logging it and terminating iteration.
@@ -85,11 +112,20 @@ impl<'a> FdtIter<'a> {
     /// rather than panicking. This allows partial parsing and graceful degradation.
     fn handle_error(&mut self, err: FdtError) {
         error!("FDT parse error: {}", err);
+        self.error = Some(err);
         self.finished = true;
     }
+
+    /// Returns the error that ended iteration early, if any.
+    ///
+    /// Returns `None` while iteration is in progress and after the
+    /// structure block was walked to its end.
+    pub fn error(&self) -> Option<&FdtError> {
+        self.error.as_ref()
+    }
 }
 
-impl<'a> Iterator for FdtIter<'a> {
+impl<'a, const DEPTH: usize> Iterator for FdtIter<'a, DEPTH> {
     type Item = Node<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -115,7 +151,7 @@ impl<'a> Iterator for FdtIter<'a> {
                             self.level -= 1;
                             // Pop stack to restore parent node context
                             self.context_stack.pop();
-                            self.path_stack.pop();
+                            self.name_stack.pop();
                         }
                         // Continue loop to process next token
                     }
@@ -132,6 +168,7 @@ impl<'a> Iterator for FdtIter<'a> {
             }
 
             // Read next token
+            let offset = self.reader.position();
             match self.reader.read_token() {
                 Ok(Token::BeginNode) => {
                     // Create new node iterator to handle this node
@@ -139,12 +176,13 @@ impl<'a> Iterator for FdtIter<'a> {
                         self.reader.clone(),
                         self.strings.clone(),
                         self.level,
-                        self.current_context().clone(),
+                        self.current_context(),
                         self.fdt.clone(),
+                        AncestorNames::from_slice(self.name_stack.as_slice()),
                     );
 
                     // Read node name
-                    match node_iter.read_node_name(&self.path_stack) {
+                    match node_iter.read_node_name(offset) {
                         Ok(mut node) => {
                             // Process node properties to get address-cells, size-cells
                             match node_iter.process() {
@@ -166,14 +204,21 @@ impl<'a> Iterator for FdtIter<'a> {
                                                     .interrupt_parent
                                                     .or(node.interrupt_parent()),
                                             };
-                                            let _ = self.context_stack.push(child_context);
-
                                             // Has child nodes, update reader position
                                             self.reader = node_iter.reader().clone();
-                                            // Push current node name onto path stack
-                                            if !node.name().is_empty() {
-                                                let _ = self.path_stack.push(node.name());
+
+                                            // If the children would not fit on the stack,
+                                            // yield this node and stop before the stack and
+                                            // the level drift apart.
+                                            if self.context_stack.push(child_context).is_err() {
+                                                self.handle_error(FdtError::TooDeep {
+                                                    max_depth: DEPTH,
+                                                });
+                                                return Some(node.into());
                                             }
+                                            // Both stacks share DEPTH and are pushed and
+                                            // popped together, so this push fits too.
+                                            let _ = self.name_stack.push(node.name());
                                             // Increase level (node has children)
                                             self.level += 1;
                                         }
@@ -210,7 +255,7 @@ impl<'a> Iterator for FdtIter<'a> {
                         self.level -= 1;
                         // Pop stack to restore parent node context
                         self.context_stack.pop();
-                        self.path_stack.pop();
+                        self.name_stack.pop();
                     }
                     continue;
                 }
@@ -238,3 +283,52 @@ impl<'a> Iterator for FdtIter<'a> {
         }
     }
 }
+
+/// Stack of per-level traversal state.
+///
+/// Backed by a `heapless::Vec` with capacity `N`, or by a growable `Vec`
+/// when the `alloc` feature is enabled.
+#[derive(Clone)]
+pub(crate) struct Stack<T, const N: usize> {
+    #[cfg(feature = "alloc")]
+    items: alloc::vec::Vec<T>,
+    #[cfg(not(feature = "alloc"))]
+    items: heapless::Vec<T, N>,
+}
+
+impl<T, const N: usize> Stack<T, N> {
+    /// Creates an empty stack.
+    pub fn new() -> Self {
+        Self {
+            items: Default::default(),
+        }
+    }
+
+    /// Pushes `item`, handing it back if the stack is full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        #[cfg(feature = "alloc")]
+        {
+            self.items.push(item);
+            Ok(())
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            self.items.push(item)
+        }
+    }
+
+    /// Removes and returns the top item.
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+
+    /// Returns the top item.
+    pub fn last(&self) -> Option<&T> {
+        self.items.last()
+    }
+
+    /// Returns the items, bottom first.
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+}
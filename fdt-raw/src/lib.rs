@@ -40,16 +40,24 @@
 #![no_std]
 #![deny(warnings, missing_docs)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod data;
 mod define;
 mod fdt;
+mod fdt_mut;
 mod header;
 mod iter;
 mod node;
+mod validate;
 
 mod fmt_utils;
 
 pub use define::*;
 pub use fdt::Fdt;
+pub use fdt_mut::FdtMut;
 pub use header::Header;
+pub use iter::{DEFAULT_DEPTH, FdtIter};
 pub use node::*;
+pub use validate::{FDT_FIRST_SUPPORTED_VERSION, FDT_LAST_SUPPORTED_VERSION, ValidationLevel};
@@ -0,0 +1,126 @@
+//! `/aliases` and `/__symbols__` nodes.
+//!
+//! Both nodes map names to absolute node paths through string
+//! properties: `/aliases` holds stable names such as `serial0`, and
+//! `/__symbols__` holds the source labels kept by `dtc -@`.
+
+use core::ops::Deref;
+
+use super::{NodeBase, PropIter};
+
+/// Iterator over `(name, path)` pairs of a path-valued node.
+///
+/// Yields the string properties of `/aliases` (see
+/// [`Fdt::aliases`](crate::Fdt::aliases)) or `/__symbols__` (see
+/// [`Symbols::iter`]). Properties whose value is not a string, such as
+/// a `phandle`, are skipped.
+pub struct Aliases<'a> {
+    props: Option<PropIter<'a>>,
+}
+
+impl<'a> Aliases<'a> {
+    pub(crate) fn new(node: Option<&NodeBase<'a>>) -> Self {
+        Self {
+            props: node.map(|node| node.properties()),
+        }
+    }
+
+    /// Returns the path of the entry named `name`.
+    pub fn get(mut self, name: &str) -> Option<&'a str> {
+        self.find_map(|(n, path)| (n == name).then_some(path))
+    }
+
+    /// Returns the path of the `<stem><index>` entry, e.g. `serial2`.
+    pub fn get_indexed(mut self, stem: &str, index: u32) -> Option<&'a str> {
+        self.find_map(|(name, path)| (parse_index(name, stem)? == index).then_some(path))
+    }
+
+    /// Returns the index of the `<stem>N` entry pointing at `path`.
+    ///
+    /// This is the reverse of [`Aliases::get_indexed`], e.g. which
+    /// `serialN` a UART at `path` is.
+    pub fn index_of(mut self, stem: &str, path: &str) -> Option<u32> {
+        self.find_map(|(name, p)| {
+            if p == path {
+                parse_index(name, stem)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<'a> Iterator for Aliases<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let props = self.props.as_mut()?;
+        props.find_map(|prop| {
+            let path = prop.as_str()?;
+            path.starts_with('/').then_some((prop.name(), path))
+        })
+    }
+}
+
+/// The `/__symbols__` node, mapping labels to node paths.
+///
+/// Present in blobs compiled with `dtc -@`; overlays resolve their
+/// external references through it.
+#[derive(Clone)]
+pub struct Symbols<'a> {
+    node: NodeBase<'a>,
+}
+
+impl<'a> Symbols<'a> {
+    /// Creates a new Symbols wrapper from a NodeBase.
+    pub(crate) fn new(node: NodeBase<'a>) -> Self {
+        Self { node }
+    }
+
+    /// Returns the path of the node carrying `label`.
+    pub fn get(&self, label: &str) -> Option<&'a str> {
+        self.iter().get(label)
+    }
+
+    /// Returns an iterator over `(label, path)` pairs.
+    pub fn iter(&self) -> Aliases<'a> {
+        Aliases::new(Some(&self.node))
+    }
+}
+
+impl<'a> Deref for Symbols<'a> {
+    type Target = NodeBase<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl core::fmt::Debug for Symbols<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Parses `name` as `<stem><decimal index>`.
+fn parse_index(name: &str, stem: &str) -> Option<u32> {
+    let digits = name.strip_prefix(stem)?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_index;
+
+    #[test]
+    fn parse_index_requires_digits_after_stem() {
+        assert_eq!(parse_index("serial0", "serial"), Some(0));
+        assert_eq!(parse_index("serial12", "serial"), Some(12));
+        assert_eq!(parse_index("serial", "serial"), None);
+        assert_eq!(parse_index("serial+1", "serial"), None);
+        assert_eq!(parse_index("i2c1", "serial"), None);
+    }
+}
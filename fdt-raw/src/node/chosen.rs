@@ -3,9 +3,9 @@
 //! This module provides the `Chosen` type which represents the /chosen node
 //! in the device tree, containing boot parameters and system configuration.
 
-use core::ops::Deref;
+use core::ops::{Deref, Range};
 
-use super::{Node, NodeBase};
+use super::{MemoryRegion, Node, NodeBase, RegIter};
 
 /// The /chosen node containing boot parameters.
 ///
@@ -49,6 +49,15 @@ impl<'a> Chosen<'a> {
         self.node._fdt.find_by_path(path)
     }
 
+    /// Returns the serial options appended to stdout-path, such as
+    /// `115200n8`.
+    ///
+    /// Returns `None` if the path has no options or they cannot be parsed.
+    pub fn stdout_options(&self) -> Option<StdoutOptions> {
+        let (_, options) = self.stdout_path()?.split_once(':')?;
+        StdoutOptions::parse(options)
+    }
+
     /// Returns the stdin-path property value.
     ///
     /// This property specifies the path to the device to be used for
@@ -56,6 +65,160 @@ impl<'a> Chosen<'a> {
     pub fn stdin_path(&self) -> Option<&'a str> {
         self.node.find_property_str("stdin-path")
     }
+
+    /// Returns the physical start address of the initrd.
+    ///
+    /// `linux,initrd-start` may be written with one or two cells.
+    pub fn initrd_start(&self) -> Option<u64> {
+        self.u32_or_u64("linux,initrd-start")
+    }
+
+    /// Returns the physical end address (exclusive) of the initrd.
+    pub fn initrd_end(&self) -> Option<u64> {
+        self.u32_or_u64("linux,initrd-end")
+    }
+
+    /// Returns the physical address range of the initrd.
+    pub fn initrd(&self) -> Option<Range<u64>> {
+        Some(self.initrd_start()?..self.initrd_end()?)
+    }
+
+    /// Returns the 64-bit `kaslr-seed`.
+    pub fn kaslr_seed(&self) -> Option<u64> {
+        self.node.find_property("kaslr-seed")?.as_u64()
+    }
+
+    /// Returns the raw bytes of `rng-seed`.
+    pub fn rng_seed(&self) -> Option<&'a [u8]> {
+        Some(self.node.find_property("rng-seed")?.data().as_slice())
+    }
+
+    /// Returns the ELF core header of the crashed kernel, from
+    /// `linux,elfcorehdr`.
+    ///
+    /// The address and size use the root `#address-cells` and
+    /// `#size-cells`.
+    pub fn elfcorehdr(&self) -> Option<MemoryRegion> {
+        self.memory_ranges("linux,elfcorehdr").next()
+    }
+
+    /// Returns the memory a crash dump kernel may use, from
+    /// `linux,usable-memory-range`.
+    ///
+    /// The first entry is the main range; further entries describe extra
+    /// ranges such as low memory for DMA.
+    pub fn usable_memory_range(&self) -> impl Iterator<Item = MemoryRegion> + 'a {
+        self.memory_ranges("linux,usable-memory-range")
+    }
+
+    /// Returns true if `linux,booted-from-kexec` is present.
+    pub fn booted_from_kexec(&self) -> bool {
+        self.node.find_property("linux,booted-from-kexec").is_some()
+    }
+
+    /// Returns the FIT configuration U-Boot booted, from `u-boot,bootconf`.
+    pub fn bootconf(&self) -> Option<&'a str> {
+        self.node.find_property_str("u-boot,bootconf")
+    }
+
+    /// Reads a property encoded as either one or two cells.
+    fn u32_or_u64(&self, name: &str) -> Option<u64> {
+        let prop = self.node.find_property(name)?;
+        prop.as_u32().map(u64::from).or_else(|| prop.as_u64())
+    }
+
+    /// Reads `(address, size)` pairs sized by the root node's cells.
+    fn memory_ranges(&self, name: &str) -> impl Iterator<Item = MemoryRegion> + 'a {
+        let prop = self.node.find_property(name);
+        let context = &self.node.context;
+        let (address_cells, size_cells) = (context.address_cells, context.size_cells);
+        prop.into_iter()
+            .flat_map(move |prop| RegIter::new(prop.data().reader(), address_cells, size_cells))
+            .map(|reg| MemoryRegion {
+                address: reg.address,
+                size: reg.size.unwrap_or(0),
+            })
+    }
+}
+
+/// Parity setting of a serial console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Parity {
+    /// No parity bit (`n`)
+    #[default]
+    None,
+    /// Odd parity (`o`)
+    Odd,
+    /// Even parity (`e`)
+    Even,
+}
+
+/// Serial console options appended to `stdout-path`.
+///
+/// Uses the Linux console syntax `<baud>[<parity>[<bits>[<flow>]]]`, e.g.
+/// `115200n8`. Omitted fields default to no parity, 8 data bits and no
+/// flow control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StdoutOptions {
+    /// Baud rate
+    pub baud: u32,
+    /// Parity
+    pub parity: Parity,
+    /// Data bits per character
+    pub bits: u8,
+    /// RTS/CTS flow control (`r`)
+    pub flow_control: bool,
+}
+
+impl StdoutOptions {
+    /// Parses an option string such as `115200n8` or `9600e7r`.
+    pub fn parse(options: &str) -> Option<Self> {
+        let digits = options
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(options.len());
+        let (baud, rest) = options.split_at(digits);
+        let mut result = Self {
+            baud: baud.parse().ok()?,
+            parity: Parity::None,
+            bits: 8,
+            flow_control: false,
+        };
+
+        let mut rest = rest.chars();
+        match rest.next() {
+            None => return Some(result),
+            Some('n') => {}
+            Some('o') => result.parity = Parity::Odd,
+            Some('e') => result.parity = Parity::Even,
+            Some(_) => return None,
+        }
+        match rest.next() {
+            None => return Some(result),
+            Some(c @ '5'..='8') => result.bits = c as u8 - b'0',
+            Some(_) => return None,
+        }
+        match rest.next() {
+            None => {}
+            Some('r') => result.flow_control = true,
+            Some(_) => return None,
+        }
+        rest.next().is_none().then_some(result)
+    }
+}
+
+impl core::fmt::Display for StdoutOptions {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let parity = match self.parity {
+            Parity::None => 'n',
+            Parity::Odd => 'o',
+            Parity::Even => 'e',
+        };
+        write!(f, "{}{}{}", self.baud, parity, self.bits)?;
+        if self.flow_control {
+            f.write_str("r")?;
+        }
+        Ok(())
+    }
 }
 
 impl<'a> Deref for Chosen<'a> {
@@ -71,6 +234,7 @@ impl core::fmt::Debug for Chosen<'_> {
         f.debug_struct("Chosen")
             .field("bootargs", &self.bootargs())
             .field("stdout_path", &self.stdout_path())
+            .field("initrd", &self.initrd())
             .finish()
     }
 }
@@ -81,7 +245,7 @@ fn split_path_options(path: &str) -> &str {
 
 #[cfg(test)]
 mod tests {
-    use super::split_path_options;
+    use super::{Parity, StdoutOptions, split_path_options};
 
     #[test]
     fn split_path_options_keeps_plain_path() {
@@ -97,4 +261,28 @@ mod tests {
         );
         assert_eq!(split_path_options("serial0:115200n8"), "serial0");
     }
+
+    #[test]
+    fn stdout_options_parse() {
+        let options = StdoutOptions::parse("115200n8").unwrap();
+        assert_eq!(options.baud, 115200);
+        assert_eq!(options.parity, Parity::None);
+        assert_eq!(options.bits, 8);
+        assert!(!options.flow_control);
+
+        let options = StdoutOptions::parse("9600e7r").unwrap();
+        assert_eq!(options.parity, Parity::Even);
+        assert_eq!(options.bits, 7);
+        assert!(options.flow_control);
+
+        let options = StdoutOptions::parse("1500000").unwrap();
+        assert_eq!((options.baud, options.bits), (1500000, 8));
+    }
+
+    #[test]
+    fn stdout_options_rejects_garbage() {
+        for bad in ["", "n8", "115200x8", "115200n9", "115200n8rx"] {
+            assert_eq!(StdoutOptions::parse(bad), None, "{bad}");
+        }
+    }
 }
@@ -0,0 +1,366 @@
+//! CPU topology nodes.
+//!
+//! This module provides [`CpusView`] for the `/cpus` node and [`Cpu`] for
+//! its `cpu@*` children, decoding the properties SMP bring-up code needs:
+//! hardware IDs, enable methods, cache hierarchy and the `cpu-map`
+//! topology description.
+
+use core::ops::Deref;
+
+use super::{Node, NodeBase};
+use crate::{Phandle, Property, iter::FdtIter};
+
+/// Maximum number of `next-level-cache` hops followed by [`Cpu::caches`].
+///
+/// Guards against phandle loops in malformed trees.
+pub const MAX_CACHE_LEVELS: usize = 8;
+
+/// Maximum nesting depth of `cpu-map` below the `cpu-map` node itself.
+const MAX_CPU_MAP_DEPTH: usize = 8;
+
+/// The `/cpus` node.
+///
+/// Dereferences to [`NodeBase`] for raw property access.
+#[derive(Clone)]
+pub struct CpusView<'a> {
+    node: NodeBase<'a>,
+}
+
+impl<'a> CpusView<'a> {
+    /// Creates a new CpusView wrapper from a NodeBase.
+    pub(crate) fn new(node: NodeBase<'a>) -> Self {
+        Self { node }
+    }
+
+    /// Returns an iterator over the CPU nodes (`device_type = "cpu"`).
+    pub fn cpus(&self) -> impl Iterator<Item = Cpu<'a>> + 'a {
+        let cpus = self.node.clone();
+        self.children().filter_map(move |node| {
+            if node.find_property_str("device_type") == Some("cpu") {
+                Some(Cpu {
+                    node: node.deref().clone(),
+                    cpus: cpus.clone(),
+                })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Finds the CPU node with the given phandle.
+    pub fn cpu_by_phandle(&self, phandle: Phandle) -> Option<Cpu<'a>> {
+        self.cpus().find(|cpu| cpu.phandle() == Some(phandle))
+    }
+
+    /// Finds the CPU node whose `reg` contains the given hardware ID.
+    pub fn cpu_by_hwid(&self, hwid: u64) -> Option<Cpu<'a>> {
+        self.cpus().find(|cpu| cpu.hwids().any(|id| id == hwid))
+    }
+
+    /// Returns the parsed `cpu-map` topology, if the node has one.
+    pub fn cpu_map(&self) -> Option<CpuMapIter<'a>> {
+        let mut iter = self.node._fdt.all_nodes();
+        iter.by_ref().find(|node| node.offset == self.node.offset)?;
+        let map_level = self.node.level + 1;
+        for node in iter.by_ref() {
+            if node.level() < map_level {
+                return None;
+            }
+            if node.level() == map_level && node.name() == "cpu-map" {
+                return Some(CpuMapIter {
+                    iter,
+                    map_level,
+                    stack: [None; MAX_CPU_MAP_DEPTH],
+                    done: false,
+                });
+            }
+        }
+        None
+    }
+
+    /// Returns an iterator over the direct children of `/cpus`.
+    fn children(&self) -> impl Iterator<Item = Node<'a>> + 'a {
+        let mut iter = self.node._fdt.all_nodes();
+        let offset = self.node.offset;
+        let child_level = self.node.level + 1;
+        // Leaves `iter` exhausted if `/cpus` is not found again
+        iter.by_ref().find(|node| node.offset == offset);
+        iter.take_while(move |node| node.level() >= child_level)
+            .filter(move |node| node.level() == child_level)
+    }
+}
+
+impl<'a> Deref for CpusView<'a> {
+    type Target = NodeBase<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl core::fmt::Debug for CpusView<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CpusView")
+            .field("address_cells", &self.node.address_cells)
+            .finish()
+    }
+}
+
+/// How a secondary CPU is brought online, from `enable-method`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnableMethod<'a> {
+    /// Started through PSCI `CPU_ON`.
+    Psci,
+    /// Released by writing its entry point to `cpu-release-addr`.
+    SpinTable {
+        /// Address the boot CPU writes the entry point to, if present.
+        cpu_release_addr: Option<u64>,
+    },
+    /// A vendor-specific method, e.g. `"brcm,bcm2836-smp"`.
+    Other(&'a str),
+}
+
+/// A CPU node below `/cpus`.
+///
+/// Dereferences to [`NodeBase`] for raw property access. Properties that
+/// the devicetree specification allows to be shared through `/cpus`
+/// (`enable-method`, `clock-frequency`) fall back to the parent value.
+#[derive(Clone)]
+pub struct Cpu<'a> {
+    node: NodeBase<'a>,
+    cpus: NodeBase<'a>,
+}
+
+impl<'a> Cpu<'a> {
+    /// Returns the hardware ID of the CPU: the first `reg` entry.
+    ///
+    /// The width follows the `#address-cells` of `/cpus`; on arm64 this
+    /// is the MPIDR affinity value.
+    pub fn hwid(&self) -> Option<u64> {
+        self.hwids().next()
+    }
+
+    /// Returns all hardware IDs in `reg`, one per hardware thread.
+    pub fn hwids(&self) -> impl Iterator<Item = u64> + 'a {
+        self.node.reg().into_iter().flatten().map(|reg| reg.address)
+    }
+
+    /// Returns the CPU's `phandle`.
+    pub fn phandle(&self) -> Option<Phandle> {
+        self.node.phandle()
+    }
+
+    /// Returns the enable method, inherited from `/cpus` when absent.
+    ///
+    /// When `enable-method` lists several strings the first recognized
+    /// one wins; otherwise the first string is returned as
+    /// [`EnableMethod::Other`].
+    pub fn enable_method(&self) -> Option<EnableMethod<'a>> {
+        let prop = self
+            .node
+            .find_property("enable-method")
+            .or_else(|| self.cpus.find_property("enable-method"))?;
+        let mut fallback = None;
+        for method in prop.as_str_iter() {
+            match method {
+                "psci" => return Some(EnableMethod::Psci),
+                "spin-table" => {
+                    return Some(EnableMethod::SpinTable {
+                        cpu_release_addr: self.cpu_release_addr(),
+                    });
+                }
+                other => {
+                    fallback.get_or_insert(EnableMethod::Other(other));
+                }
+            }
+        }
+        fallback
+    }
+
+    /// Returns the `cpu-release-addr` used by the spin-table method.
+    pub fn cpu_release_addr(&self) -> Option<u64> {
+        self.node
+            .find_property("cpu-release-addr")
+            .and_then(|prop| read_u32_or_u64(&prop))
+    }
+
+    /// Returns the `clock-frequency` in Hz, inherited from `/cpus`.
+    ///
+    /// Both the one-cell and two-cell encodings are accepted.
+    pub fn clock_frequency(&self) -> Option<u64> {
+        self.node
+            .find_property("clock-frequency")
+            .or_else(|| self.cpus.find_property("clock-frequency"))
+            .and_then(|prop| read_u32_or_u64(&prop))
+    }
+
+    /// Returns the `next-level-cache` phandle.
+    pub fn next_level_cache(&self) -> Option<Phandle> {
+        phandle_prop(&self.node, "next-level-cache")
+    }
+
+    /// Returns an iterator over the cache nodes reachable through
+    /// `next-level-cache`, starting with the CPU's own next level.
+    ///
+    /// At most [`MAX_CACHE_LEVELS`] nodes are yielded.
+    pub fn caches(&self) -> CacheIter<'a> {
+        CacheIter {
+            node: self.node.clone(),
+            next: self.next_level_cache(),
+            remaining: MAX_CACHE_LEVELS,
+        }
+    }
+
+    /// Returns the `operating-points-v2` table phandle.
+    pub fn operating_points_v2(&self) -> Option<Phandle> {
+        phandle_prop(&self.node, "operating-points-v2")
+    }
+}
+
+impl<'a> Deref for Cpu<'a> {
+    type Target = NodeBase<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl core::fmt::Debug for Cpu<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Cpu")
+            .field("name", &self.node.name())
+            .field("hwid", &self.hwid())
+            .field("enable_method", &self.enable_method())
+            .finish()
+    }
+}
+
+/// Iterator over the cache hierarchy of a CPU.
+///
+/// Created by [`Cpu::caches`].
+pub struct CacheIter<'a> {
+    node: NodeBase<'a>,
+    next: Option<Phandle>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for CacheIter<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let phandle = self.next.take()?;
+        self.remaining = self.remaining.checked_sub(1)?;
+        let cache = self.node._fdt.find_by_phandle(phandle)?;
+        self.next = phandle_prop(&cache, "next-level-cache");
+        Some(cache)
+    }
+}
+
+/// One level of a `cpu-map` path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MapLevel {
+    Socket(u32),
+    Cluster(u32),
+    Core(u32),
+    Thread(u32),
+}
+
+impl MapLevel {
+    fn parse(name: &str) -> Option<Self> {
+        let split = name.find(|c: char| c.is_ascii_digit())?;
+        let (kind, index) = name.split_at(split);
+        let index = index.parse().ok()?;
+        match kind {
+            "socket" => Some(Self::Socket(index)),
+            "cluster" => Some(Self::Cluster(index)),
+            "core" => Some(Self::Core(index)),
+            "thread" => Some(Self::Thread(index)),
+            _ => None,
+        }
+    }
+}
+
+/// A leaf of the `cpu-map` topology, mapping a position to a CPU node.
+///
+/// Nested clusters report the innermost cluster index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuMapEntry {
+    /// Index of the `socketN` node, if the map describes sockets.
+    pub socket: Option<u32>,
+    /// Index of the enclosing `clusterN` node.
+    pub cluster: Option<u32>,
+    /// Index of the `coreN` node.
+    pub core: u32,
+    /// Index of the `threadN` node, for SMT cores.
+    pub thread: Option<u32>,
+    /// Phandle of the CPU node, from the leaf's `cpu` property.
+    pub cpu: Phandle,
+}
+
+/// Iterator over the leaves of `/cpus/cpu-map`.
+///
+/// Created by [`CpusView::cpu_map`]. Leaves without a `coreN` ancestor
+/// and nodes nested deeper than the supported depth are skipped.
+pub struct CpuMapIter<'a> {
+    iter: FdtIter<'a>,
+    map_level: usize,
+    stack: [Option<MapLevel>; MAX_CPU_MAP_DEPTH],
+    done: bool,
+}
+
+impl Iterator for CpuMapIter<'_> {
+    type Item = CpuMapEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        for node in self.iter.by_ref() {
+            if node.level() <= self.map_level {
+                self.done = true;
+                return None;
+            }
+            let depth = node.level() - self.map_level - 1;
+            let Some(slot) = self.stack.get_mut(depth) else {
+                continue;
+            };
+            *slot = MapLevel::parse(node.name());
+
+            let Some(cpu) = phandle_prop(&node, "cpu") else {
+                continue;
+            };
+            let (mut socket, mut cluster, mut core, mut thread) = (None, None, None, None);
+            for level in self.stack[..=depth].iter().flatten() {
+                match *level {
+                    MapLevel::Socket(i) => socket = Some(i),
+                    MapLevel::Cluster(i) => cluster = Some(i),
+                    MapLevel::Core(i) => core = Some(i),
+                    MapLevel::Thread(i) => thread = Some(i),
+                }
+            }
+            if let Some(core) = core {
+                return Some(CpuMapEntry {
+                    socket,
+                    cluster,
+                    core,
+                    thread,
+                    cpu,
+                });
+            }
+        }
+        self.done = true;
+        None
+    }
+}
+
+/// Reads a single phandle-valued property.
+fn phandle_prop(node: &NodeBase<'_>, name: &str) -> Option<Phandle> {
+    node.find_property(name)
+        .and_then(|prop| prop.as_u32())
+        .map(Phandle::from)
+}
+
+/// Reads a property encoded as either one or two cells.
+fn read_u32_or_u64(prop: &Property<'_>) -> Option<u64> {
+    prop.as_u32().map(u64::from).or_else(|| prop.as_u64())
+}
@@ -11,7 +11,7 @@ use super::NodeBase;
 ///
 /// Represents a contiguous region of physical memory with its base address
 /// and size.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MemoryRegion {
     /// Base address of the memory region
     pub address: u64,
@@ -63,8 +63,11 @@ impl<'a> Memory<'a> {
     }
 
     /// Returns the total memory size across all regions.
+    ///
+    /// Saturates at `u64::MAX` instead of overflowing.
     pub fn total_size(&self) -> u64 {
-        self.regions().map(|r| r.size).sum()
+        self.regions()
+            .fold(0u64, |total, r| total.saturating_add(r.size))
     }
 }
 
@@ -12,17 +12,70 @@ use core::{ffi::CStr, fmt::Debug};
 use crate::Fdt;
 use crate::fmt_utils;
 use crate::{
-    FdtError, Phandle, Token,
+    FdtError, Phandle, Token, TranslateError,
     data::{Bytes, Reader, U32_SIZE},
 };
 
+mod aliases;
 mod chosen;
+mod cpus;
 mod memory;
 mod prop;
+mod reserved_memory;
 
-pub use chosen::Chosen;
+pub use aliases::{Aliases, Symbols};
+pub use chosen::{Chosen, Parity, StdoutOptions};
+pub use cpus::{CacheIter, Cpu, CpuMapEntry, CpuMapIter, CpusView, EnableMethod, MAX_CACHE_LEVELS};
 pub use memory::{Memory, MemoryRegion};
-pub use prop::{PropIter, Property, RangeInfo, RegInfo, RegIter, VecRange};
+pub use prop::{PropIter, Property, RangeInfo, RegInfo, RegIter, VecRange, translate_ranges};
+pub use reserved_memory::ReservedMemoryRegion;
+
+/// Maximum length in bytes of a path returned by [`NodeBase::path`]
+/// without the `alloc` feature.
+pub const MAX_PATH_LEN: usize = 256;
+
+/// Owned node path returned by [`NodeBase::path`].
+///
+/// A growable `String` with the `alloc` feature, otherwise a
+/// `heapless::String` of [`MAX_PATH_LEN`] bytes.
+#[cfg(feature = "alloc")]
+pub type NodePath = alloc::string::String;
+
+/// Owned node path returned by [`NodeBase::path`].
+///
+/// A growable `String` with the `alloc` feature, otherwise a
+/// `heapless::String` of [`MAX_PATH_LEN`] bytes.
+#[cfg(not(feature = "alloc"))]
+pub type NodePath = heapless::String<MAX_PATH_LEN>;
+
+/// Names of a node's ancestors, root first, as recorded by the iterator.
+///
+/// Only the first [`DEFAULT_DEPTH`](crate::DEFAULT_DEPTH) levels are kept
+/// so that nodes stay free of drop glue; deeper ancestors are looked up in
+/// the structure block.
+#[derive(Clone, Copy)]
+pub(crate) struct AncestorNames<'a> {
+    names: [&'a str; crate::DEFAULT_DEPTH],
+    len: usize,
+}
+
+impl<'a> AncestorNames<'a> {
+    /// Keeps the leading names of `names` that fit.
+    pub(crate) fn from_slice(names: &[&'a str]) -> Self {
+        let len = names.len().min(crate::DEFAULT_DEPTH);
+        let mut ancestors = Self {
+            names: [""; crate::DEFAULT_DEPTH],
+            len,
+        };
+        ancestors.names[..len].copy_from_slice(&names[..len]);
+        ancestors
+    }
+
+    /// Returns the name of the ancestor at `depth` (the root is 0).
+    fn get(&self, depth: usize) -> Option<&'a str> {
+        self.names[..self.len].get(depth).copied()
+    }
+}
 
 /// Context inherited from a node's parent.
 ///
@@ -71,8 +124,10 @@ pub struct NodeBase<'a> {
     pub size_cells: u8,
     /// Inherited context (contains parent's cells)
     context: NodeContext,
-    /// Path components from root to this node
-    path_components: heapless::Vec<&'a str, 16>,
+    /// Offset of this node's `FDT_BEGIN_NODE` token in the blob
+    offset: usize,
+    /// Names of the enclosing nodes, root first
+    ancestors: AncestorNames<'a>,
 }
 
 impl<'a> NodeBase<'a> {
@@ -86,6 +141,14 @@ impl<'a> NodeBase<'a> {
         self.level
     }
 
+    /// Returns the offset of this node's `FDT_BEGIN_NODE` token from the
+    /// start of the structure block.
+    ///
+    /// This is the node offset libfdt uses; the root node is at 0.
+    pub fn offset(&self) -> usize {
+        self.offset - self._fdt.header().off_dt_struct as usize
+    }
+
     /// Returns an iterator over this node's properties.
     pub fn properties(&self) -> PropIter<'a> {
         PropIter::new(self.data.reader(), self.strings.clone())
@@ -146,6 +209,20 @@ impl<'a> NodeBase<'a> {
         ))
     }
 
+    /// Translates a child bus address through this node's `ranges`.
+    ///
+    /// See [`translate_ranges`] for the rules.
+    pub(crate) fn translate_child(&self, address: u128, size: u64) -> Result<u128, TranslateError> {
+        translate_ranges(
+            self.find_property("ranges").map(|p| p.data().as_slice()),
+            self.address_cells as usize,
+            self.context.address_cells as usize,
+            self.size_cells as usize,
+            address,
+            size,
+        )
+    }
+
     /// Returns an iterator over compatible strings.
     pub fn compatibles(&self) -> impl Iterator<Item = &'a str> {
         self.find_property("compatible")
@@ -153,6 +230,14 @@ impl<'a> NodeBase<'a> {
             .flat_map(|p| p.as_str_iter())
     }
 
+    /// Returns the node's `phandle` (or legacy `linux,phandle`) value.
+    pub fn phandle(&self) -> Option<Phandle> {
+        self.properties()
+            .find(|p| p.name() == "phandle" || p.name() == "linux,phandle")
+            .and_then(|prop| prop.as_u32())
+            .map(Phandle::from)
+    }
+
     /// Returns the effective `interrupt-parent`, inheriting from ancestors.
     pub fn interrupt_parent(&self) -> Option<Phandle> {
         self.find_property("interrupt-parent")
@@ -164,17 +249,83 @@ impl<'a> NodeBase<'a> {
     ///
     /// For the root node, returns "/". For other nodes, returns the
     /// absolute path like "/soc/serial@0".
-    pub fn path(&self) -> heapless::String<256> {
+    ///
+    /// Without the `alloc` feature the path is truncated to
+    /// [`MAX_PATH_LEN`] bytes; use [`NodeBase::path_with`] to detect that.
+    pub fn path(&self) -> NodePath {
+        let mut result = NodePath::new();
+        let _ = self.write_path(&mut result);
+        result
+    }
+
+    /// Returns the full path of this node in a buffer of `N` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FdtError::PathTooLong` if the path does not fit.
+    pub fn path_with<const N: usize>(&self) -> Result<heapless::String<N>, FdtError> {
         let mut result = heapless::String::new();
-        if self.path_components.is_empty() {
-            let _ = result.push('/');
-            return result;
+        self.write_path(&mut result)
+            .map_err(|_| FdtError::PathTooLong { capacity: N })?;
+        Ok(result)
+    }
+
+    /// Writes the full path of this node to `out`.
+    ///
+    /// Ancestor names come from the iterator that produced this node.
+    /// Any it could not keep are looked up in the structure block, so the
+    /// path length is not bounded by the iterator's depth.
+    pub fn write_path<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        if self.level == 0 {
+            return out.write_char('/');
         }
-        for component in &self.path_components {
-            let _ = result.push('/');
-            let _ = result.push_str(component);
+        for depth in 1..self.level {
+            let name = match self.ancestors.get(depth) {
+                Some(name) => name,
+                None => self.ancestor_name(depth).ok_or(fmt::Error)?,
+            };
+            out.write_char('/')?;
+            out.write_str(name)?;
+        }
+        out.write_char('/')?;
+        out.write_str(self.name)
+    }
+
+    /// Finds the name of this node's ancestor at `depth` (the root is 0).
+    ///
+    /// Walks the structure block up to this node and remembers the most
+    /// recent node opened at `depth`.
+    fn ancestor_name(&self, depth: usize) -> Option<&'a str> {
+        let data = &self._fdt.data;
+        let start = self._fdt.header().off_dt_struct as usize;
+        let mut reader = data.try_slice(start..data.len())?.reader();
+        let mut level = 0usize;
+        let mut found = None;
+        loop {
+            let position = reader.position();
+            match reader.read_token().ok()? {
+                Token::BeginNode => {
+                    if position == self.offset {
+                        return found;
+                    }
+                    let name = reader.read_cstr().ok()?;
+                    reader.align4();
+                    if level == depth {
+                        found = Some(name);
+                    }
+                    level += 1;
+                }
+                Token::EndNode => level = level.checked_sub(1)?,
+                Token::Prop => {
+                    let len = reader.read_u32()?;
+                    reader.read_u32()?;
+                    reader.read_bytes(len as usize)?;
+                    reader.align4();
+                }
+                Token::Nop => {}
+                Token::End | Token::Data(_) => return None,
+            }
         }
-        result
     }
 }
 
@@ -299,6 +450,8 @@ pub(crate) struct OneNodeIter<'a> {
     parsed_props: ParsedProps,
     /// Reference to the containing FDT for path resolution
     fdt: Fdt<'a>,
+    /// Names of the enclosing nodes, root first
+    ancestors: AncestorNames<'a>,
 }
 
 impl<'a> OneNodeIter<'a> {
@@ -309,6 +462,7 @@ impl<'a> OneNodeIter<'a> {
         level: usize,
         context: NodeContext,
         fdt: Fdt<'a>,
+        ancestors: AncestorNames<'a>,
     ) -> Self {
         Self {
             reader,
@@ -318,6 +472,7 @@ impl<'a> OneNodeIter<'a> {
             context,
             parsed_props: ParsedProps::default(),
             fdt,
+            ancestors,
         }
     }
 
@@ -335,25 +490,17 @@ impl<'a> OneNodeIter<'a> {
     ///
     /// Reads the null-terminated node name and aligns to a 4-byte boundary.
     /// Returns a partially-constructed `NodeBase` with default cell values
-    /// that will be updated by `process()`.
-    pub fn read_node_name(
-        &mut self,
-        parent_path: &heapless::Vec<&'a str, 16>,
-    ) -> Result<NodeBase<'a>, FdtError> {
+    /// that will be updated by `process()`. `offset` is the position of the
+    /// node's `FDT_BEGIN_NODE` token.
+    pub fn read_node_name(&mut self, offset: usize) -> Result<NodeBase<'a>, FdtError> {
         // Read null-terminated name string
-        let name = self.read_cstr()?;
+        let name = self.reader.read_cstr()?;
 
         // Align to 4-byte boundary
-        self.align4();
+        self.reader.align4();
 
         let data = self.reader.remain();
 
-        // Build path components: parent path + current node name
-        let mut path_components = parent_path.clone();
-        if !name.is_empty() {
-            let _ = path_components.push(name);
-        }
-
         Ok(NodeBase {
             name,
             data,
@@ -364,46 +511,31 @@ impl<'a> OneNodeIter<'a> {
             size_cells: 1,
             context: self.context.clone(),
             _fdt: self.fdt.clone(),
-            path_components,
+            offset,
+            ancestors: self.ancestors,
         })
     }
 
-    /// Reads a null-terminated string from the current position.
-    fn read_cstr(&mut self) -> Result<&'a str, FdtError> {
-        let bytes = self.reader.remain();
-        let cstr = CStr::from_bytes_until_nul(bytes.as_slice())?;
-        let s = cstr.to_str()?;
-        // Skip string content + null terminator
-        let _ = self.reader.read_bytes(s.len() + 1);
-        Ok(s)
-    }
-
-    /// Aligns the reader to a 4-byte boundary.
-    ///
-    /// FDT structures are 4-byte aligned, so after reading variable-length
-    /// data (like node names), we need to pad to the next 4-byte boundary.
-    fn align4(&mut self) {
-        let pos = self.reader.position();
-        let aligned = (pos + U32_SIZE - 1) & !(U32_SIZE - 1);
-        let skip = aligned - pos;
-        if skip > 0 {
-            let _ = self.reader.read_bytes(skip);
-        }
-    }
-
     /// Reads a property name from the strings block.
     ///
     /// Property names are stored as offsets into the strings block,
     /// not inline with the property data.
     fn read_prop_name(&self, nameoff: u32) -> Result<&'a str, FdtError> {
-        let bytes = self.strings.slice(nameoff as usize..self.strings.len());
+        let bytes = self
+            .strings
+            .try_slice(nameoff as usize..self.strings.len())
+            .ok_or(FdtError::BufferTooSmall {
+                pos: nameoff as usize,
+            })?;
         let cstr = CStr::from_bytes_until_nul(bytes.as_slice())?;
         Ok(cstr.to_str()?)
     }
 
-    /// Reads a u32 value from big-endian bytes at the given offset.
-    fn read_u32_be(data: &[u8], offset: usize) -> u64 {
-        u32::from_be_bytes(data[offset..offset + U32_SIZE].try_into().unwrap()) as u64
+    /// Reads a big-endian u32 value from the start of `data`.
+    ///
+    /// Returns `None` if `data` is shorter than four bytes.
+    fn read_u32_be(data: &[u8]) -> Option<u32> {
+        Some(u32::from_be_bytes(*data.first_chunk()?))
     }
 
     /// Processes node content, parsing properties until child node or end.
@@ -458,22 +590,22 @@ impl<'a> OneNodeIter<'a> {
                         match prop_name {
                             "#address-cells" if len == 4 => {
                                 self.parsed_props.address_cells =
-                                    Some(Self::read_u32_be(&prop_data, 0) as u8);
+                                    Self::read_u32_be(&prop_data).map(|v| v as u8);
                             }
                             "#size-cells" if len == 4 => {
                                 self.parsed_props.size_cells =
-                                    Some(Self::read_u32_be(&prop_data, 0) as u8);
+                                    Self::read_u32_be(&prop_data).map(|v| v as u8);
                             }
                             "interrupt-parent" if len == 4 => {
                                 self.parsed_props.interrupt_parent =
-                                    Some(Phandle::from(Self::read_u32_be(&prop_data, 0) as u32));
+                                    Self::read_u32_be(&prop_data).map(Phandle::from);
                             }
                             _ => {}
                         }
                     }
 
                     // Align to 4-byte boundary
-                    self.align4();
+                    self.reader.align4();
                 }
                 Token::Nop => {
                     // Ignore NOP tokens
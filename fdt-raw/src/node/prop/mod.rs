@@ -290,9 +290,8 @@ impl Property<'_> {
         }
 
         // Try as single u32
-        if self.len() == 4 {
-            let v = u32::from_be_bytes(self.data().as_slice().try_into().unwrap());
-            return write!(f, "{} = <{:#x}>", self.name(), v);
+        if let Ok(v) = <[u8; 4]>::try_from(self.data().as_slice()) {
+            return write!(f, "{} = <{:#x}>", self.name(), u32::from_be_bytes(v));
         }
 
         // Default to raw bytes
@@ -330,11 +329,11 @@ fn format_bytes(f: &mut fmt::Formatter<'_>, data: &[u8]) -> fmt::Result {
         // Format as u32 values
         write!(f, "<")?;
         let mut first = true;
-        for chunk in data.chunks(4) {
+        for chunk in data.chunks_exact(4) {
             if !first {
                 write!(f, " ")?;
             }
-            let v = u32::from_be_bytes(chunk.try_into().unwrap());
+            let v = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
             write!(f, "{:#x}", v)?;
             first = false;
         }
@@ -393,25 +392,15 @@ impl<'a> PropIter<'a> {
 
     /// Reads a property name from the strings block.
     fn read_prop_name(&self, nameoff: u32) -> Result<&'a str, FdtError> {
-        if nameoff as usize >= self.strings.len() {
-            return Err(FdtError::BufferTooSmall {
+        let bytes = self
+            .strings
+            .try_slice(nameoff as usize..self.strings.len())
+            .ok_or(FdtError::BufferTooSmall {
                 pos: nameoff as usize,
-            });
-        }
-        let bytes = self.strings.slice(nameoff as usize..self.strings.len());
+            })?;
         let cstr = CStr::from_bytes_until_nul(bytes.as_slice())?;
         Ok(cstr.to_str()?)
     }
-
-    /// Aligns the reader to a 4-byte boundary.
-    fn align4(&mut self) {
-        let pos = self.reader.position();
-        let aligned = (pos + U32_SIZE - 1) & !(U32_SIZE - 1);
-        let skip = aligned - pos;
-        if skip > 0 {
-            let _ = self.reader.read_bytes(skip);
-        }
-    }
 }
 
 impl<'a> Iterator for PropIter<'a> {
@@ -455,19 +444,16 @@ impl<'a> Iterator for PropIter<'a> {
                         }
                     };
 
-                    // Read property data
-                    let prop_data = if len > 0 {
-                        match self.reader.read_bytes(len as _) {
-                            Some(b) => b,
-                            None => {
-                                self.handle_error(FdtError::BufferTooSmall {
-                                    pos: self.reader.position(),
-                                });
-                                return None;
-                            }
+                    // Read property data; an empty value still points
+                    // into the blob
+                    let prop_data = match self.reader.read_bytes(len as _) {
+                        Some(b) => b,
+                        None => {
+                            self.handle_error(FdtError::BufferTooSmall {
+                                pos: self.reader.position(),
+                            });
+                            return None;
                         }
-                    } else {
-                        Bytes::new(&[])
                     };
 
                     // Read property name
@@ -480,7 +466,7 @@ impl<'a> Iterator for PropIter<'a> {
                     };
 
                     // Align to 4-byte boundary
-                    self.align4();
+                    self.reader.align4();
 
                     return Some(Property::new(name, prop_data));
                 }
@@ -3,7 +3,10 @@
 //! This module provides types for parsing the `ranges` property, which maps
 //! child bus addresses to parent bus addresses for address translation.
 
-use crate::data::{Bytes, Reader};
+use crate::{
+    TranslateError,
+    data::{Bytes, Reader},
+};
 
 /// Ranges property wrapper for parsing address translation entries.
 ///
@@ -70,6 +73,11 @@ impl<'a> Iterator for VecRangeIter<'a> {
     type Item = RangeInfo;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // Zero-width entries would never consume any data
+        if self.address_cells + self.parent_address_cells + self.size_cells == 0 {
+            return None;
+        }
+
         let child_address = self.reader.read_cells(self.address_cells)?;
         let parent_address = self.reader.read_cells(self.parent_address_cells)?;
         let length = self.reader.read_cells(self.size_cells)?;
@@ -81,3 +89,93 @@ impl<'a> Iterator for VecRangeIter<'a> {
         })
     }
 }
+
+/// `phys.hi` bits selecting the PCI address space (configuration, I/O,
+/// 32-bit or 64-bit memory).
+const PCI_SPACE_MASK: u128 = 0x0300_0000 << 64;
+
+const LOW_64: u128 = u64::MAX as u128;
+
+/// Translates the region `[address, address + size)` through one bus's
+/// `ranges` property.
+///
+/// `ranges` is the raw property value, or `None` if the bus has no `ranges`
+/// at all. `child_cells` and `size_cells` are the bus node's own
+/// `#address-cells` and `#size-cells`, `parent_cells` the `#address-cells`
+/// of its parent.
+///
+/// An empty property is an identity mapping, while a missing one means the
+/// children are not memory mapped. The whole region must fit inside one
+/// window. For 3-cell (PCI) child addresses, bits 64..96 of `address` hold
+/// `phys.hi`: a window only matches if its space code is the same, and the
+/// offset is taken from the low 64 bits.
+pub fn translate_ranges(
+    ranges: Option<&[u8]>,
+    child_cells: usize,
+    parent_cells: usize,
+    size_cells: usize,
+    address: u128,
+    size: u64,
+) -> Result<u128, TranslateError> {
+    let data = ranges.ok_or(TranslateError::NoRanges { address })?;
+    if data.is_empty() {
+        // PCI space bits have no meaning on a non-PCI parent bus
+        return Ok(if child_cells == 3 && parent_cells != 3 {
+            address & LOW_64
+        } else {
+            address
+        });
+    }
+    if child_cells + parent_cells + size_cells == 0 {
+        return Err(TranslateError::Unmapped { address });
+    }
+
+    let mut reader = Bytes::new(data).reader();
+    let mut crosses = false;
+    while let (Some(child), Some(parent), Some(length)) = (
+        read_wide(&mut reader, child_cells),
+        read_wide(&mut reader, parent_cells),
+        read_wide(&mut reader, size_cells),
+    ) {
+        let offset = if child_cells == 3 {
+            if child & PCI_SPACE_MASK != address & PCI_SPACE_MASK {
+                continue;
+            }
+            (address as u64).checked_sub(child as u64).map(u128::from)
+        } else {
+            address.checked_sub(child)
+        };
+        let Some(offset) = offset else {
+            continue;
+        };
+        if offset >= length {
+            continue;
+        }
+        if offset + size as u128 > length {
+            crosses = true;
+            continue;
+        }
+
+        return if parent_cells == 3 {
+            let low = (parent as u64)
+                .checked_add(offset as u64)
+                .ok_or(TranslateError::Overflow)?;
+            Ok((parent & !LOW_64) | low as u128)
+        } else {
+            parent.checked_add(offset).ok_or(TranslateError::Overflow)
+        };
+    }
+
+    Err(if crosses {
+        TranslateError::CrossesWindow { address, size }
+    } else {
+        TranslateError::Unmapped { address }
+    })
+}
+
+/// Reads `cells` big-endian cells without dropping the high ones.
+fn read_wide(reader: &mut Reader<'_>, cells: usize) -> Option<u128> {
+    (0..cells).try_fold(0u128, |acc, _| {
+        Some((acc << 32) | reader.read_u32()? as u128)
+    })
+}
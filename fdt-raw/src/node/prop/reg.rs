@@ -93,9 +93,10 @@ impl Iterator for RegIter<'_> {
         if self.size_cells == 0 {
             size = None;
         } else if self.size_cells == 1 {
-            size = self.reader.read_u32().map(|s| s as u64);
+            // A truncated entry ends iteration
+            size = Some(self.reader.read_u32()? as u64);
         } else if self.size_cells == 2 {
-            size = self.reader.read_u64();
+            size = Some(self.reader.read_u64()?);
         } else {
             // Unsupported size_cells value
             return None;
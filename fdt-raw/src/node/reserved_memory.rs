@@ -0,0 +1,129 @@
+//! Reserved memory regions.
+//!
+//! This module provides the `ReservedMemoryRegion` type for the children
+//! of `/reserved-memory`, following the `reserved-memory.yaml` binding.
+//! A region is either static, with a fixed `reg`, or dynamic, with a
+//! `size` (and optionally `alignment` and `alloc-ranges`) that the
+//! firmware or OS places at boot.
+
+use core::ops::Deref;
+
+use super::{MemoryRegion, NodeBase, RegIter};
+use crate::Status;
+
+/// A child node of `/reserved-memory`.
+///
+/// Cell widths of `reg`, `size`, `alignment` and `alloc-ranges` come from
+/// the `#address-cells` and `#size-cells` of `/reserved-memory`.
+#[derive(Clone)]
+pub struct ReservedMemoryRegion<'a> {
+    node: NodeBase<'a>,
+}
+
+impl<'a> ReservedMemoryRegion<'a> {
+    /// Creates a new ReservedMemoryRegion wrapper from a NodeBase.
+    pub(crate) fn new(node: NodeBase<'a>) -> Self {
+        Self { node }
+    }
+
+    /// Returns the statically placed ranges from `reg`.
+    ///
+    /// Empty for dynamic regions.
+    pub fn regions(&self) -> impl Iterator<Item = MemoryRegion> + 'a {
+        self.node
+            .reg()
+            .into_iter()
+            .flatten()
+            .map(|reg| MemoryRegion {
+                address: reg.address,
+                size: reg.size.unwrap_or(0),
+            })
+    }
+
+    /// Returns true if the region has no `reg` and must be allocated
+    /// from its `size`.
+    pub fn is_dynamic(&self) -> bool {
+        self.node.find_property("reg").is_none() && self.size().is_some()
+    }
+
+    /// Returns the `size` of a dynamic region.
+    pub fn size(&self) -> Option<u64> {
+        self.size_cells_prop("size")
+    }
+
+    /// Returns the `alignment` of a dynamic region.
+    pub fn alignment(&self) -> Option<u64> {
+        self.size_cells_prop("alignment")
+    }
+
+    /// Returns the `alloc-ranges` a dynamic region must be placed in.
+    ///
+    /// An empty iterator means the region may go anywhere in memory.
+    pub fn alloc_ranges(&self) -> impl Iterator<Item = MemoryRegion> + 'a {
+        let prop = self.node.find_property("alloc-ranges");
+        let (address_cells, size_cells) = (
+            self.node.context.address_cells,
+            self.node.context.size_cells,
+        );
+        prop.into_iter()
+            .flat_map(move |prop| RegIter::new(prop.data().reader(), address_cells, size_cells))
+            .map(|reg| MemoryRegion {
+                address: reg.address,
+                size: reg.size.unwrap_or(0),
+            })
+    }
+
+    /// Returns true if the OS must not create a mapping for the region.
+    pub fn no_map(&self) -> bool {
+        self.node.find_property("no-map").is_some()
+    }
+
+    /// Returns true if the OS may use the region while its owner does not.
+    pub fn reusable(&self) -> bool {
+        self.node.find_property("reusable").is_some()
+    }
+
+    /// Returns true unless the region has `status = "disabled"`.
+    ///
+    /// Disabled regions are neither reserved nor allocated.
+    pub fn is_available(&self) -> bool {
+        self.node
+            .find_property("status")
+            .and_then(|prop| prop.as_status())
+            != Some(Status::Disabled)
+    }
+
+    /// Reads a property that is `#size-cells` of `/reserved-memory` wide.
+    fn size_cells_prop(&self, name: &str) -> Option<u64> {
+        let prop = self.node.find_property(name)?;
+        let cells = self.node.context.size_cells as usize;
+        if cells == 0 || prop.len() != cells * 4 {
+            return None;
+        }
+        prop.data().reader().read_cells(cells)
+    }
+}
+
+impl<'a> Deref for ReservedMemoryRegion<'a> {
+    type Target = NodeBase<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+impl core::fmt::Debug for ReservedMemoryRegion<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut s = f.debug_struct("ReservedMemoryRegion");
+        s.field("name", &self.node.name());
+        if self.is_dynamic() {
+            s.field("size", &self.size())
+                .field("alignment", &self.alignment());
+        } else {
+            s.field("reg", &self.regions().next());
+        }
+        s.field("no_map", &self.no_map())
+            .field("reusable", &self.reusable())
+            .finish()
+    }
+}
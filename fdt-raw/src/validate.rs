@@ -0,0 +1,168 @@
+//! Structural validation of device tree blobs.
+//!
+//! [`Fdt::from_bytes`](crate::Fdt::from_bytes) only checks the magic number
+//! and that the buffer holds `totalsize` bytes. Blobs from untrusted sources
+//! should be opened with [`Fdt::from_bytes_checked`](crate::Fdt::from_bytes_checked),
+//! which additionally verifies the header layout according to a
+//! [`ValidationLevel`].
+
+use crate::{FdtError, Header, Token, data::MEM_RSV_ENTRY_SIZE};
+
+/// Oldest blob version this crate can parse.
+pub const FDT_FIRST_SUPPORTED_VERSION: u32 = 16;
+/// Newest blob version this crate can parse.
+pub const FDT_LAST_SUPPORTED_VERSION: u32 = 17;
+
+/// How thoroughly a blob is checked before it is used.
+///
+/// Each level includes the checks of the previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ValidationLevel {
+    /// Magic number and `totalsize` against the buffer length only.
+    Basic,
+    /// Header fields: version compatibility, block alignment, blocks lying
+    /// inside `totalsize` and not overlapping each other or the header.
+    Header,
+    /// Everything in [`Header`](ValidationLevel::Header), plus the memory
+    /// reservation block terminator lies in bounds and the structure block
+    /// ends with an `FDT_END` token.
+    #[default]
+    Strict,
+}
+
+/// A block of the blob, as a half-open byte range.
+#[derive(Clone, Copy)]
+struct Block {
+    name: &'static str,
+    start: usize,
+    end: usize,
+}
+
+impl Block {
+    fn new(name: &'static str, offset: u32, size: usize) -> Self {
+        let start = offset as usize;
+        Self {
+            name,
+            start,
+            end: start.saturating_add(size),
+        }
+    }
+
+    fn overlaps(&self, other: &Block) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// Checks `data` against `header` at the given level.
+///
+/// `data` must already be known to hold at least `header.totalsize` bytes.
+pub(crate) fn validate(
+    header: &Header,
+    data: &[u8],
+    level: ValidationLevel,
+) -> Result<(), FdtError> {
+    if level == ValidationLevel::Basic {
+        return Ok(());
+    }
+
+    if header.version < FDT_FIRST_SUPPORTED_VERSION
+        || header.last_comp_version > FDT_LAST_SUPPORTED_VERSION
+        || header.last_comp_version > header.version
+    {
+        return Err(FdtError::UnsupportedVersion {
+            version: header.version,
+            last_comp_version: header.last_comp_version,
+        });
+    }
+
+    if !header.off_mem_rsvmap.is_multiple_of(8) {
+        return Err(FdtError::MisalignedBlock {
+            block: "memory reservation",
+            offset: header.off_mem_rsvmap as usize,
+        });
+    }
+    if !header.off_dt_struct.is_multiple_of(4) {
+        return Err(FdtError::MisalignedBlock {
+            block: "structure",
+            offset: header.off_dt_struct as usize,
+        });
+    }
+
+    let totalsize = header.totalsize as usize;
+    let data = &data[..totalsize.min(data.len())];
+
+    // Version 16 blobs do not record the structure block size; the block is
+    // then only known to start inside the blob.
+    let struct_size = if header.version >= 17 {
+        header.size_dt_struct as usize
+    } else {
+        0
+    };
+
+    let rsvmap_size = if level >= ValidationLevel::Strict {
+        rsvmap_size(header, data)?
+    } else {
+        MEM_RSV_ENTRY_SIZE
+    };
+
+    let blocks = [
+        Block::new("header", 0, size_of::<Header>()),
+        Block::new("memory reservation", header.off_mem_rsvmap, rsvmap_size),
+        Block::new("structure", header.off_dt_struct, struct_size),
+        Block::new(
+            "strings",
+            header.off_dt_strings,
+            header.size_dt_strings as usize,
+        ),
+    ];
+
+    for block in &blocks {
+        if block.end > totalsize || block.start > totalsize {
+            return Err(FdtError::BlockOutOfBounds {
+                block: block.name,
+                offset: block.start,
+                size: block.end - block.start,
+            });
+        }
+    }
+
+    for (i, a) in blocks.iter().enumerate() {
+        for b in &blocks[i + 1..] {
+            if a.start != a.end && b.start != b.end && a.overlaps(b) {
+                return Err(FdtError::BlocksOverlap {
+                    first: a.name,
+                    second: b.name,
+                });
+            }
+        }
+    }
+
+    if level >= ValidationLevel::Strict && header.version >= 17 {
+        let end = blocks[2].end;
+        let last = end
+            .checked_sub(4)
+            .filter(|&start| start >= blocks[2].start)
+            .and_then(|start| data.get(start..end))
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+        if last.map(Token::from) != Some(Token::End) {
+            return Err(FdtError::MissingEndToken);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the size of the memory reservation block, terminator included.
+fn rsvmap_size(header: &Header, data: &[u8]) -> Result<usize, FdtError> {
+    let mut offset = header.off_mem_rsvmap as usize;
+    loop {
+        let entry = offset
+            .checked_add(MEM_RSV_ENTRY_SIZE)
+            .and_then(|end| data.get(offset..end))
+            .ok_or(FdtError::MissingRsvmapTerminator)?;
+        offset += MEM_RSV_ENTRY_SIZE;
+        if entry.iter().all(|&b| b == 0) {
+            return Ok(offset - header.off_mem_rsvmap as usize);
+        }
+    }
+}
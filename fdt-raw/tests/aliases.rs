@@ -0,0 +1,78 @@
+//! `/aliases` and `/__symbols__` lookup tests.
+
+use dtb_file::*;
+use fdt_raw::Fdt;
+
+#[test]
+fn test_aliases_iter() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    let aliases: Vec<_> = fdt.aliases().collect();
+    assert!(aliases.len() > 10);
+    assert_eq!(aliases[0], ("serial0", "/soc/serial@7e215040"));
+    assert!(aliases.iter().all(|(_, path)| path.starts_with('/')));
+    assert_eq!(
+        fdt.aliases().get("ethernet0"),
+        Some("/scb/ethernet@7d580000")
+    );
+    assert_eq!(fdt.aliases().get("missing"), None);
+}
+
+#[test]
+fn test_find_by_alias() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    let node = fdt.find_by_alias("serial1").unwrap();
+    assert_eq!(node.name(), "serial@7e201000");
+    assert_eq!(node.path(), "/soc/serial@7e201000");
+    assert!(fdt.find_by_alias("/soc").is_none());
+}
+
+#[test]
+fn test_serial_by_index() {
+    let raw = fdt_phytium();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    assert_eq!(fdt.serial_by_index(5).unwrap().name(), "uart@2802A000");
+    assert_eq!(fdt.serial_by_index(1).unwrap().name(), "uart@2800d000");
+    assert!(fdt.serial_by_index(99).is_none());
+
+    let eth = fdt.find_by_alias_index("ethernet", 3).unwrap();
+    assert_eq!(eth.path(), "/soc/ethernet@32012000");
+    assert_eq!(
+        fdt.aliases().index_of("serial", "/soc/uart@2800f000"),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_no_aliases() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    assert_eq!(fdt.aliases().count(), 0);
+    assert!(fdt.serial_by_index(0).is_none());
+    assert!(fdt.symbols().is_none());
+    assert!(fdt.find_by_label("uart0").is_none());
+}
+
+#[test]
+fn test_symbols() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let symbols = fdt.symbols().unwrap();
+
+    assert_eq!(
+        symbols.get("cpu_thermal"),
+        Some("/thermal-zones/cpu-thermal")
+    );
+    assert!(symbols.iter().count() > 100);
+
+    let node = fdt.find_by_label("cpu_thermal").unwrap();
+    assert_eq!(node.path(), "/thermal-zones/cpu-thermal");
+    let node = fdt.find_by_label("cma").unwrap();
+    assert_eq!(node.path(), "/reserved-memory/linux,cma");
+    assert!(fdt.find_by_label("no_such_label").is_none());
+}
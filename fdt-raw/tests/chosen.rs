@@ -0,0 +1,52 @@
+//! `/chosen` boot parameter tests.
+
+use dtb_file::*;
+use fdt_raw::{Fdt, Parity};
+
+#[test]
+fn test_chosen_seeds() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let chosen = fdt.chosen().unwrap();
+
+    assert_eq!(chosen.kaslr_seed(), Some(0x1b3e_3a33_2187_3fc3));
+    let seed = chosen.rng_seed().unwrap();
+    assert_eq!(seed.len(), 32);
+    assert_eq!(&seed[..4], &[0xaa, 0x81, 0xe9, 0x60]);
+
+    assert_eq!(chosen.stdout_options(), None);
+    assert_eq!(chosen.initrd(), None);
+    assert_eq!(chosen.elfcorehdr(), None);
+    assert_eq!(chosen.usable_memory_range().count(), 0);
+    assert!(!chosen.booted_from_kexec());
+    assert_eq!(chosen.bootconf(), None);
+}
+
+#[test]
+fn test_chosen_stdout_options() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let chosen = fdt.chosen().unwrap();
+
+    let options = chosen.stdout_options().unwrap();
+    assert_eq!(options.baud, 115200);
+    assert_eq!(options.parity, Parity::None);
+    assert_eq!(options.bits, 8);
+    assert!(!options.flow_control);
+    assert_eq!(options.to_string(), "115200n8");
+
+    // The options do not get in the way of resolving the node
+    assert_eq!(chosen.stdout().unwrap().name(), "serial@7e215040");
+}
+
+#[test]
+fn test_chosen_initrd() {
+    let raw = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let chosen = fdt.chosen().unwrap();
+
+    let start = chosen.initrd_start().unwrap();
+    let end = chosen.initrd_end().unwrap();
+    assert!(start < end);
+    assert_eq!(chosen.initrd(), Some(start..end));
+}
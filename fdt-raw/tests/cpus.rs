@@ -0,0 +1,114 @@
+//! `/cpus` view tests.
+
+use dtb_file::*;
+use fdt_raw::{EnableMethod, Fdt, Phandle};
+
+#[test]
+fn test_rk3568_cpus() {
+    let raw = fdt_3568();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let cpus = fdt.cpus().unwrap();
+    assert_eq!(cpus.address_cells, 2);
+
+    let hwids: Vec<_> = cpus.cpus().map(|cpu| cpu.hwid().unwrap()).collect();
+    assert_eq!(hwids, [0x0, 0x100, 0x200, 0x300]);
+
+    for cpu in cpus.cpus() {
+        assert_eq!(cpu.enable_method(), Some(EnableMethod::Psci));
+        let opp = cpu.operating_points_v2().unwrap();
+        let table = fdt.find_by_phandle(opp).unwrap();
+        assert!(table.compatibles().any(|c| c == "operating-points-v2"));
+    }
+
+    // The idle-states child of /cpus is not a CPU
+    assert_eq!(cpus.cpus().count(), 4);
+    assert!(cpus.cpu_map().is_none());
+    assert_eq!(cpus.cpu_by_hwid(0x200).unwrap().name(), "cpu@200");
+}
+
+#[test]
+fn test_phytium_cpu_map() {
+    let raw = fdt_phytium();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let cpus = fdt.cpus().unwrap();
+
+    let mut topology: Vec<_> = cpus
+        .cpu_map()
+        .unwrap()
+        .map(|entry| {
+            let cpu = cpus.cpu_by_phandle(entry.cpu).unwrap();
+            (entry.cluster.unwrap(), entry.core, cpu.hwid().unwrap())
+        })
+        .collect();
+    topology.sort();
+    assert_eq!(
+        topology,
+        [(0, 0, 0x200), (0, 1, 0x201), (1, 0, 0x0), (2, 0, 0x100)]
+    );
+
+    for entry in cpus.cpu_map().unwrap() {
+        assert_eq!(entry.socket, None);
+        assert_eq!(entry.thread, None);
+    }
+    assert!(
+        cpus.cpus()
+            .all(|cpu| cpu.enable_method() == Some(EnableMethod::Psci))
+    );
+}
+
+#[test]
+fn test_qemu_cpu_map_socket() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let cpus = fdt.cpus().unwrap();
+
+    let entries: Vec<_> = cpus.cpu_map().unwrap().collect();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].socket, Some(0));
+    assert_eq!(entries[0].cluster, Some(0));
+    assert_eq!(entries[0].core, 0);
+
+    let cpu = cpus.cpu_by_phandle(entries[0].cpu).unwrap();
+    assert_eq!(cpu.hwid(), Some(0));
+    assert_eq!(cpu.enable_method(), None);
+}
+
+#[test]
+fn test_rpi_spin_table_and_caches() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let cpus = fdt.cpus().unwrap();
+    assert_eq!(cpus.address_cells, 1);
+
+    let methods: Vec<_> = cpus.cpus().map(|cpu| cpu.enable_method()).collect();
+    assert_eq!(
+        methods,
+        [0xd8, 0xe0, 0xe8, 0xf0].map(|addr| Some(EnableMethod::SpinTable {
+            cpu_release_addr: Some(addr)
+        }))
+    );
+
+    for cpu in cpus.cpus() {
+        let caches: Vec<_> = cpu.caches().map(|cache| cache.path()).collect();
+        assert_eq!(caches, ["/cpus/l2-cache0"]);
+    }
+}
+
+#[test]
+fn test_orangepi_cache_chain() {
+    let raw = fdt_orangepi_5plus();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let cpus = fdt.cpus().unwrap();
+
+    let cpu = cpus.cpu_by_hwid(0x400).unwrap();
+    let caches: Vec<_> = cpu.caches().map(|cache| cache.path()).collect();
+    assert_eq!(caches, ["/cpus/l2-cache-b0", "/cpus/l3-cache"]);
+
+    let clusters: Vec<_> = cpus.cpu_map().unwrap().map(|e| e.cluster).collect();
+    assert_eq!(clusters.iter().filter(|c| **c == Some(0)).count(), 4);
+    assert_eq!(clusters.iter().filter(|c| **c == Some(1)).count(), 2);
+    assert_eq!(clusters.iter().filter(|c| **c == Some(2)).count(), 2);
+
+    let cpu = fdt.find_by_phandle(Phandle::from(6)).unwrap();
+    assert_eq!(cpu.name(), "cpu@0");
+}
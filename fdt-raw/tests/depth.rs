@@ -0,0 +1,131 @@
+//! Traversal depth and node path length limits.
+
+use fdt_raw::{DEFAULT_DEPTH, Fdt, FdtError};
+
+/// Builds a blob holding a single chain of `depth` nested nodes below the
+/// root, each named `name`.
+fn nested(depth: usize, name: &str) -> Vec<u8> {
+    let mut structure = Vec::new();
+    for level in 0..=depth {
+        structure.extend(1u32.to_be_bytes());
+        if level > 0 {
+            structure.extend(name.as_bytes());
+        }
+        structure.push(0);
+        structure.resize(structure.len().next_multiple_of(4), 0);
+    }
+    for _ in 0..=depth {
+        structure.extend(2u32.to_be_bytes());
+    }
+    structure.extend(9u32.to_be_bytes());
+
+    let off_struct = 40 + 16;
+    let totalsize = off_struct + structure.len();
+    let header = [
+        0xd00d_feed,
+        totalsize as u32,
+        off_struct as u32,
+        totalsize as u32,
+        40,
+        17,
+        16,
+        0,
+        0,
+        structure.len() as u32,
+    ];
+    let mut data: Vec<u8> = header.iter().flat_map(|v| v.to_be_bytes()).collect();
+    data.resize(off_struct, 0);
+    data.extend(structure);
+    data
+}
+
+#[cfg(not(feature = "alloc"))]
+#[test]
+fn test_default_depth_reports_too_deep() {
+    let data = nested(40, "n");
+    let fdt = Fdt::from_bytes(&data).unwrap();
+
+    let mut iter = fdt.all_nodes();
+    assert_eq!(iter.by_ref().count(), DEFAULT_DEPTH);
+    assert!(matches!(
+        iter.error(),
+        Some(FdtError::TooDeep {
+            max_depth: DEFAULT_DEPTH
+        })
+    ));
+
+    let mut iter = fdt.all_nodes_with::<8>();
+    assert_eq!(iter.by_ref().count(), 8);
+    assert!(matches!(
+        iter.error(),
+        Some(FdtError::TooDeep { max_depth: 8 })
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_alloc_stack_grows() {
+    let data = nested(40, "n");
+    let fdt = Fdt::from_bytes(&data).unwrap();
+
+    let mut iter = fdt.all_nodes();
+    assert_eq!(iter.by_ref().count(), 41);
+    assert!(iter.error().is_none());
+}
+
+#[test]
+fn test_all_nodes_with_deeper_stack() {
+    let data = nested(40, "n");
+    let fdt = Fdt::from_bytes(&data).unwrap();
+
+    let mut iter = fdt.all_nodes_with::<64>();
+    let nodes: Vec<_> = iter.by_ref().collect();
+    assert_eq!(nodes.len(), 41);
+    assert!(iter.error().is_none());
+
+    let deepest = nodes.last().unwrap();
+    assert_eq!(deepest.level(), 40);
+    assert_eq!(deepest.path().as_str(), "/n".repeat(40));
+}
+
+#[test]
+fn test_exact_depth_fits() {
+    // Root plus 15 levels is exactly the default capacity
+    let data = nested(DEFAULT_DEPTH - 1, "n");
+    let fdt = Fdt::from_bytes(&data).unwrap();
+
+    let mut iter = fdt.all_nodes();
+    assert_eq!(iter.by_ref().count(), DEFAULT_DEPTH);
+    assert!(iter.error().is_none());
+}
+
+#[test]
+fn test_long_paths() {
+    let name = "reserved-memory-region-with-a-long-name@12345678";
+    let data = nested(8, name);
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    let deepest = fdt.all_nodes().last().unwrap();
+    let expected = format!("/{name}").repeat(8);
+    assert!(expected.len() > fdt_raw::MAX_PATH_LEN);
+
+    let path = deepest.path_with::<512>().unwrap();
+    assert_eq!(path.as_str(), expected);
+    assert!(matches!(
+        deepest.path_with::<64>(),
+        Err(FdtError::PathTooLong { capacity: 64 })
+    ));
+
+    #[cfg(feature = "alloc")]
+    assert_eq!(deepest.path(), expected);
+    #[cfg(not(feature = "alloc"))]
+    {
+        let truncated = deepest.path();
+        assert!(truncated.len() <= fdt_raw::MAX_PATH_LEN);
+        assert!(expected.starts_with(truncated.as_str()));
+    }
+
+    // Paths found while walking are accepted by `find_by_path`
+    let shallow = fdt.all_nodes().nth(3).unwrap();
+    let found = fdt.find_by_path(shallow.path().as_str()).unwrap();
+    assert_eq!(found.level(), 3);
+}
@@ -0,0 +1,171 @@
+//! In-place editing tests.
+
+use dtb_file::*;
+use fdt_raw::{Fdt, FdtError, FdtMut, ValidationLevel};
+
+/// Parses `data` with full validation.
+fn checked(data: &[u8]) -> Fdt<'_> {
+    Fdt::from_bytes_checked(data, ValidationLevel::Strict).unwrap()
+}
+
+/// Copies the first `totalsize` bytes of a blob, followed by `slack` bytes
+/// of spare buffer.
+fn tight_copy(raw: &[u8], slack: usize) -> Vec<u8> {
+    let used = Fdt::from_bytes(raw).unwrap().header().totalsize as usize;
+    let mut data = raw[..used].to_vec();
+    data.resize(used + slack, 0);
+    data
+}
+
+#[test]
+fn test_setprop_inplace() {
+    let raw = fdt_rpi_4b();
+    let mut data = raw.to_vec();
+    let mut fdt = FdtMut::from_bytes(&mut data).unwrap();
+
+    let old = fdt.as_fdt().find_by_path("/chosen").unwrap();
+    let old_len = old.find_property("bootargs").unwrap().len();
+    let count = fdt.as_fdt().all_nodes().count();
+
+    fdt.setprop_inplace("/chosen", "bootargs", b"quiet\0")
+        .unwrap();
+    let err = fdt
+        .setprop_inplace("/chosen", "bootargs", &vec![b'x'; old_len + 1])
+        .unwrap_err();
+    assert!(matches!(err, FdtError::NoSpace { .. }));
+    assert!(matches!(
+        fdt.setprop_inplace("/chosen", "missing", b"x"),
+        Err(FdtError::NotFound)
+    ));
+
+    // Nothing moved: the blob keeps its size and layout
+    assert_eq!(data.len(), raw.len());
+    let fdt = checked(&data);
+    assert_eq!(
+        fdt.header().off_dt_strings,
+        Fdt::from_bytes(&raw).unwrap().header().off_dt_strings
+    );
+    let chosen = fdt.chosen().unwrap();
+    assert_eq!(chosen.bootargs(), Some("quiet"));
+    assert!(chosen.find_property("stdout-path").is_some());
+    assert_eq!(fdt.all_nodes().count(), count);
+}
+
+#[test]
+fn test_setprop_inplace_reg() {
+    let raw = fdt_qemu();
+    let mut data = raw.to_vec();
+    let mut fdt = FdtMut::from_bytes(&mut data).unwrap();
+
+    let reg = [0u8, 0, 0, 0, 0x80, 0, 0, 0, 0, 0, 0, 0, 0x10, 0, 0, 0];
+    fdt.setprop_inplace("/memory", "reg", &reg).unwrap();
+
+    let fdt = checked(&data);
+    let memory = fdt.memory().next().unwrap();
+    let region = memory.regions().next().unwrap();
+    assert_eq!(region.address, 0x8000_0000);
+    assert_eq!(region.size, 0x1000_0000);
+}
+
+#[test]
+fn test_nop_property_and_node() {
+    let raw = fdt_qemu();
+    let mut data = raw.to_vec();
+    let mut fdt = FdtMut::from_bytes(&mut data).unwrap();
+    let count = fdt.as_fdt().all_nodes().count();
+
+    fdt.nop_property("/chosen", "kaslr-seed").unwrap();
+    fdt.nop_node("/platform-bus@c000000").unwrap();
+    assert!(matches!(fdt.nop_node("/"), Err(FdtError::InvalidInput)));
+    assert!(matches!(
+        fdt.nop_node("/platform-bus@c000000"),
+        Err(FdtError::NotFound)
+    ));
+
+    let fdt = checked(&data);
+    let chosen = fdt.chosen().unwrap();
+    assert!(chosen.kaslr_seed().is_none());
+    assert!(chosen.rng_seed().is_some());
+    assert!(fdt.find_by_path("/platform-bus@c000000").is_none());
+    assert_eq!(fdt.all_nodes().count(), count - 1);
+}
+
+#[test]
+fn test_grow_into_slack() {
+    let raw = fdt_rpi_4b();
+    let mut data = tight_copy(&raw, 0x1000);
+    let mut fdt = FdtMut::from_bytes(&mut data).unwrap();
+    let header = fdt.header();
+    let free = fdt.free_space();
+    let count = fdt.as_fdt().all_nodes().count();
+
+    let bootargs = "console=ttyS0,115200 root=/dev/mmcblk0p2 rootwait earlycon ".repeat(4);
+    fdt.setprop_str("/chosen", "bootargs", &bootargs).unwrap();
+    fdt.setprop_u64("/chosen", "linux,initrd-start", 0x0280_0000)
+        .unwrap();
+    fdt.add_subnode("/", "test@0").unwrap();
+    fdt.setprop_str("/test@0", "compatible", "vendor,test")
+        .unwrap();
+    fdt.setprop_u32("/test", "a-brand-new-property", 7).unwrap();
+    assert!(matches!(
+        fdt.add_subnode("/", "test@0"),
+        Err(FdtError::Exists)
+    ));
+    assert!(fdt.free_space() < free);
+
+    let new_header = fdt.header();
+    assert!(new_header.totalsize > header.totalsize);
+    assert!(new_header.off_dt_strings > header.off_dt_strings);
+    assert!(new_header.size_dt_strings > header.size_dt_strings);
+
+    let fdt = checked(&data);
+    assert_eq!(fdt.all_nodes().count(), count + 1);
+    let chosen = fdt.chosen().unwrap();
+    assert_eq!(chosen.bootargs(), Some(bootargs.as_str()));
+    assert_eq!(chosen.initrd_start(), Some(0x0280_0000));
+    let node = fdt.find_by_path("/test@0").unwrap();
+    assert_eq!(node.compatibles().next(), Some("vendor,test"));
+    assert_eq!(
+        node.find_property("a-brand-new-property").unwrap().as_u32(),
+        Some(7)
+    );
+    // Untouched nodes survive the shift
+    assert_eq!(
+        fdt.find_by_alias("serial1").unwrap().path(),
+        "/soc/serial@7e201000"
+    );
+}
+
+#[test]
+fn test_grow_within_padding() {
+    // The qemu blob carries its free space inside `totalsize`
+    let raw = fdt_qemu();
+    let mut data = raw.to_vec();
+    let mut fdt = FdtMut::from_bytes(&mut data).unwrap();
+    let totalsize = fdt.header().totalsize;
+
+    fdt.setprop_str("/chosen", "bootargs", "earlycon").unwrap();
+    assert_eq!(fdt.header().totalsize, totalsize);
+
+    let fdt = checked(&data);
+    assert_eq!(fdt.chosen().unwrap().bootargs(), Some("earlycon"));
+}
+
+#[test]
+fn test_no_space() {
+    let raw = fdt_rpi_4b();
+    let mut data = tight_copy(&raw, 0);
+    let before = data.clone();
+    let mut fdt = FdtMut::from_bytes(&mut data).unwrap();
+    assert_eq!(fdt.free_space(), 0);
+
+    assert!(matches!(
+        fdt.setprop_str("/chosen", "new-prop", "value"),
+        Err(FdtError::NoSpace { .. })
+    ));
+    assert!(matches!(
+        fdt.add_subnode("/", "extra"),
+        Err(FdtError::NoSpace { .. })
+    ));
+    assert_eq!(data, before);
+}
@@ -0,0 +1,262 @@
+//! Regression tests for malformed blobs found by fuzzing.
+//!
+//! Each case used to panic. Parsing must now either fail with an error or
+//! stop iterating early, and formatting must still complete.
+
+use dtb_file::*;
+use fdt_raw::Fdt;
+
+// Header field offsets
+const TOTALSIZE: usize = 4;
+const OFF_DT_STRUCT: usize = 8;
+const OFF_DT_STRINGS: usize = 12;
+const OFF_MEM_RSVMAP: usize = 16;
+const SIZE_DT_STRINGS: usize = 32;
+
+const FDT_BEGIN_NODE: u32 = 1;
+const FDT_END_NODE: u32 = 2;
+const FDT_PROP: u32 = 3;
+const FDT_END: u32 = 9;
+
+fn read(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn write(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+}
+
+/// Offset of the first `FDT_PROP` token in the structure block.
+fn first_prop(data: &[u8]) -> usize {
+    let start = read(data, OFF_DT_STRUCT) as usize;
+    (start..data.len())
+        .step_by(4)
+        .find(|&off| read(data, off) == FDT_PROP)
+        .unwrap()
+}
+
+/// Walks everything reachable from `data`, the way the fuzz target does.
+fn exercise(data: &[u8]) -> usize {
+    let Ok(fdt) = Fdt::from_bytes(data) else {
+        return 0;
+    };
+    let _ = fdt.memory_reservations().count();
+    let mut nodes = 0;
+    for node in fdt.all_nodes() {
+        nodes += 1;
+        for prop in node.properties() {
+            let _ = prop.to_string();
+        }
+        if let Some(reg) = node.reg() {
+            for info in reg {
+                let _ = fdt.translate_address(node.path().as_str(), info.address);
+            }
+        }
+        if let Some(ranges) = node.ranges() {
+            let _ = ranges.iter().count();
+        }
+    }
+    let _ = fdt.to_string();
+    let _ = format!("{fdt:?}");
+    nodes
+}
+
+/// Builds a minimal blob from structure block cells and a strings block.
+fn build(structure: &[u32], strings: &[u8]) -> Vec<u8> {
+    let off_rsvmap = 40;
+    let off_struct = off_rsvmap + 16;
+    let off_strings = off_struct + structure.len() * 4;
+    let totalsize = off_strings + strings.len();
+
+    let mut data = vec![0u8; totalsize];
+    write(&mut data, 0, 0xd00d_feed);
+    write(&mut data, TOTALSIZE, totalsize as u32);
+    write(&mut data, OFF_DT_STRUCT, off_struct as u32);
+    write(&mut data, OFF_DT_STRINGS, off_strings as u32);
+    write(&mut data, OFF_MEM_RSVMAP, off_rsvmap as u32);
+    write(&mut data, 20, 17);
+    write(&mut data, 24, 16);
+    write(&mut data, SIZE_DT_STRINGS, strings.len() as u32);
+    write(&mut data, 36, (structure.len() * 4) as u32);
+    for (i, cell) in structure.iter().enumerate() {
+        write(&mut data, off_struct + i * 4, *cell);
+    }
+    data[off_strings..].copy_from_slice(strings);
+    data
+}
+
+/// `FDT_BEGIN_NODE` followed by the padded node name.
+fn begin(name: &str) -> Vec<u32> {
+    let mut bytes = name.as_bytes().to_vec();
+    bytes.resize(name.len() / 4 * 4 + 4, 0);
+    let mut cells = vec![FDT_BEGIN_NODE];
+    cells.extend(
+        bytes
+            .chunks(4)
+            .map(|c| u32::from_be_bytes(c.try_into().unwrap())),
+    );
+    cells
+}
+
+#[test]
+fn test_nameoff_out_of_bounds() {
+    let mut data = fdt_qemu().to_vec();
+    let prop = first_prop(&data);
+    write(&mut data, prop + 8, 0xffff_fff0);
+    exercise(&data);
+    // The root node is still yielded; its properties stop at the bad name.
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    let root = fdt.all_nodes().next().unwrap();
+    assert_eq!(root.properties().count(), 0);
+}
+
+#[test]
+fn test_property_length_out_of_bounds() {
+    let mut data = fdt_qemu().to_vec();
+    let prop = first_prop(&data);
+    write(&mut data, prop + 4, u32::MAX);
+    // The bad property belongs to the root node, so nothing can be yielded.
+    assert_eq!(exercise(&data), 0);
+}
+
+#[test]
+fn test_struct_offset_out_of_bounds() {
+    for offset in [u32::MAX, read(&fdt_qemu(), TOTALSIZE)] {
+        let mut data = fdt_qemu().to_vec();
+        write(&mut data, OFF_DT_STRUCT, offset);
+        assert_eq!(exercise(&data), 0);
+    }
+}
+
+#[test]
+fn test_strings_block_out_of_bounds() {
+    let mut data = fdt_rpi_4b().to_vec();
+    write(&mut data, SIZE_DT_STRINGS, u32::MAX);
+    // Names inside the blob still resolve
+    let expected = exercise(&fdt_rpi_4b());
+    assert_eq!(exercise(&data), expected);
+
+    let mut data = fdt_rpi_4b().to_vec();
+    write(&mut data, OFF_DT_STRINGS, u32::MAX);
+    exercise(&data);
+}
+
+#[test]
+fn test_rsvmap_without_terminator() {
+    let mut data = fdt_reserve().to_vec();
+    let totalsize = read(&data, TOTALSIZE);
+    for offset in [totalsize - 8, totalsize, u32::MAX] {
+        write(&mut data, OFF_MEM_RSVMAP, offset);
+        let fdt = Fdt::from_bytes(&data).unwrap();
+        assert_eq!(fdt.memory_reservations().count(), 0);
+    }
+}
+
+#[test]
+fn test_truncated_structure_block() {
+    let raw = fdt_phytium();
+    let mut data = raw.to_vec();
+    let cut = read(&data, OFF_DT_STRUCT) as usize + 256;
+    write(&mut data, TOTALSIZE, cut as u32);
+    data.truncate(cut);
+    assert!(exercise(&data) > 0);
+}
+
+#[test]
+fn test_nesting_deeper_than_stack() {
+    let mut structure = Vec::new();
+    structure.extend(begin(""));
+    for _ in 0..40 {
+        structure.extend(begin("n"));
+    }
+    structure.extend([FDT_END_NODE; 41]);
+    structure.push(FDT_END);
+    let data = build(&structure, b"");
+
+    // Iteration stops once the nesting stacks are full
+    let nodes = exercise(&data);
+    assert!(nodes > 1 && nodes < 42);
+}
+
+#[test]
+fn test_zero_cell_ranges() {
+    let strings = b"#address-cells\0#size-cells\0ranges\0";
+    let mut structure = Vec::new();
+    structure.extend(begin(""));
+    structure.extend([FDT_PROP, 4, 0, 0]);
+    structure.extend([FDT_PROP, 4, 15, 0]);
+    structure.extend(begin("bus"));
+    structure.extend([FDT_PROP, 4, 0, 0]);
+    structure.extend([FDT_PROP, 8, 27, 0, 0]);
+    structure.extend([FDT_END_NODE, FDT_END_NODE, FDT_END]);
+    let data = build(&structure, strings);
+
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    let bus = fdt.find_by_path("/bus").unwrap();
+    assert_eq!(bus.ranges().unwrap().iter().count(), 0);
+    exercise(&data);
+}
+
+#[test]
+fn test_translate_overflowing_range() {
+    let strings = b"#address-cells\0#size-cells\0ranges\0reg\0";
+    let mut structure = Vec::new();
+    structure.extend(begin(""));
+    structure.extend([FDT_PROP, 4, 0, 2]);
+    structure.extend([FDT_PROP, 4, 15, 2]);
+    structure.extend(begin("bus"));
+    structure.extend([FDT_PROP, 4, 0, 2]);
+    structure.extend([FDT_PROP, 4, 15, 2]);
+    // The child window ends past u64::MAX
+    structure.extend([FDT_PROP, 48, 27]);
+    structure.extend([u32::MAX, 0xffff_ff00, 0, 0x1000, 0, 0x1000]);
+    // The parent address overflows once the offset is added
+    structure.extend([0, 0, u32::MAX, 0xffff_ff00, 0, 0x1000]);
+    structure.extend(begin("dev"));
+    structure.extend([FDT_PROP, 16, 34, 0, 0x200, 0, 0x10]);
+    structure.extend([FDT_END_NODE, FDT_END_NODE, FDT_END_NODE, FDT_END]);
+    let data = build(&structure, strings);
+
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    assert_eq!(
+        fdt.translate_address("/bus/dev", 0xffff_ffff_ffff_ff10),
+        0x1010
+    );
+    assert_eq!(fdt.translate_address("/bus/dev", 0x200), 0x200);
+    exercise(&data);
+}
+
+#[test]
+fn test_translate_path_deeper_than_buffer() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let path = "/a".repeat(40);
+    assert_eq!(fdt.translate_address(&path, 0x1234), 0x1234);
+}
+
+#[test]
+fn test_memory_total_size_saturates() {
+    let strings = b"#address-cells\0#size-cells\0reg\0";
+    let mut structure = Vec::new();
+    structure.extend(begin(""));
+    structure.extend([FDT_PROP, 4, 0, 1]);
+    structure.extend([FDT_PROP, 4, 15, 2]);
+    structure.extend(begin("memory"));
+    structure.extend([
+        FDT_PROP,
+        24,
+        27,
+        0,
+        u32::MAX,
+        u32::MAX,
+        0x1000,
+        u32::MAX,
+        u32::MAX,
+    ]);
+    structure.extend([FDT_END_NODE, FDT_END_NODE, FDT_END]);
+    let data = build(&structure, strings);
+
+    let fdt = Fdt::from_bytes(&data).unwrap();
+    let memory = fdt.memory().next().unwrap();
+    assert_eq!(memory.total_size(), u64::MAX);
+}
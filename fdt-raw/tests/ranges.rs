@@ -1,7 +1,7 @@
 use std::sync::Once;
 
-use dtb_file::fdt_rpi_4b;
-use fdt_raw::Fdt;
+use dtb_file::{fdt_qemu, fdt_rpi_4b};
+use fdt_raw::{Fdt, TranslateError, translate_ranges};
 
 fn init_logging() {
     static INIT: Once = Once::new();
@@ -93,3 +93,88 @@ fn test_translate_addresses_batch() {
         );
     }
 }
+
+#[test]
+fn test_try_translate() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    let path = "/soc/serial@7e215040";
+    assert_eq!(fdt.try_translate(path, 0x7e215040, 0x40), Ok(0xfe215040));
+    // The /soc window at 0x7e000000 is 0x1800000 bytes long
+    assert_eq!(
+        fdt.try_translate(path, 0x7f7ffff0, 0x20),
+        Err(TranslateError::CrossesWindow {
+            address: 0x7f7ffff0,
+            size: 0x20
+        })
+    );
+    assert_eq!(fdt.try_translate(path, 0x7f7ffff0, 0x10), Ok(0xff7ffff0));
+    assert_eq!(
+        fdt.try_translate(path, 0x1000, 0x10),
+        Err(TranslateError::Unmapped { address: 0x1000 })
+    );
+
+    // /cpus has no `ranges`: CPU ids are not memory mapped
+    assert_eq!(
+        fdt.try_translate("/cpus/cpu@1", 1, 0),
+        Err(TranslateError::NoRanges { address: 1 })
+    );
+    assert_eq!(
+        fdt.try_translate("/soc/missing@0", 0, 0),
+        Err(TranslateError::NodeNotFound)
+    );
+}
+
+#[test]
+fn test_try_translate_empty_ranges() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    // intc@8000000 has an empty `ranges`, an identity mapping
+    let path = "/intc@8000000/v2m@8020000";
+    assert_eq!(fdt.try_translate(path, 0x8020000, 0x1000), Ok(0x8020000));
+    assert_eq!(fdt.translate_address(path, 0x8020000), 0x8020000);
+}
+
+#[test]
+fn test_translate_ranges_pci() {
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    let node = fdt.find_by_path("/pcie@10000000").unwrap();
+    let ranges = node.find_property("ranges").unwrap();
+    let translate = |phys_hi: u32, low: u64, size: u64| {
+        let address = ((phys_hi as u128) << 64) | low as u128;
+        translate_ranges(Some(ranges.as_slice()), 3, 2, 2, address, size)
+    };
+
+    // I/O space at 0x3eff0000, 32-bit memory identity at 0x10000000
+    assert_eq!(translate(0x0100_0000, 0x20, 4), Ok(0x3eff0020));
+    assert_eq!(translate(0x0200_0000, 0x1000_1000, 0x1000), Ok(0x1000_1000));
+    // The space code picks the window, not the low address
+    assert_eq!(
+        translate(0x0200_0000, 0x20, 4),
+        Err(TranslateError::Unmapped {
+            address: (0x0200_0000u128 << 64) | 0x20
+        })
+    );
+    // Prefetchable 64-bit memory still matches the 64-bit window
+    assert_eq!(
+        translate(0x4300_0000, 0x80_0000_1000, 0x1000),
+        Ok(0x80_0000_1000)
+    );
+}
+
+#[test]
+fn test_translate_ranges_missing_or_empty() {
+    assert_eq!(
+        translate_ranges(None, 1, 1, 1, 0x10, 4),
+        Err(TranslateError::NoRanges { address: 0x10 })
+    );
+    assert_eq!(translate_ranges(Some(&[]), 1, 1, 1, 0x10, 4), Ok(0x10));
+    // Identity from a PCI bus to a plain parent drops `phys.hi`
+    assert_eq!(
+        translate_ranges(Some(&[]), 3, 2, 2, (0x0200_0000u128 << 64) | 0x10, 4),
+        Ok(0x10)
+    );
+}
@@ -46,3 +46,51 @@ fn test_rsv2() {
         assert_eq!(node.name(), want_names[i]);
     }
 }
+
+#[test]
+fn test_reserved_memory_regions() {
+    let raw = fdt_rpi_4b();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    let regions: Vec<_> = fdt.reserved_memory_regions().collect();
+    assert_eq!(regions.len(), 3);
+
+    let cma = &regions[0];
+    assert_eq!(cma.name(), "linux,cma");
+    assert!(cma.is_dynamic());
+    assert!(cma.reusable());
+    assert!(!cma.no_map());
+    assert!(cma.is_available());
+    assert_eq!(cma.size(), Some(0x400_0000));
+    assert_eq!(cma.alignment(), None);
+    assert_eq!(cma.regions().count(), 0);
+    assert!(cma.compatibles().any(|c| c == "shared-dma-pool"));
+    let ranges: Vec<_> = cma.alloc_ranges().map(|r| (r.address, r.size)).collect();
+    assert_eq!(ranges, [(0, 0x3000_0000)]);
+
+    let nvram = &regions[1];
+    assert_eq!(nvram.name(), "nvram@0");
+    assert!(!nvram.is_dynamic());
+    assert!(nvram.no_map());
+    assert!(!nvram.is_available());
+    assert_eq!(nvram.regions().count(), 1);
+}
+
+#[test]
+fn test_reserved_memory_regions_static() {
+    let raw = dtb_file::fdt_3568();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+
+    let ramoops = fdt
+        .reserved_memory_regions()
+        .find(|r| r.name() == "ramoops@110000")
+        .unwrap();
+    assert!(!ramoops.is_dynamic());
+    let reg = ramoops.regions().next().unwrap();
+    assert_eq!(reg.address, 0x11_0000);
+
+    // No /reserved-memory at all
+    let raw = fdt_qemu();
+    let fdt = Fdt::from_bytes(&raw).unwrap();
+    assert_eq!(fdt.reserved_memory_regions().count(), 0);
+}
@@ -0,0 +1,162 @@
+use dtb_file::*;
+use fdt_raw::{Fdt, FdtError, ValidationLevel};
+
+// Header field offsets
+const TOTALSIZE: usize = 4;
+const OFF_DT_STRUCT: usize = 8;
+const OFF_DT_STRINGS: usize = 12;
+const OFF_MEM_RSVMAP: usize = 16;
+const VERSION: usize = 20;
+const LAST_COMP_VERSION: usize = 24;
+const SIZE_DT_STRINGS: usize = 32;
+const SIZE_DT_STRUCT: usize = 36;
+
+fn read(data: &[u8], field: usize) -> u32 {
+    u32::from_be_bytes(data[field..field + 4].try_into().unwrap())
+}
+
+fn write(data: &mut [u8], field: usize, value: u32) {
+    data[field..field + 4].copy_from_slice(&value.to_be_bytes());
+}
+
+fn check(data: &[u8]) -> Result<(), FdtError> {
+    Fdt::from_bytes_checked(data, ValidationLevel::Strict).map(|_| ())
+}
+
+#[test]
+fn test_fixtures_pass_strict_validation() {
+    for raw in [
+        fdt_qemu(),
+        fdt_rpi_4b(),
+        fdt_phytium(),
+        fdt_3568(),
+        fdt_orangepi_5plus(),
+        fdt_reserve(),
+    ] {
+        let fdt = Fdt::from_bytes_checked(&raw, ValidationLevel::Strict).unwrap();
+        assert!(fdt.all_nodes().count() > 0);
+    }
+}
+
+#[test]
+fn test_unsupported_version() {
+    let mut data = fdt_qemu().to_vec();
+    write(&mut data, VERSION, 3);
+    write(&mut data, LAST_COMP_VERSION, 2);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::UnsupportedVersion {
+            version: 3,
+            last_comp_version: 2
+        })
+    ));
+
+    let mut data = fdt_qemu().to_vec();
+    write(&mut data, LAST_COMP_VERSION, 18);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::UnsupportedVersion { .. })
+    ));
+
+    // `Basic` keeps the permissive behaviour of `from_bytes`.
+    assert!(Fdt::from_bytes_checked(&data, ValidationLevel::Basic).is_ok());
+}
+
+#[test]
+fn test_misaligned_block() {
+    let mut data = fdt_qemu().to_vec();
+    let off = read(&data, OFF_DT_STRUCT);
+    write(&mut data, OFF_DT_STRUCT, off + 2);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::MisalignedBlock {
+            block: "structure",
+            ..
+        })
+    ));
+
+    let mut data = fdt_qemu().to_vec();
+    let off = read(&data, OFF_MEM_RSVMAP);
+    write(&mut data, OFF_MEM_RSVMAP, off + 4);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::MisalignedBlock {
+            block: "memory reservation",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_block_out_of_bounds() {
+    let mut data = fdt_qemu().to_vec();
+    let size = read(&data, SIZE_DT_STRINGS);
+    write(&mut data, SIZE_DT_STRINGS, size + 0x10_0000);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::BlockOutOfBounds {
+            block: "strings",
+            ..
+        })
+    ));
+
+    let mut data = fdt_qemu().to_vec();
+    write(&mut data, SIZE_DT_STRUCT, u32::MAX);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::BlockOutOfBounds {
+            block: "structure",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_blocks_overlap() {
+    let mut data = fdt_qemu().to_vec();
+    let off = read(&data, OFF_DT_STRUCT);
+    write(&mut data, OFF_DT_STRINGS, off + 4);
+    write(&mut data, SIZE_DT_STRINGS, 4);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::BlocksOverlap {
+            first: "structure",
+            second: "strings"
+        })
+    ));
+
+    let mut data = fdt_qemu().to_vec();
+    write(&mut data, OFF_MEM_RSVMAP, 0x20);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::BlocksOverlap {
+            first: "header",
+            second: "memory reservation"
+        })
+    ));
+}
+
+#[test]
+fn test_missing_rsvmap_terminator() {
+    let mut data = fdt_reserve().to_vec();
+    let totalsize = read(&data, TOTALSIZE) as usize;
+    // Point the reservation map at a non-zero entry at the very end of the blob.
+    let off = (totalsize - 16) & !7;
+    data[off..off + 16].fill(0xff);
+    write(&mut data, OFF_MEM_RSVMAP, off as u32);
+    assert!(matches!(
+        check(&data),
+        Err(FdtError::MissingRsvmapTerminator)
+    ));
+}
+
+#[test]
+fn test_missing_end_token() {
+    let mut data = fdt_qemu().to_vec();
+    let size = read(&data, SIZE_DT_STRUCT);
+    write(&mut data, SIZE_DT_STRUCT, size - 4);
+    assert!(matches!(check(&data), Err(FdtError::MissingEndToken)));
+
+    // The header level does not look at the structure block contents.
+    assert!(Fdt::from_bytes_checked(&data, ValidationLevel::Header).is_ok());
+}
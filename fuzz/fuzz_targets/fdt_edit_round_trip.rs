@@ -0,0 +1,22 @@
+//! Loads arbitrary input with `fdt-edit`, encodes it and parses the result.
+//!
+//! Whatever `from_bytes` accepts must encode into a blob that parses again
+//! into the same number of nodes.
+
+#![no_main]
+
+use fdt_edit::Fdt;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(fdt) = Fdt::from_bytes(data) else {
+        return;
+    };
+
+    let encoded = fdt.encode();
+    let reparsed = Fdt::from_bytes(&encoded).expect("encoded blob must parse");
+    assert_eq!(
+        fdt.iter_node_ids().count(),
+        reparsed.iter_node_ids().count()
+    );
+});
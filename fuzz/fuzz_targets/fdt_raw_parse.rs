@@ -0,0 +1,51 @@
+//! Parses arbitrary input with `fdt-raw` and walks everything reachable.
+//!
+//! Any panic is a bug: malformed blobs must surface as errors or end
+//! iteration early.
+
+#![no_main]
+
+use fdt_raw::{Fdt, Node};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(fdt) = Fdt::from_bytes(data) else {
+        return;
+    };
+
+    for rsv in fdt.memory_reservations() {
+        let _ = (rsv.address, rsv.size);
+    }
+
+    for node in fdt.all_nodes() {
+        let _ = node.path();
+        let _ = node.interrupt_parent();
+        let _ = node.compatibles().count();
+        for prop in node.properties() {
+            let _ = prop.to_string();
+            let _ = prop.as_u32_iter().count();
+            let _ = prop.as_str_iter().count();
+        }
+        if let Some(reg) = node.reg() {
+            for info in reg {
+                let _ = fdt.translate_address(node.path().as_str(), info.address);
+            }
+        }
+        if let Some(ranges) = node.ranges() {
+            let _ = ranges.iter().count();
+        }
+        match &node {
+            Node::Chosen(chosen) => {
+                let _ = chosen.bootargs();
+                let _ = chosen.stdout();
+            }
+            Node::Memory(memory) => {
+                let _ = memory.total_size();
+            }
+            Node::General(_) => {}
+        }
+    }
+
+    let _ = fdt.to_string();
+    let _ = format!("{fdt:?}");
+});
@@ -1,4 +1,4 @@
-dtc -O dts -I dtb -o target/phytium.dts dtb-file/src/dtb/phytium.dtb
-dtc -O dts -I dtb -o target/bcm2711-rpi-4-b.dts dtb-file/src/dtb/bcm2711-rpi-4-b.dtb
-dtc -O dts -I dtb -o target/rk3568-firefly-roc-pc-se.dts dtb-file/src/dtb/rk3568-firefly-roc-pc-se.dtb
-dtc -O dts -I dtb -o target/orangepi5plus.dts dtb-file/src/dtb/orangepi5plus.dtb
\ No newline at end of file
+cargo build -q -p fdt-cli
+for dtb in phytium bcm2711-rpi-4-b rk3568-firefly-roc-pc-se orangepi5plus; do
+    target/debug/fdt convert dtb-file/src/dtb/$dtb.dtb target/$dtb.dts
+done